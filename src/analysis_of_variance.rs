@@ -0,0 +1,374 @@
+use super::distributions::{get_f_ppf, get_f_sf, get_studentized_range_ppf, get_studentized_range_sf, get_t_ppf, get_t_sf};
+use super::linear_algebra::{get_least_squares, get_matrix_vector_product};
+use super::utilities::{check_vector_for_nans, get_mean, get_sst, get_variance};
+use ndarray::{Array1, Array2};
+use prettytable::{Table, row, cell};
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SumOfSquaresType
+{
+    // Sequential
+    TypeI,
+    // Each main effect adjusted for the other, ignoring the interaction
+    TypeII,
+    // Each effect adjusted for all others, with sum-to-zero coding
+    TypeIII,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct AnovaRow
+{
+    pub source: String,
+    pub degrees_of_freedom: f64,
+    pub sum_of_squares: f64,
+    pub mean_square: f64,
+    pub f_statistic: Option<f64>,
+    pub p_value: Option<f64>,
+    pub eta_squared: Option<f64>,
+    pub omega_squared: Option<f64>,
+}
+
+#[allow(unused)]
+#[derive(Debug)]
+pub struct AnovaTable
+{
+    pub rows: Vec<AnovaRow>,
+    pub total_sum_of_squares: f64,
+}
+
+#[allow(unused)]
+impl AnovaTable
+{
+    fn new(effects: Vec<(String, f64, f64)>, residual_degrees_of_freedom: f64, residual_sum_of_squares: f64, total_sum_of_squares: f64)
+    -> Result<Self, String>
+    {
+        if residual_degrees_of_freedom <= 0.0
+        {
+            return Err(String::from("No degrees of freedom left for the residuals!"));
+        }
+        let residual_mean_square = residual_sum_of_squares / residual_degrees_of_freedom;
+        let mut rows = Vec::with_capacity(effects.len() + 1);
+        for (source, degrees_of_freedom, sum_of_squares) in effects
+        {
+            let mean_square = sum_of_squares / degrees_of_freedom;
+            let f_statistic = mean_square / residual_mean_square;
+            rows.push
+            (
+                AnovaRow
+                {
+                    source,
+                    degrees_of_freedom,
+                    sum_of_squares,
+                    mean_square,
+                    f_statistic: Some(f_statistic),
+                    p_value: Some(get_f_sf(f_statistic, degrees_of_freedom, residual_degrees_of_freedom)?),
+                    eta_squared: Some(sum_of_squares / total_sum_of_squares),
+                    omega_squared: Some((sum_of_squares - degrees_of_freedom * residual_mean_square) / (total_sum_of_squares + residual_mean_square)),
+                }
+            );
+        }
+        rows.push
+        (
+            AnovaRow
+            {
+                source: String::from("Residual"),
+                degrees_of_freedom: residual_degrees_of_freedom,
+                sum_of_squares: residual_sum_of_squares,
+                mean_square: residual_mean_square,
+                f_statistic: None,
+                p_value: None,
+                eta_squared: None,
+                omega_squared: None,
+            }
+        );
+        Ok(AnovaTable { rows, total_sum_of_squares })
+    }
+
+    pub fn get_row(&self, source: &str) -> Option<&AnovaRow>
+    {
+        self.rows.iter().find(|row| row.source == source)
+    }
+
+    pub fn print(&self)
+    {
+        let format_option = |value: Option<f64>| value.map(|v| format!("{:.4}", v)).unwrap_or_default();
+        let mut table = Table::new();
+        table.add_row(row!["Source", "df", "SS", "MS", "F", "p", "Eta²", "Omega²"]);
+        for anova_row in &self.rows
+        {
+            table.add_row(row![anova_row.source,
+                               anova_row.degrees_of_freedom,
+                               format!("{:.4}", anova_row.sum_of_squares),
+                               format!("{:.4}", anova_row.mean_square),
+                               format_option(anova_row.f_statistic),
+                               format_option(anova_row.p_value),
+                               format_option(anova_row.eta_squared),
+                               format_option(anova_row.omega_squared)]);
+        }
+        table.printstd();
+    }
+}
+
+#[allow(unused)]
+pub fn get_one_way_anova(groups: &[Array1<f64>]) -> Result<AnovaTable, String>
+{
+    check_groups(groups)?;
+    let all_observations = get_concatenated_groups(groups);
+    let grand_mean = get_mean(&all_observations)?;
+    let mut between_sum_of_squares = 0.0;
+    for group in groups
+    {
+        between_sum_of_squares += group.len() as f64 * (get_mean(group)? - grand_mean).powi(2);
+    }
+    let total_sum_of_squares = get_sst(&all_observations)?;
+    let residual_sum_of_squares = total_sum_of_squares - between_sum_of_squares;
+
+    AnovaTable::new(vec![(String::from("Between groups"), (groups.len() - 1) as f64, between_sum_of_squares)],
+                    (all_observations.len() - groups.len()) as f64,
+                    residual_sum_of_squares,
+                    total_sum_of_squares)
+}
+
+// Factor columns hold level codes, every combination of levels needs at least one observation
+#[allow(unused)]
+pub fn get_two_way_anova(factor_a: &Array1<f64>,
+                         factor_b: &Array1<f64>,
+                         observations: &Array1<f64>,
+                         sum_of_squares_type: SumOfSquaresType) -> Result<AnovaTable, String>
+{
+    let observations = check_vector_for_nans(observations)?;
+    if factor_a.len() != observations.len() || factor_b.len() != observations.len()
+    {
+        return Err(String::from("Factors and observations must have the same length!"));
+    }
+    let a_columns = get_effect_coding(factor_a)?;
+    let b_columns = get_effect_coding(factor_b)?;
+    let ab_columns = get_interaction_columns(&a_columns, &b_columns);
+    let (df_a, df_b, df_ab) = (a_columns.ncols() as f64, b_columns.ncols() as f64, ab_columns.ncols() as f64);
+    if df_a == 0.0 || df_b == 0.0
+    {
+        return Err(String::from("Each factor needs at least two levels!"));
+    }
+
+    let rss = |blocks: &[&Array2<f64>]| -> Result<f64, String>
+    {
+        get_residual_sum_of_squares(blocks, observations)
+    };
+    let empty = Array2::<f64>::zeros((observations.len(), 0));
+    let full_rss = rss(&[&a_columns, &b_columns, &ab_columns])
+        .map_err(|_| String::from("Every combination of factor levels needs at least one observation!"))?;
+
+    let (ss_a, ss_b, ss_ab) = match sum_of_squares_type
+    {
+        SumOfSquaresType::TypeI =>
+        {
+            let null_rss = rss(&[&empty])?;
+            let a_rss = rss(&[&a_columns])?;
+            let additive_rss = rss(&[&a_columns, &b_columns])?;
+            (null_rss - a_rss, a_rss - additive_rss, additive_rss - full_rss)
+        },
+        SumOfSquaresType::TypeII =>
+        {
+            let a_rss = rss(&[&a_columns])?;
+            let b_rss = rss(&[&b_columns])?;
+            let additive_rss = rss(&[&a_columns, &b_columns])?;
+            (b_rss - additive_rss, a_rss - additive_rss, additive_rss - full_rss)
+        },
+        SumOfSquaresType::TypeIII =>
+        {
+            (rss(&[&b_columns, &ab_columns])? - full_rss,
+             rss(&[&a_columns, &ab_columns])? - full_rss,
+             rss(&[&a_columns, &b_columns])? - full_rss)
+        },
+    };
+
+    let residual_degrees_of_freedom = observations.len() as f64 - 1.0 - df_a - df_b - df_ab;
+    AnovaTable::new(vec![(String::from("A"), df_a, ss_a), (String::from("B"), df_b, ss_b), (String::from("A:B"), df_ab, ss_ab)],
+                    residual_degrees_of_freedom,
+                    full_rss,
+                    get_sst(observations)?)
+}
+
+#[allow(unused)]
+pub fn get_factor_levels(factor: &Array1<f64>) -> Result<Vec<f64>, String>
+{
+    let factor = check_vector_for_nans(factor)?;
+    let mut levels = factor.to_vec();
+    levels.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    levels.dedup();
+    Ok(levels)
+}
+
+// Sum-to-zero coding, the last level is coded as -1 in every column
+fn get_effect_coding(factor: &Array1<f64>) -> Result<Array2<f64>, String>
+{
+    let levels = get_factor_levels(factor)?;
+    let num_columns = levels.len().saturating_sub(1);
+    let mut columns = Array2::<f64>::zeros((factor.len(), num_columns));
+    for (row, value) in factor.iter().enumerate()
+    {
+        let level = levels.iter().position(|level| level == value).unwrap();
+        for column in 0..num_columns
+        {
+            columns[[row, column]] = if level == column { 1.0 } else if level == num_columns { -1.0 } else { 0.0 };
+        }
+    }
+    Ok(columns)
+}
+
+fn get_interaction_columns(a_columns: &Array2<f64>, b_columns: &Array2<f64>) -> Array2<f64>
+{
+    let mut columns = Array2::<f64>::zeros((a_columns.nrows(), a_columns.ncols() * b_columns.ncols()));
+    for i in 0..a_columns.ncols()
+    {
+        for j in 0..b_columns.ncols()
+        {
+            columns.column_mut(i * b_columns.ncols() + j).assign(&(&a_columns.column(i) * &b_columns.column(j)));
+        }
+    }
+    columns
+}
+
+fn get_residual_sum_of_squares(blocks: &[&Array2<f64>], observations: &Array1<f64>) -> Result<f64, String>
+{
+    let num_columns = 1 + blocks.iter().map(|block| block.ncols()).sum::<usize>();
+    let mut design_matrix = Array2::<f64>::ones((observations.len(), num_columns));
+    let mut offset = 1;
+    for block in blocks
+    {
+        design_matrix.slice_mut(ndarray::s![.., offset..offset + block.ncols()]).assign(block);
+        offset += block.ncols();
+    }
+    let coefficients = get_least_squares(&design_matrix, observations)?;
+    let fitted_values = get_matrix_vector_product(&design_matrix, &coefficients)?;
+    Ok((observations - &fitted_values).mapv(|r| r * r).sum())
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostHocMethod
+{
+    TukeyHsd,
+    Bonferroni,
+    Scheffe,
+    GamesHowell,
+}
+
+#[allow(unused)]
+#[derive(Debug)]
+pub struct PairwiseComparison
+{
+    pub group_1: usize,
+    pub group_2: usize,
+    pub mean_difference: f64,
+    pub standard_error: f64,
+    pub p_value: f64,
+    pub confidence_interval: (f64, f64),
+    pub reject: bool,
+}
+
+#[allow(unused)]
+pub fn get_post_hoc_comparisons(groups: &[Array1<f64>], method: PostHocMethod, alpha_level: f64) -> Result<Vec<PairwiseComparison>, String>
+{
+    check_groups(groups)?;
+    if alpha_level <= 0.0 || alpha_level >= 1.0
+    {
+        return Err(String::from("Alpha level must be between 0 and 1!"));
+    }
+    let num_groups = groups.len();
+    let total_observations: usize = groups.iter().map(|group| group.len()).sum();
+    let residual_degrees_of_freedom = (total_observations - num_groups) as f64;
+    let residual_mean_square = get_one_way_anova(groups)?.rows.last().unwrap().mean_square;
+    let num_comparisons = (num_groups * (num_groups - 1) / 2) as f64;
+
+    let mut comparisons = Vec::new();
+    for i in 0..num_groups
+    {
+        for j in i + 1..num_groups
+        {
+            let (n_i, n_j) = (groups[i].len() as f64, groups[j].len() as f64);
+            let mean_difference = get_mean(&groups[i])? - get_mean(&groups[j])?;
+            let pooled_standard_error = (residual_mean_square * (1.0 / n_i + 1.0 / n_j)).sqrt();
+
+            let (standard_error, p_value, margin) = match method
+            {
+                PostHocMethod::TukeyHsd =>
+                {
+                    let q = mean_difference.abs() / pooled_standard_error * 2.0f64.sqrt();
+                    let q_critical = get_studentized_range_ppf(1.0 - alpha_level, num_groups, residual_degrees_of_freedom)?;
+                    (pooled_standard_error,
+                     get_studentized_range_sf(q, num_groups, residual_degrees_of_freedom)?,
+                     q_critical / 2.0f64.sqrt() * pooled_standard_error)
+                },
+                PostHocMethod::Bonferroni =>
+                {
+                    let t = mean_difference.abs() / pooled_standard_error;
+                    let t_critical = get_t_ppf(1.0 - alpha_level / (2.0 * num_comparisons), residual_degrees_of_freedom)?;
+                    (pooled_standard_error,
+                     (2.0 * num_comparisons * get_t_sf(t, residual_degrees_of_freedom)?).min(1.0),
+                     t_critical * pooled_standard_error)
+                },
+                PostHocMethod::Scheffe =>
+                {
+                    let numerator_degrees_of_freedom = (num_groups - 1) as f64;
+                    let f = (mean_difference / pooled_standard_error).powi(2) / numerator_degrees_of_freedom;
+                    let f_critical = get_f_ppf(1.0 - alpha_level, numerator_degrees_of_freedom, residual_degrees_of_freedom)?;
+                    (pooled_standard_error,
+                     get_f_sf(f, numerator_degrees_of_freedom, residual_degrees_of_freedom)?,
+                     (numerator_degrees_of_freedom * f_critical).sqrt() * pooled_standard_error)
+                },
+                PostHocMethod::GamesHowell =>
+                {
+                    // Unequal variances with Welch-Satterthwaite degrees of freedom
+                    let (v_i, v_j) = (get_variance(&groups[i])? / n_i, get_variance(&groups[j])? / n_j);
+                    let standard_error = (v_i + v_j).sqrt();
+                    let degrees_of_freedom = (v_i + v_j).powi(2) / (v_i.powi(2) / (n_i - 1.0) + v_j.powi(2) / (n_j - 1.0));
+                    let q = mean_difference.abs() / standard_error * 2.0f64.sqrt();
+                    let q_critical = get_studentized_range_ppf(1.0 - alpha_level, num_groups, degrees_of_freedom)?;
+                    (standard_error,
+                     get_studentized_range_sf(q, num_groups, degrees_of_freedom)?,
+                     q_critical / 2.0f64.sqrt() * standard_error)
+                },
+            };
+
+            comparisons.push
+            (
+                PairwiseComparison
+                {
+                    group_1: i,
+                    group_2: j,
+                    mean_difference,
+                    standard_error,
+                    p_value,
+                    confidence_interval: (mean_difference - margin, mean_difference + margin),
+                    reject: p_value < alpha_level,
+                }
+            );
+        }
+    }
+    Ok(comparisons)
+}
+
+fn check_groups(groups: &[Array1<f64>]) -> Result<(), String>
+{
+    if groups.len() < 2
+    {
+        return Err(String::from("Need at least two groups!"));
+    }
+    for group in groups
+    {
+        check_vector_for_nans(group)?;
+        if group.len() < 2
+        {
+            return Err(String::from("Every group needs at least two observations!"));
+        }
+    }
+    Ok(())
+}
+
+fn get_concatenated_groups(groups: &[Array1<f64>]) -> Array1<f64>
+{
+    groups.iter().flat_map(|group| group.iter().cloned()).collect()
+}
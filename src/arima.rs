@@ -0,0 +1,631 @@
+use super::hypothesis_testing::{HypothesisTest, TestResult};
+use super::linear_algebra::get_inverse;
+use super::normality_tests::JarqueBera;
+use super::optimization::get_nelder_mead_minimum;
+use super::stationarity_tests::{get_differencing_order, StationarityTest};
+use super::time_series::{Correlogram, Forecast, InformationCriterion, TimeSeries};
+use ndarray::{Array1, Array2};
+
+const HESSIAN_STEP: f64 = 1e-4;
+// auto.arima in R's forecast package rejects models with roots inside a circle of this radius
+const ROOT_MARGIN: f64 = 1.01;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArimaMethod
+{
+    // Residuals are computed conditional on the first observations being fixed
+    ConditionalSumOfSquares,
+    // Exact Gaussian likelihood from the Kalman filter, started from the CSS estimates
+    MaximumLikelihood,
+}
+
+// (1 - phi(B)) (1 - Phi(B^s)) (1 - B)^d (1 - B^s)^D (y_t - mean) = (1 + theta(B)) (1 + Theta(B^s)) e_t
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct Arima
+{
+    order: (usize, usize, usize),
+    seasonal_order: (usize, usize, usize),
+    period: usize,
+    include_mean: bool,
+    method: ArimaMethod,
+}
+
+// Coefficients are ordered ar, ma, seasonal ar, seasonal ma and mean, as named in coefficient_names.
+// Residuals belong to the last residuals.len() observations
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct ArimaFit
+{
+    pub coefficients: Array1<f64>,
+    pub coefficient_names: Vec<String>,
+    pub standard_errors: Array1<f64>,
+    pub sigma_squared: f64,
+    pub log_likelihood: f64,
+    pub aic: f64,
+    pub bic: f64,
+    pub residuals: Array1<f64>,
+    model: Arima,
+    values: Array1<f64>,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct ResidualDiagnostics
+{
+    pub ljung_box: TestResult,
+    pub jarque_bera: TestResult,
+    pub acf: Correlogram,
+}
+
+// Searches the orders by an information criterion after choosing d with a stationarity test
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct AutoArima
+{
+    max_order: (usize, usize, usize),
+    max_seasonal_order: (usize, usize),
+    seasonal_differences: usize,
+    period: usize,
+    test: StationarityTest,
+    alpha_level: f64,
+    criterion: InformationCriterion,
+    method: ArimaMethod,
+}
+
+struct Likelihood
+{
+    log_likelihood: f64,
+    sigma_squared: f64,
+    residuals: Vec<f64>,
+    num_observations: usize,
+}
+
+#[allow(unused)]
+impl Arima
+{
+    // Includes a mean only for undifferenced models
+    pub fn new(p: usize, d: usize, q: usize, method: ArimaMethod) -> Self
+    {
+        Arima { order: (p, d, q), seasonal_order: (0, 0, 0), period: 1, include_mean: d == 0, method }
+    }
+
+    pub fn with_seasonal_order(mut self, p: usize, d: usize, q: usize, period: usize) -> Result<Self, String>
+    {
+        if period < 2
+        {
+            return Err(String::from("Seasonal period must be at least 2!"));
+        }
+        self.seasonal_order = (p, d, q);
+        self.period = period;
+        self.include_mean = self.include_mean && d == 0;
+        Ok(self)
+    }
+
+    pub fn with_mean(mut self, include_mean: bool) -> Result<Self, String>
+    {
+        if include_mean && self.order.1 + self.seasonal_order.1 > 0
+        {
+            return Err(String::from("Differenced models cannot include a mean!"));
+        }
+        self.include_mean = include_mean;
+        Ok(self)
+    }
+
+    pub fn fit(&self, series: &TimeSeries) -> Result<ArimaFit, String>
+    {
+        // The search runs on the standardized differences, the ARMA coefficients do not depend on the scale
+        let differenced = self.get_differenced_values(series)?;
+        let location = if self.include_mean { differenced.mean().unwrap() } else { 0.0 };
+        let scale = (differenced.mapv(|value| (value - location).powi(2)).mean().unwrap()).sqrt();
+        if scale == 0.0
+        {
+            return Err(String::from("Differenced series is constant!"));
+        }
+        let standardized = differenced.mapv(|value| (value - location) / scale);
+
+        let mut coefficients = Array1::<f64>::zeros(self.get_num_coefficients());
+        if !coefficients.is_empty()
+        {
+            let objective = |point: &Array1<f64>| self.get_negative_log_likelihood(&standardized, point, ArimaMethod::ConditionalSumOfSquares);
+            coefficients = get_coefficients_minimum(objective, &coefficients)?;
+            if self.method == ArimaMethod::MaximumLikelihood
+            {
+                // Restart from zero if the CSS estimates are not stationary and invertible
+                if !self.is_stationary_and_invertible(&coefficients)
+                {
+                    coefficients.iter_mut().take(self.get_num_arma_coefficients()).for_each(|coefficient| *coefficient = 0.0);
+                }
+                let objective = |point: &Array1<f64>| self.get_negative_log_likelihood(&standardized, point, ArimaMethod::MaximumLikelihood);
+                coefficients = get_coefficients_minimum(objective, &coefficients)?;
+            }
+        }
+        if self.include_mean
+        {
+            let last = coefficients.len() - 1;
+            coefficients[last] = location + scale * coefficients[last];
+        }
+        self.fit_with_coefficients(series, &coefficients)
+    }
+
+    // Evaluates the model at given coefficients, standard errors come from the numerical Hessian there
+    pub fn fit_with_coefficients(&self, series: &TimeSeries, coefficients: &Array1<f64>) -> Result<ArimaFit, String>
+    {
+        if coefficients.len() != self.get_num_coefficients()
+        {
+            return Err(format!("Model needs {} coefficients!", self.get_num_coefficients()));
+        }
+        let differenced = self.get_differenced_values(series)?;
+        let likelihood = self.get_likelihood(&differenced, coefficients, self.method)?;
+        let standard_errors = self.get_standard_errors(&differenced, coefficients)?;
+
+        let num_parameters = (coefficients.len() + 1) as f64;
+        let log_likelihood = likelihood.log_likelihood;
+        Ok(ArimaFit
+        {
+            coefficients: coefficients.clone(),
+            coefficient_names: self.get_coefficient_names(),
+            standard_errors,
+            sigma_squared: likelihood.sigma_squared,
+            log_likelihood,
+            aic: -2.0 * log_likelihood + 2.0 * num_parameters,
+            bic: -2.0 * log_likelihood + (likelihood.num_observations as f64).ln() * num_parameters,
+            residuals: Array1::from(likelihood.residuals),
+            model: *self,
+            values: series.get_values().clone(),
+        })
+    }
+
+    fn get_differenced_values(&self, series: &TimeSeries) -> Result<Array1<f64>, String>
+    {
+        let mut differenced = series.clone();
+        if self.seasonal_order.1 > 0
+        {
+            differenced = differenced.get_difference(self.period, self.seasonal_order.1)?;
+        }
+        if self.order.1 > 0
+        {
+            differenced = differenced.get_difference(1, self.order.1)?;
+        }
+        let minimum = self.get_num_coefficients() + self.get_ar_polynomial(&Array1::zeros(self.get_num_coefficients())).len() + 2;
+        if differenced.len() < minimum
+        {
+            return Err(String::from("Series is too short for this model!"));
+        }
+        Ok(differenced.get_values().clone())
+    }
+
+    fn get_num_arma_coefficients(&self) -> usize
+    {
+        self.order.0 + self.order.2 + self.seasonal_order.0 + self.seasonal_order.2
+    }
+
+    fn get_num_coefficients(&self) -> usize
+    {
+        self.get_num_arma_coefficients() + self.include_mean as usize
+    }
+
+    fn get_coefficient_names(&self) -> Vec<String>
+    {
+        let mut names: Vec<String> = (1..=self.order.0).map(|i| format!("ar{}", i)).collect();
+        names.extend((1..=self.order.2).map(|i| format!("ma{}", i)));
+        names.extend((1..=self.seasonal_order.0).map(|i| format!("sar{}", i)));
+        names.extend((1..=self.seasonal_order.2).map(|i| format!("sma{}", i)));
+        if self.include_mean
+        {
+            names.push(String::from("mean"));
+        }
+        names
+    }
+
+    // Coefficients a_1, a_2, ... of x_t = a_1 x_{t-1} + a_2 x_{t-2} + ... after multiplying out the seasonal part
+    fn get_ar_polynomial(&self, coefficients: &Array1<f64>) -> Vec<f64>
+    {
+        let (p, q, seasonal_p) = (self.order.0, self.order.2, self.seasonal_order.0);
+        let nonseasonal: Vec<f64> = coefficients.iter().take(p).map(|&phi| -phi).collect();
+        let seasonal = self.get_seasonal_polynomial(coefficients.iter().skip(p + q).take(seasonal_p).map(|&phi| -phi).collect());
+        get_polynomial_product(&nonseasonal, &seasonal).iter().skip(1).map(|&c| -c).collect()
+    }
+
+    // Coefficients b_1, b_2, ... of e_t + b_1 e_{t-1} + b_2 e_{t-2} + ...
+    fn get_ma_polynomial(&self, coefficients: &Array1<f64>) -> Vec<f64>
+    {
+        let (p, q, seasonal_p, seasonal_q) = (self.order.0, self.order.2, self.seasonal_order.0, self.seasonal_order.2);
+        let nonseasonal: Vec<f64> = coefficients.iter().skip(p).take(q).cloned().collect();
+        let seasonal = self.get_seasonal_polynomial(coefficients.iter().skip(p + q + seasonal_p).take(seasonal_q).cloned().collect());
+        get_polynomial_product(&nonseasonal, &seasonal).iter().skip(1).cloned().collect()
+    }
+
+    // Places the coefficients of B^s, B^2s, ... into a polynomial in B without its leading one
+    fn get_seasonal_polynomial(&self, coefficients: Vec<f64>) -> Vec<f64>
+    {
+        let mut polynomial = vec![0.0; coefficients.len() * self.period];
+        for (i, coefficient) in coefficients.into_iter().enumerate()
+        {
+            polynomial[(i + 1) * self.period - 1] = coefficient;
+        }
+        polynomial
+    }
+
+    fn get_mean(&self, coefficients: &Array1<f64>) -> f64
+    {
+        if self.include_mean { coefficients[coefficients.len() - 1] } else { 0.0 }
+    }
+
+    fn is_stationary_and_invertible(&self, coefficients: &Array1<f64>) -> bool
+    {
+        let ma: Vec<f64> = self.get_ma_polynomial(coefficients).iter().map(|&b| -b).collect();
+        is_stationary(&self.get_ar_polynomial(coefficients)) && is_stationary(&ma)
+    }
+
+    fn get_negative_log_likelihood(&self, differenced: &Array1<f64>, coefficients: &Array1<f64>, method: ArimaMethod) -> f64
+    {
+        match self.get_likelihood(differenced, coefficients, method)
+        {
+            Ok(likelihood) if likelihood.log_likelihood.is_finite() => -likelihood.log_likelihood,
+            _ => f64::INFINITY,
+        }
+    }
+
+    fn get_likelihood(&self, differenced: &Array1<f64>, coefficients: &Array1<f64>, method: ArimaMethod) -> Result<Likelihood, String>
+    {
+        let ar = self.get_ar_polynomial(coefficients);
+        let ma = self.get_ma_polynomial(coefficients);
+        let mean = self.get_mean(coefficients);
+        let centered: Vec<f64> = differenced.iter().map(|&value| value - mean).collect();
+        match method
+        {
+            ArimaMethod::ConditionalSumOfSquares => Ok(get_css_likelihood(&centered, &ar, &ma)),
+            ArimaMethod::MaximumLikelihood =>
+            {
+                if !self.is_stationary_and_invertible(coefficients)
+                {
+                    return Err(String::from("Model is not stationary and invertible!"));
+                }
+                get_exact_likelihood(&centered, &ar, &ma)
+            },
+        }
+    }
+
+    // Inverse of the central difference Hessian of the negative log-likelihood
+    fn get_standard_errors(&self, differenced: &Array1<f64>, coefficients: &Array1<f64>) -> Result<Array1<f64>, String>
+    {
+        let k = coefficients.len();
+        if k == 0
+        {
+            return Ok(Array1::zeros(0));
+        }
+        let f = |point: &Array1<f64>| self.get_negative_log_likelihood(differenced, point, self.method);
+        let steps: Vec<f64> = coefficients.iter().map(|&c| HESSIAN_STEP * c.abs().max(0.01)).collect();
+        let mut hessian = Array2::<f64>::zeros((k, k));
+        for i in 0..k
+        {
+            for j in i..k
+            {
+                let shifted = |di: f64, dj: f64|
+                {
+                    let mut point = coefficients.clone();
+                    point[i] += di * steps[i];
+                    point[j] += dj * steps[j];
+                    f(&point)
+                };
+                let value = (shifted(1.0, 1.0) - shifted(1.0, -1.0) - shifted(-1.0, 1.0) + shifted(-1.0, -1.0)) / (4.0 * steps[i] * steps[j]);
+                hessian[[i, j]] = value;
+                hessian[[j, i]] = value;
+            }
+        }
+        // Undefined at the boundary of the admissible region, the fit itself stays usable
+        if hessian.iter().any(|value| !value.is_finite())
+        {
+            return Ok(Array1::from_elem(k, f64::NAN));
+        }
+        let covariance = match get_inverse(&hessian)
+        {
+            Ok(covariance) => covariance,
+            Err(_) => return Ok(Array1::from_elem(k, f64::NAN)),
+        };
+        Ok(covariance.diag().mapv(|variance| if variance > 0.0 { variance.sqrt() } else { f64::NAN }))
+    }
+}
+
+#[allow(unused)]
+impl ArimaFit
+{
+    // Point forecasts from the recursion of the full model including the differences,
+    // variances sigma^2 (psi_0^2 + ... + psi_{h-1}^2) from its psi-weights
+    pub fn get_forecast(&self, horizon: usize, confidence_level: f64) -> Result<Forecast, String>
+    {
+        if horizon == 0
+        {
+            return Err(String::from("Horizon must be at least 1!"));
+        }
+        let mut differencing: Vec<f64> = Vec::new();
+        for _ in 0..self.model.order.1
+        {
+            differencing = get_polynomial_product(&differencing, &[-1.0]);
+            differencing.remove(0);
+        }
+        for _ in 0..self.model.seasonal_order.1
+        {
+            differencing = get_polynomial_product(&differencing, &self.model.get_seasonal_polynomial(vec![-1.0]));
+            differencing.remove(0);
+        }
+        let arma_ar: Vec<f64> = self.model.get_ar_polynomial(&self.coefficients).iter().map(|&a| -a).collect();
+        let ar: Vec<f64> = get_polynomial_product(&arma_ar, &differencing).iter().skip(1).map(|&c| -c).collect();
+        let ma = self.model.get_ma_polynomial(&self.coefficients);
+        let mean = self.model.get_mean(&self.coefficients);
+
+        let n = self.values.len();
+        let mut values: Vec<f64> = self.values.iter().map(|&value| value - mean).collect();
+        let mut errors = vec![0.0; n - self.residuals.len()];
+        errors.extend(self.residuals.iter());
+        for t in n..n + horizon
+        {
+            let prediction = ar.iter().enumerate().filter(|&(i, _)| i < t).map(|(i, a)| a * values[t - 1 - i]).sum::<f64>()
+                + ma.iter().enumerate().filter(|&(j, _)| j < t).map(|(j, b)| b * errors[t - 1 - j]).sum::<f64>();
+            values.push(prediction);
+            errors.push(0.0);
+        }
+
+        let mut psi = vec![1.0];
+        for j in 1..horizon
+        {
+            let weight = ma.get(j - 1).cloned().unwrap_or(0.0) + (1..=j.min(ar.len())).map(|i| ar[i - 1] * psi[j - i]).sum::<f64>();
+            psi.push(weight);
+        }
+        let mut cumulative = 0.0;
+        let standard_errors: Array1<f64> = psi.iter().map(|weight| { cumulative += weight.powi(2); (self.sigma_squared * cumulative).sqrt() }).collect();
+        let mean_forecast: Array1<f64> = values[n..].iter().map(|&value| value + mean).collect();
+        Forecast::new(mean_forecast, standard_errors, confidence_level)
+    }
+
+    // All roots of the AR and MA polynomials lie outside the circle with the given radius
+    fn has_roots_outside(&self, radius: f64) -> bool
+    {
+        let scale = |polynomial: Vec<f64>| -> Vec<f64> { polynomial.iter().enumerate().map(|(i, c)| c * radius.powi(i as i32 + 1)).collect() };
+        let ma: Vec<f64> = self.model.get_ma_polynomial(&self.coefficients).iter().map(|&b| -b).collect();
+        is_stationary(&scale(self.model.get_ar_polynomial(&self.coefficients))) && is_stationary(&scale(ma))
+    }
+
+    // Ljung-Box with the ARMA coefficients subtracted from the degrees of freedom, Jarque-Bera for normality
+    pub fn get_residual_diagnostics(&self, lags: usize) -> Result<ResidualDiagnostics, String>
+    {
+        let residuals = TimeSeries::from_values(self.residuals.clone())?;
+        let num_arma_coefficients = self.model.get_num_arma_coefficients();
+        Ok(ResidualDiagnostics
+        {
+            ljung_box: residuals.get_ljung_box_test(lags, num_arma_coefficients)?,
+            jarque_bera: JarqueBera.run(&self.residuals)?,
+            acf: residuals.get_acf(lags, 0.95)?,
+        })
+    }
+}
+
+#[allow(unused)]
+impl AutoArima
+{
+    // Defaults: p, q up to 3, d up to 2 chosen by KPSS at the 5 % level, no seasonal part
+    pub fn new(criterion: InformationCriterion, method: ArimaMethod) -> Self
+    {
+        AutoArima
+        {
+            max_order: (3, 2, 3),
+            max_seasonal_order: (0, 0),
+            seasonal_differences: 0,
+            period: 1,
+            test: StationarityTest::Kpss,
+            alpha_level: 0.05,
+            criterion,
+            method,
+        }
+    }
+
+    pub fn with_max_order(mut self, max_p: usize, max_d: usize, max_q: usize) -> Self
+    {
+        self.max_order = (max_p, max_d, max_q);
+        self
+    }
+
+    // The seasonal differencing order is fixed, d is chosen after seasonal differencing
+    pub fn with_seasonal_search(mut self, max_p: usize, differences: usize, max_q: usize, period: usize) -> Result<Self, String>
+    {
+        if period < 2
+        {
+            return Err(String::from("Seasonal period must be at least 2!"));
+        }
+        self.max_seasonal_order = (max_p, max_q);
+        self.seasonal_differences = differences;
+        self.period = period;
+        Ok(self)
+    }
+
+    pub fn with_stationarity_test(mut self, test: StationarityTest, alpha_level: f64) -> Self
+    {
+        self.test = test;
+        self.alpha_level = alpha_level;
+        self
+    }
+
+    pub fn fit(&self, series: &TimeSeries) -> Result<ArimaFit, String>
+    {
+        let seasonally_differenced = if self.seasonal_differences > 0 { series.get_difference(self.period, self.seasonal_differences)? } else { series.clone() };
+        let d = get_differencing_order(&seasonally_differenced, self.max_order.1, self.test, self.alpha_level)?;
+
+        let mut best: Option<(f64, ArimaFit)> = None;
+        for p in 0..=self.max_order.0
+        {
+            for q in 0..=self.max_order.2
+            {
+                for seasonal_p in 0..=self.max_seasonal_order.0
+                {
+                    for seasonal_q in 0..=self.max_seasonal_order.1
+                    {
+                        let mut model = Arima::new(p, d, q, self.method);
+                        if self.period > 1
+                        {
+                            model = model.with_seasonal_order(seasonal_p, self.seasonal_differences, seasonal_q, self.period)?;
+                        }
+                        // Orders that cannot be fitted or end up with roots close to the unit circle are skipped
+                        if let Ok(fit) = model.fit(series)
+                        {
+                            if !fit.has_roots_outside(ROOT_MARGIN)
+                            {
+                                continue;
+                            }
+                            let score = if self.criterion == InformationCriterion::Aic { fit.aic } else { fit.bic };
+                            if score.is_finite() && best.as_ref().is_none_or(|(best_score, _)| score < *best_score)
+                            {
+                                best = Some((score, fit));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        best.map(|(_, fit)| fit).ok_or_else(|| String::from("No model could be fitted!"))
+    }
+}
+
+// Residuals e_t = x_t - a_1 x_{t-1} - ... - b_1 e_{t-1} - ... for t past the AR order, earlier residuals are zero
+fn get_css_likelihood(centered: &[f64], ar: &[f64], ma: &[f64]) -> Likelihood
+{
+    let start = ar.len();
+    let mut errors = vec![0.0; centered.len()];
+    for t in start..centered.len()
+    {
+        errors[t] = centered[t] - ar.iter().enumerate().map(|(i, a)| a * centered[t - 1 - i]).sum::<f64>()
+            - ma.iter().enumerate().filter(|&(j, _)| j < t).map(|(j, b)| b * errors[t - 1 - j]).sum::<f64>();
+    }
+    let residuals = errors[start..].to_vec();
+    let num_observations = residuals.len();
+    let sigma_squared = residuals.iter().map(|e| e.powi(2)).sum::<f64>() / num_observations as f64;
+    let log_likelihood = -0.5 * num_observations as f64 * ((2.0 * std::f64::consts::PI * sigma_squared).ln() + 1.0);
+    Likelihood { log_likelihood, sigma_squared, residuals, num_observations }
+}
+
+// Kalman filter on Harvey's state space form with state dimension r = max(p, q + 1),
+// sigma^2 is concentrated out of the likelihood
+fn get_exact_likelihood(centered: &[f64], ar: &[f64], ma: &[f64]) -> Result<Likelihood, String>
+{
+    let r = ar.len().max(ma.len() + 1);
+    let phi: Vec<f64> = (0..r).map(|i| ar.get(i).cloned().unwrap_or(0.0)).collect();
+    let loading: Vec<f64> = (0..r).map(|i| if i == 0 { 1.0 } else { ma.get(i - 1).cloned().unwrap_or(0.0) }).collect();
+
+    let mut state = vec![0.0; r];
+    let mut covariance = get_stationary_covariance(&phi, &loading)?;
+    let mut residuals = Vec::with_capacity(centered.len());
+    let mut sum_of_squares = 0.0;
+    let mut sum_of_log_variances = 0.0;
+    for &value in centered
+    {
+        let innovation = value - state[0];
+        let variance = covariance[0][0];
+        if variance <= 0.0
+        {
+            return Err(String::from("Innovation variance is not positive!"));
+        }
+        residuals.push(innovation);
+        sum_of_squares += innovation.powi(2) / variance;
+        sum_of_log_variances += variance.ln();
+
+        // Update with the new observation
+        let gain: Vec<f64> = (0..r).map(|i| covariance[i][0] / variance).collect();
+        let first_row = covariance[0].clone();
+        for i in 0..r
+        {
+            state[i] += gain[i] * innovation;
+            for j in 0..r
+            {
+                covariance[i][j] -= gain[i] * first_row[j];
+            }
+        }
+
+        // Predict the next state, T shifts the state up and adds phi times its first element
+        state = (0..r).map(|i| phi[i] * state[0] + state.get(i + 1).cloned().unwrap_or(0.0)).collect();
+        covariance = get_transition_covariance(&covariance, &phi, &loading);
+    }
+
+    let n = centered.len();
+    let sigma_squared = sum_of_squares / n as f64;
+    let log_likelihood = -0.5 * (n as f64 * ((2.0 * std::f64::consts::PI * sigma_squared).ln() + 1.0) + sum_of_log_variances);
+    Ok(Likelihood { log_likelihood, sigma_squared, residuals, num_observations: n })
+}
+
+// T P T' + R R'
+fn get_transition_covariance(covariance: &[Vec<f64>], phi: &[f64], loading: &[f64]) -> Vec<Vec<f64>>
+{
+    let r = phi.len();
+    let shifted: Vec<Vec<f64>> = (0..r).map(|i| (0..r).map(|j| phi[i] * covariance[0][j] + covariance.get(i + 1).map_or(0.0, |row| row[j])).collect()).collect();
+    (0..r).map(|i| (0..r).map(|j| phi[j] * shifted[i][0] + shifted[i].get(j + 1).cloned().unwrap_or(0.0) + loading[i] * loading[j]).collect()).collect()
+}
+
+// Solves P = T P T' + R R' by the doubling algorithm P <- P + A P A', A <- A A
+fn get_stationary_covariance(phi: &[f64], loading: &[f64]) -> Result<Vec<Vec<f64>>, String>
+{
+    let r = phi.len();
+    let mut covariance: Vec<Vec<f64>> = (0..r).map(|i| (0..r).map(|j| loading[i] * loading[j]).collect()).collect();
+    let mut transition: Vec<Vec<f64>> = (0..r).map(|i| (0..r).map(|j| if j == 0 { phi[i] } else if j == i + 1 { 1.0 } else { 0.0 }).collect()).collect();
+    let multiply = |a: &Vec<Vec<f64>>, b: &Vec<Vec<f64>>, transpose_b: bool| -> Vec<Vec<f64>>
+    {
+        (0..r).map(|i| (0..r).map(|j| (0..r).map(|k| a[i][k] * if transpose_b { b[j][k] } else { b[k][j] }).sum()).collect()).collect()
+    };
+    for _ in 0..64
+    {
+        let increment = multiply(&multiply(&transition, &covariance, false), &transition, true);
+        covariance = (0..r).map(|i| (0..r).map(|j| covariance[i][j] + increment[i][j]).collect()).collect();
+        transition = multiply(&transition, &transition, false);
+        let size = transition.iter().flatten().fold(0.0f64, |acc, value| acc.max(value.abs()));
+        if size < 1e-15
+        {
+            return Ok(covariance);
+        }
+        if !size.is_finite()
+        {
+            break;
+        }
+    }
+    Err(String::from("Model is not stationary!"))
+}
+
+// 1 - a_1 z - ... - a_p z^p has all roots outside the unit circle iff all reflection
+// coefficients of the step-down recursion are smaller than one in absolute value
+fn is_stationary(ar: &[f64]) -> bool
+{
+    let mut coefficients = ar.to_vec();
+    while let Some(&reflection) = coefficients.last()
+    {
+        if reflection.abs() >= 1.0 || reflection.is_nan()
+        {
+            return false;
+        }
+        let k = coefficients.len();
+        coefficients = (0..k - 1).map(|i| (coefficients[i] + reflection * coefficients[k - 2 - i]) / (1.0 - reflection.powi(2))).collect();
+    }
+    true
+}
+
+// (1 + a_1 z + ...) (1 + b_1 z + ...), both given without their leading one, the result includes it
+fn get_polynomial_product(a: &[f64], b: &[f64]) -> Vec<f64>
+{
+    let a: Vec<f64> = std::iter::once(1.0).chain(a.iter().cloned()).collect();
+    let b: Vec<f64> = std::iter::once(1.0).chain(b.iter().cloned()).collect();
+    let mut product = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate()
+    {
+        for (j, y) in b.iter().enumerate()
+        {
+            product[i + j] += x * y;
+        }
+    }
+    product
+}
+
+// Nelder-Mead restarted once from its solution
+fn get_coefficients_minimum<F>(objective: F, initial_point: &Array1<f64>) -> Result<Array1<f64>, String>
+where F: Fn(&Array1<f64>) -> f64 + Copy
+{
+    let (point, _) = get_nelder_mead_minimum(objective, initial_point, 0.1, 1e-10, 5000)?;
+    let (point, _) = get_nelder_mead_minimum(objective, &point, 0.05, 1e-12, 5000)?;
+    Ok(point)
+}
@@ -0,0 +1,375 @@
+use super::linear_algebra::{add_intercept_column, get_least_squares, get_matrix_vector_product};
+use super::utilities::{get_coefficient_of_determination, get_sse, check_vector_for_nans};
+use ndarray::{Array1, Array2};
+
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transform
+{
+    Identity,
+    Log,
+    BoxCox(f64),
+}
+
+#[allow(unused)]
+impl Transform
+{
+    pub fn apply(&self, x: &Array1<f64>) -> Result<Array1<f64>, String>
+    {
+        let x = check_vector_for_nans(x)?;
+        match self
+        {
+            Transform::Identity => Ok(x.to_owned()),
+            Transform::Log | Transform::BoxCox(_) if x.iter().any(|&x_i| x_i <= 0.0) =>
+            {
+                Err(String::from("Log and Box-Cox transforms require positive values!"))
+            },
+            Transform::Log => Ok(x.mapv(f64::ln)),
+            Transform::BoxCox(lambda) => Ok(x.mapv(|x_i| get_box_cox(x_i, *lambda))),
+        }
+    }
+
+    pub fn invert(&self, z: &Array1<f64>) -> Array1<f64>
+    {
+        match self
+        {
+            Transform::Identity => z.to_owned(),
+            Transform::Log => z.mapv(f64::exp),
+            Transform::BoxCox(lambda) if *lambda == 0.0 => z.mapv(f64::exp),
+            Transform::BoxCox(lambda) => z.mapv(|z_i| (lambda * z_i + 1.0).powf(1.0 / lambda)),
+        }
+    }
+}
+
+fn get_box_cox(x: f64, lambda: f64) -> f64
+{
+    if lambda == 0.0 { x.ln() } else { (x.powf(lambda) - 1.0) / lambda }
+}
+
+// Maximizes the profile log-likelihood of the Box-Cox transformed data on [-2, 2]
+#[allow(unused)]
+pub fn get_box_cox_lambda(x: &Array1<f64>) -> Result<f64, String>
+{
+    if x.len() < 2
+    {
+        return Err(String::from("Need at least two values to estimate lambda!"));
+    }
+    Transform::Log.apply(x)?;
+    let sum_of_logs: f64 = x.iter().map(|x_i| x_i.ln()).sum();
+    let n = x.len() as f64;
+    let negative_log_likelihood = |lambda: f64| -> f64
+    {
+        let transformed = x.mapv(|x_i| get_box_cox(x_i, lambda));
+        let mean = transformed.sum() / n;
+        let variance = transformed.mapv(|z| (z - mean).powi(2)).sum() / n;
+        n / 2.0 * variance.ln() - (lambda - 1.0) * sum_of_logs
+    };
+
+    // Golden section search
+    let golden_ratio = (5.0f64.sqrt() - 1.0) / 2.0;
+    let (mut lower, mut upper) = (-2.0f64, 2.0f64);
+    let mut c = upper - golden_ratio * (upper - lower);
+    let mut d = lower + golden_ratio * (upper - lower);
+    while (upper - lower).abs() > 1e-8
+    {
+        if negative_log_likelihood(c) < negative_log_likelihood(d)
+        {
+            upper = d;
+        }
+        else
+        {
+            lower = c;
+        }
+        c = upper - golden_ratio * (upper - lower);
+        d = lower + golden_ratio * (upper - lower);
+    }
+    Ok((lower + upper) / 2.0)
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub enum Basis
+{
+    Polynomial(usize),
+    // Interior knots, boundary knots are taken from the range of the data
+    BSpline { interior_knots: Vec<f64>, degree: usize },
+    NaturalCubicSpline { interior_knots: Vec<f64> },
+}
+
+// A basis with everything needed to evaluate it on new data
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub enum FittedBasis
+{
+    OrthogonalPolynomial { alphas: Vec<f64>, norms_squared: Vec<f64> },
+    BSpline { knot_vector: Vec<f64>, degree: usize },
+    NaturalCubicSpline { knots: Vec<f64> },
+}
+
+#[allow(unused)]
+impl FittedBasis
+{
+    pub fn new(basis: &Basis, x: &Array1<f64>) -> Result<Self, String>
+    {
+        let x = check_vector_for_nans(x)?;
+        if x.is_empty()
+        {
+            return Err(String::from("Vector cannot be empty"));
+        }
+        let min = x.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = x.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+        match basis
+        {
+            Basis::Polynomial(degree) => get_orthogonal_polynomial_coefficients(x, *degree),
+            Basis::BSpline { interior_knots, degree } =>
+            {
+                let interior_knots = check_knots(interior_knots, min, max)?;
+                let mut knot_vector = vec![min; degree + 1];
+                knot_vector.extend(interior_knots);
+                knot_vector.extend(vec![max; degree + 1]);
+                Ok(FittedBasis::BSpline { knot_vector, degree: *degree })
+            },
+            Basis::NaturalCubicSpline { interior_knots } =>
+            {
+                let mut knots = vec![min];
+                knots.extend(check_knots(interior_knots, min, max)?);
+                knots.push(max);
+                Ok(FittedBasis::NaturalCubicSpline { knots })
+            },
+        }
+    }
+
+    // Basis columns without the intercept
+    pub fn evaluate(&self, x: &Array1<f64>) -> Result<Array2<f64>, String>
+    {
+        let x = check_vector_for_nans(x)?;
+        match self
+        {
+            FittedBasis::OrthogonalPolynomial { alphas, norms_squared } =>
+            {
+                Ok(get_orthogonal_polynomial_basis(x, alphas, norms_squared))
+            },
+            FittedBasis::BSpline { knot_vector, degree } =>
+            {
+                get_b_spline_basis(x, knot_vector, *degree)
+            },
+            FittedBasis::NaturalCubicSpline { knots } =>
+            {
+                Ok(get_natural_cubic_spline_basis(x, knots))
+            },
+        }
+    }
+}
+
+fn check_knots(interior_knots: &[f64], min: f64, max: f64) -> Result<Vec<f64>, String>
+{
+    let mut knots = interior_knots.to_vec();
+    knots.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    if knots.iter().any(|&knot| knot <= min || knot >= max)
+    {
+        return Err(String::from("Interior knots must lie strictly inside the range of the data!"));
+    }
+    Ok(knots)
+}
+
+// Stieltjes procedure: p_{k+1}(x) = (x - alpha_k) p_k(x) - (norm_k / norm_{k-1}) p_{k-1}(x)
+fn get_orthogonal_polynomial_coefficients(x: &Array1<f64>, degree: usize) -> Result<FittedBasis, String>
+{
+    if degree == 0
+    {
+        return Err(String::from("Degree must be at least 1!"));
+    }
+    let distinct_values = {
+        let mut sorted = x.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        sorted.dedup();
+        sorted.len()
+    };
+    if degree >= distinct_values
+    {
+        return Err(String::from("Degree must be less than the number of distinct values!"));
+    }
+
+    let mut alphas = Vec::with_capacity(degree);
+    let mut norms_squared = vec![1.0, x.len() as f64];
+    let mut previous = Array1::<f64>::zeros(x.len());
+    let mut current = Array1::<f64>::ones(x.len());
+
+    for k in 0..degree
+    {
+        let norm_squared = norms_squared[k + 1];
+        let alpha = (x * &current.mapv(|p| p * p)).sum() / norm_squared;
+        let beta = norm_squared / norms_squared[k];
+        let next = (x - alpha) * &current - &previous * beta;
+        alphas.push(alpha);
+        norms_squared.push(next.mapv(|p| p * p).sum());
+        previous = current;
+        current = next;
+    }
+    Ok(FittedBasis::OrthogonalPolynomial { alphas, norms_squared })
+}
+
+#[allow(unused)]
+pub fn get_orthogonal_polynomial_basis(x: &Array1<f64>, alphas: &[f64], norms_squared: &[f64]) -> Array2<f64>
+{
+    let degree = alphas.len();
+    let mut basis = Array2::<f64>::zeros((x.len(), degree));
+    let mut previous = Array1::<f64>::zeros(x.len());
+    let mut current = Array1::<f64>::ones(x.len());
+
+    for k in 0..degree
+    {
+        let beta = norms_squared[k + 1] / norms_squared[k];
+        let next = (x - alphas[k]) * &current - &previous * beta;
+        basis.column_mut(k).assign(&(&next / norms_squared[k + 2].sqrt()));
+        previous = current;
+        current = next;
+    }
+    basis
+}
+
+// Cox-de Boor recursion, the first basis function is dropped so the intercept stays identifiable
+#[allow(unused)]
+pub fn get_b_spline_basis(x: &Array1<f64>, knot_vector: &[f64], degree: usize) -> Result<Array2<f64>, String>
+{
+    let num_basis_functions = knot_vector.len() - degree - 1;
+    let lower = knot_vector[0];
+    let upper = knot_vector[knot_vector.len() - 1];
+    if x.iter().any(|&x_i| x_i < lower || x_i > upper)
+    {
+        return Err(String::from("Values must lie within the boundary knots!"));
+    }
+
+    let mut basis = Array2::<f64>::zeros((x.len(), num_basis_functions - 1));
+    for (row, &x_i) in x.iter().enumerate()
+    {
+        let mut values: Vec<f64> = (0..knot_vector.len() - 1)
+            .map(|i|
+            {
+                let in_span = knot_vector[i] <= x_i && x_i < knot_vector[i + 1];
+                // The right boundary belongs to the last non-empty span
+                let at_upper_boundary = x_i == upper && knot_vector[i] < upper && knot_vector[i + 1] == upper;
+                if in_span || at_upper_boundary { 1.0 } else { 0.0 }
+            })
+            .collect();
+
+        for p in 1..=degree
+        {
+            for i in 0..knot_vector.len() - p - 1
+            {
+                let left_denominator = knot_vector[i + p] - knot_vector[i];
+                let right_denominator = knot_vector[i + p + 1] - knot_vector[i + 1];
+                let left = if left_denominator > 0.0 { (x_i - knot_vector[i]) / left_denominator * values[i] } else { 0.0 };
+                let right = if right_denominator > 0.0 { (knot_vector[i + p + 1] - x_i) / right_denominator * values[i + 1] } else { 0.0 };
+                values[i] = left + right;
+            }
+        }
+
+        for j in 1..num_basis_functions
+        {
+            basis[[row, j - 1]] = values[j];
+        }
+    }
+    Ok(basis)
+}
+
+// Truncated power basis of Hastie, Tibshirani & Friedman (eq. 5.4), linear beyond the boundary knots
+#[allow(unused)]
+pub fn get_natural_cubic_spline_basis(x: &Array1<f64>, knots: &[f64]) -> Array2<f64>
+{
+    let num_knots = knots.len();
+    let last_knot = knots[num_knots - 1];
+    let d = |x: f64, k: usize| -> f64
+    {
+        ((x - knots[k]).max(0.0).powi(3) - (x - last_knot).max(0.0).powi(3)) / (last_knot - knots[k])
+    };
+
+    let mut basis = Array2::<f64>::zeros((x.len(), num_knots - 1));
+    for (row, &x_i) in x.iter().enumerate()
+    {
+        basis[[row, 0]] = x_i;
+        for k in 0..num_knots - 2
+        {
+            basis[[row, k + 1]] = d(x_i, k) - d(x_i, num_knots - 2);
+        }
+    }
+    basis
+}
+
+#[allow(unused)]
+#[derive(Debug)]
+pub struct BasisRegression
+{
+    basis: FittedBasis,
+    input_transform: Transform,
+    response_transform: Transform,
+    pub coefficients: Array1<f64>,
+    pub fitted_values: Array1<f64>,
+    pub sse: f64,
+    pub coefficient_of_determination: f64,
+}
+
+#[allow(unused)]
+impl BasisRegression
+{
+    pub fn fit(independent_variable: &Array1<f64>,
+               dependent_variable: &Array1<f64>,
+               basis: &Basis,
+               input_transform: Transform,
+               response_transform: Transform) -> Result<Self, String>
+    {
+        if independent_variable.len() != dependent_variable.len()
+        {
+            return Err(String::from("Vector lengths do not match!"));
+        }
+        let transformed_x = input_transform.apply(independent_variable)?;
+        let transformed_y = response_transform.apply(dependent_variable)?;
+
+        let fitted_basis = FittedBasis::new(basis, &transformed_x)?;
+        let design_matrix = add_intercept_column(&fitted_basis.evaluate(&transformed_x)?);
+        if design_matrix.nrows() <= design_matrix.ncols()
+        {
+            return Err(String::from("Number of observations must be greater than the number of basis functions!"));
+        }
+        let coefficients = get_least_squares(&design_matrix, &transformed_y)?;
+        let transformed_fit = get_matrix_vector_product(&design_matrix, &coefficients)?;
+
+        // Goodness of fit is measured on the scale the model was fitted on
+        let sse = get_sse(&transformed_fit, &transformed_y)?;
+        let coefficient_of_determination = get_coefficient_of_determination(&transformed_fit, &transformed_y)?;
+
+        Ok
+        (
+            BasisRegression
+            {
+                fitted_values: response_transform.invert(&transformed_fit),
+                basis: fitted_basis,
+                input_transform,
+                response_transform,
+                coefficients,
+                sse,
+                coefficient_of_determination,
+            }
+        )
+    }
+
+    pub fn predict(&self, independent_variable: &Array1<f64>) -> Result<Array1<f64>, String>
+    {
+        let transformed_x = self.input_transform.apply(independent_variable)?;
+        let design_matrix = add_intercept_column(&self.basis.evaluate(&transformed_x)?);
+        Ok(self.response_transform.invert(&get_matrix_vector_product(&design_matrix, &self.coefficients)?))
+    }
+
+    pub fn get_basis(&self) -> &FittedBasis
+    {
+        &self.basis
+    }
+}
+
+#[allow(unused)]
+pub fn get_polynomial_regression(independent_variable: &Array1<f64>, dependent_variable: &Array1<f64>, degree: usize)
+-> Result<BasisRegression, String>
+{
+    BasisRegression::fit(independent_variable, dependent_variable, &Basis::Polynomial(degree), Transform::Identity, Transform::Identity)
+}
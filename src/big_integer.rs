@@ -0,0 +1,161 @@
+use std::fmt;
+
+// Unsigned integer of arbitrary size, stored as little-endian base 2^32 limbs
+// without trailing zero limbs, zero has no limbs
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BigUint
+{
+    limbs: Vec<u32>,
+}
+
+#[allow(unused)]
+impl BigUint
+{
+    pub fn zero() -> Self
+    {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self
+    {
+        BigUint::from_u128(1)
+    }
+
+    pub fn from_u128(mut value: u128) -> Self
+    {
+        let mut limbs = Vec::new();
+        while value > 0
+        {
+            limbs.push(value as u32);
+            value >>= 32;
+        }
+        BigUint { limbs }
+    }
+
+    pub fn is_zero(&self) -> bool
+    {
+        self.limbs.is_empty()
+    }
+
+    // None if the value does not fit
+    pub fn to_u128(&self) -> Option<u128>
+    {
+        if self.limbs.len() > 4
+        {
+            return None;
+        }
+        Some(self.limbs.iter().rev().fold(0u128, |value, &limb| (value << 32) | limb as u128))
+    }
+
+    pub fn multiply_small(&mut self, factor: u64)
+    {
+        if factor == 0
+        {
+            self.limbs.clear();
+            return;
+        }
+        let mut carry: u128 = 0;
+        for limb in self.limbs.iter_mut()
+        {
+            let product = *limb as u128 * factor as u128 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        while carry > 0
+        {
+            self.limbs.push(carry as u32);
+            carry >>= 32;
+        }
+    }
+
+    // Divides in place and returns the remainder
+    pub fn divide_small(&mut self, divisor: u64) -> Result<u64, String>
+    {
+        if divisor == 0
+        {
+            return Err(String::from("Division by zero!"));
+        }
+        let mut remainder: u128 = 0;
+        for limb in self.limbs.iter_mut().rev()
+        {
+            let current = (remainder << 32) | *limb as u128;
+            *limb = (current / divisor as u128) as u32;
+            remainder = current % divisor as u128;
+        }
+        self.trim();
+        Ok(remainder as u64)
+    }
+
+    pub fn add(&self, other: &BigUint) -> BigUint
+    {
+        let mut limbs = Vec::with_capacity(self.limbs.len().max(other.limbs.len()) + 1);
+        let mut carry: u64 = 0;
+        for i in 0..self.limbs.len().max(other.limbs.len())
+        {
+            let sum = *self.limbs.get(i).unwrap_or(&0) as u64 + *other.limbs.get(i).unwrap_or(&0) as u64 + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        if carry > 0
+        {
+            limbs.push(carry as u32);
+        }
+        BigUint { limbs }
+    }
+
+    pub fn multiply(&self, other: &BigUint) -> BigUint
+    {
+        if self.is_zero() || other.is_zero()
+        {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate()
+        {
+            let mut carry: u64 = 0;
+            for (j, &b) in other.limbs.iter().enumerate()
+            {
+                let current = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = current as u32;
+                carry = current >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        let mut result = BigUint { limbs };
+        result.trim();
+        result
+    }
+
+    fn trim(&mut self)
+    {
+        while self.limbs.last() == Some(&0)
+        {
+            self.limbs.pop();
+        }
+    }
+}
+
+// Decimal digits, peeled off nine at a time
+impl fmt::Display for BigUint
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        if self.is_zero()
+        {
+            return write!(f, "0");
+        }
+        let mut value = self.clone();
+        let mut chunks = Vec::new();
+        while !value.is_zero()
+        {
+            chunks.push(value.divide_small(1_000_000_000).unwrap());
+        }
+        let mut text = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev()
+        {
+            text.push_str(&format!("{:09}", chunk));
+        }
+        write!(f, "{}", text)
+    }
+}
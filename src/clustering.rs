@@ -0,0 +1,640 @@
+use super::linear_algebra::{get_matrix_product, get_symmetric_eigendecomposition};
+use super::utilities::get_sample_covariance_matrix;
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KMeansAlgorithm
+{
+    // Alternates assigning every point to its nearest centroid and moving the centroids to the cluster means
+    Lloyd,
+    // Elkan (2003): the same iterations as Lloyd, skipping distances ruled out by the triangle inequality
+    Elkan,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DistanceMetric
+{
+    Euclidean,
+    Manhattan,
+    // One minus the cosine of the angle between the points, which must not be zero
+    Cosine,
+    // Euclidean distance after decorrelating the columns with the inverse of their sample covariance matrix
+    Mahalanobis,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct KMeans
+{
+    num_clusters: usize,
+    seed: u64,
+    algorithm: KMeansAlgorithm,
+    num_initializations: usize,
+    max_iterations: usize,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct MiniBatchKMeans
+{
+    num_clusters: usize,
+    batch_size: usize,
+    seed: u64,
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct KMedoids
+{
+    num_clusters: usize,
+    max_iterations: usize,
+}
+
+// Inertia is the sum of squared distances of the points to their centroids
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct Clustering
+{
+    pub labels: Array1<usize>,
+    pub centroids: Array2<f64>,
+    pub inertia: f64,
+    pub iterations: usize,
+}
+
+// Medoids are indices of the points, total distance is the sum of distances of the points to their medoids
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct MedoidClustering
+{
+    pub labels: Array1<usize>,
+    pub medoids: Array1<usize>,
+    pub total_distance: f64,
+    pub iterations: usize,
+}
+
+#[allow(unused)]
+impl KMeans
+{
+    pub fn new(num_clusters: usize, seed: u64) -> Self
+    {
+        KMeans { num_clusters, seed, algorithm: KMeansAlgorithm::Lloyd, num_initializations: 1, max_iterations: 300 }
+    }
+
+    pub fn with_algorithm(mut self, algorithm: KMeansAlgorithm) -> Self
+    {
+        self.algorithm = algorithm;
+        self
+    }
+
+    // Runs from several k-means++ seedings and keeps the clustering with the lowest inertia
+    pub fn with_num_initializations(mut self, num_initializations: usize) -> Self
+    {
+        self.num_initializations = num_initializations;
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self
+    {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    // Rows are observations, iterations count the centroid updates until the assignments stop changing
+    pub fn fit(&self, data: &Array2<f64>) -> Result<Clustering, String>
+    {
+        check_data(data, self.num_clusters)?;
+        if self.num_initializations == 0
+        {
+            return Err(String::from("Need at least one initialization!"));
+        }
+        let rng = fastrand::Rng::with_seed(self.seed);
+        let mut best: Option<Clustering> = None;
+        for _ in 0..self.num_initializations
+        {
+            let centroids = get_kmeans_plus_plus_centroids(data, self.num_clusters, &rng)?;
+            let clustering = match self.algorithm
+            {
+                KMeansAlgorithm::Lloyd => get_lloyd_clustering(data, centroids, self.max_iterations)?,
+                KMeansAlgorithm::Elkan => get_elkan_clustering(data, centroids, self.max_iterations)?,
+            };
+            if best.as_ref().is_none_or(|b| clustering.inertia < b.inertia)
+            {
+                best = Some(clustering);
+            }
+        }
+        Ok(best.unwrap())
+    }
+}
+
+#[allow(unused)]
+impl MiniBatchKMeans
+{
+    pub fn new(num_clusters: usize, batch_size: usize, seed: u64) -> Self
+    {
+        MiniBatchKMeans { num_clusters, batch_size, seed, max_iterations: 100, tolerance: 1e-4 }
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self
+    {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    // Relative to the average variance of the columns, zero runs all iterations
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self
+    {
+        self.tolerance = tolerance;
+        self
+    }
+
+    // Sculley (2010): each centroid moves towards the batch points assigned to it with a learning rate
+    // of one over the number of points it has seen, labels and inertia are those of the full data
+    pub fn fit(&self, data: &Array2<f64>) -> Result<Clustering, String>
+    {
+        check_data(data, self.num_clusters)?;
+        if self.batch_size == 0 || self.max_iterations == 0
+        {
+            return Err(String::from("Batch size and number of iterations must be positive!"));
+        }
+        let rng = fastrand::Rng::with_seed(self.seed);
+        let mut centroids = get_kmeans_plus_plus_centroids(data, self.num_clusters, &rng)?;
+        let tolerance = self.tolerance * data.var_axis(Axis(0), 0.0).mean().unwrap();
+        let mut counts = vec![0usize; self.num_clusters];
+        let mut iterations = self.max_iterations;
+        for iteration in 1..=self.max_iterations
+        {
+            let batch: Vec<usize> = (0..self.batch_size).map(|_| rng.usize(..data.nrows())).collect();
+            let nearest: Vec<usize> = batch.iter().map(|&i| get_nearest_centroid(data.row(i), &centroids).0).collect();
+            let previous = centroids.clone();
+            for (&i, &label) in batch.iter().zip(nearest.iter())
+            {
+                counts[label] += 1;
+                let learning_rate = 1.0 / counts[label] as f64;
+                let mut centroid = centroids.row_mut(label);
+                centroid.zip_mut_with(&data.row(i), |c, &x| *c += learning_rate * (x - *c));
+            }
+            if (&centroids - &previous).mapv(|d| d * d).sum() <= tolerance
+            {
+                iterations = iteration;
+                break;
+            }
+        }
+        let labels = get_assignments(data, &centroids);
+        let inertia = get_inertia(data, &centroids, &labels);
+        Ok(Clustering { labels, centroids, inertia, iterations })
+    }
+}
+
+#[allow(unused)]
+impl KMedoids
+{
+    pub fn new(num_clusters: usize) -> Self
+    {
+        KMedoids { num_clusters, max_iterations: 100 }
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self
+    {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    // Partitioning around medoids (Kaufman and Rousseeuw, 1990) on a matrix of pairwise distances,
+    // a greedy build phase followed by the best improving swap of a medoid and a non-medoid until none is left
+    pub fn fit(&self, distances: &Array2<f64>) -> Result<MedoidClustering, String>
+    {
+        let num_points = check_distance_matrix(distances)?;
+        if self.num_clusters == 0 || self.num_clusters > num_points
+        {
+            return Err(String::from("Number of clusters must be between 1 and the number of points!"));
+        }
+
+        let mut medoids: Vec<usize> = Vec::with_capacity(self.num_clusters);
+        let mut nearest = Array1::from_elem(num_points, f64::INFINITY);
+        for _ in 0..self.num_clusters
+        {
+            let cost = |h: usize| nearest.iter().zip(distances.row(h).iter()).map(|(&n, &d)| n.min(d)).sum::<f64>();
+            let candidate = (0..num_points).filter(|h| !medoids.contains(h))
+                .min_by(|&a, &b| cost(a).partial_cmp(&cost(b)).unwrap()).unwrap();
+            nearest.zip_mut_with(&distances.row(candidate), |n, &d| *n = n.min(d));
+            medoids.push(candidate);
+        }
+
+        for iteration in 0..=self.max_iterations
+        {
+            // Distances to the nearest and second nearest medoid
+            let mut closest = vec![0usize; num_points];
+            let mut first = Array1::from_elem(num_points, f64::INFINITY);
+            let mut second = Array1::from_elem(num_points, f64::INFINITY);
+            for j in 0..num_points
+            {
+                for (m, &medoid) in medoids.iter().enumerate()
+                {
+                    let d = distances[[medoid, j]];
+                    if d < first[j]
+                    {
+                        second[j] = first[j];
+                        first[j] = d;
+                        closest[j] = m;
+                    }
+                    else if d < second[j]
+                    {
+                        second[j] = d;
+                    }
+                }
+            }
+            let total_distance = first.sum();
+
+            let mut best_swap = (0.0, 0, 0);
+            for m in 0..medoids.len()
+            {
+                for h in (0..num_points).filter(|h| !medoids.contains(h))
+                {
+                    let change: f64 = (0..num_points).map(|j|
+                    {
+                        let d = distances[[h, j]];
+                        if closest[j] == m { d.min(second[j]) - first[j] } else { (d - first[j]).min(0.0) }
+                    }).sum();
+                    if change < best_swap.0
+                    {
+                        best_swap = (change, m, h);
+                    }
+                }
+            }
+            // Ignore changes at rounding level so that equally good swaps cannot cycle
+            if best_swap.0 >= -1e-12 * total_distance
+            {
+                let labels = Array1::from(closest);
+                return Ok(MedoidClustering { labels, medoids: Array1::from(medoids), total_distance, iterations: iteration });
+            }
+            medoids[best_swap.1] = best_swap.2;
+        }
+        Err(String::from("PAM did not converge!"))
+    }
+}
+
+#[allow(unused)]
+pub fn get_distance_matrix(data: &Array2<f64>, metric: DistanceMetric) -> Result<Array2<f64>, String>
+{
+    let coordinates = metric.get_coordinates(data)?;
+    let num_points = data.nrows();
+    let mut distances = Array2::<f64>::zeros((num_points, num_points));
+    for i in 0..num_points
+    {
+        for j in i + 1..num_points
+        {
+            let d = metric.get_distance(coordinates.row(i), coordinates.row(j));
+            distances[[i, j]] = d;
+            distances[[j, i]] = d;
+        }
+    }
+    Ok(distances)
+}
+
+// Rousseeuw (1987): (b - a) / max(a, b) with a the mean distance to the rest of the own cluster and b the smallest
+// mean distance to another cluster, zero for points alone in their cluster
+#[allow(unused)]
+pub fn get_silhouette_widths(distances: &Array2<f64>, labels: &Array1<usize>) -> Result<Array1<f64>, String>
+{
+    let num_points = check_distance_matrix(distances)?;
+    if labels.len() != num_points
+    {
+        return Err(String::from("Need one label per point!"));
+    }
+    let num_clusters = labels.iter().max().unwrap() + 1;
+    let mut sizes = vec![0usize; num_clusters];
+    labels.iter().for_each(|&label| sizes[label] += 1);
+    let num_occupied = sizes.iter().filter(|&&size| size > 0).count();
+    if num_occupied < 2 || num_occupied >= num_points
+    {
+        return Err(String::from("Number of clusters must be between 2 and the number of points minus one!"));
+    }
+
+    let mut widths = Array1::<f64>::zeros(num_points);
+    for i in 0..num_points
+    {
+        let own = labels[i];
+        if sizes[own] == 1
+        {
+            continue;
+        }
+        let mut sums = vec![0.0; num_clusters];
+        for (j, &label) in labels.iter().enumerate()
+        {
+            sums[label] += distances[[i, j]];
+        }
+        let a = sums[own] / (sizes[own] - 1) as f64;
+        let b = (0..num_clusters).filter(|&c| c != own && sizes[c] > 0).map(|c| sums[c] / sizes[c] as f64).fold(f64::INFINITY, f64::min);
+        if a.max(b) > 0.0
+        {
+            widths[i] = (b - a) / a.max(b);
+        }
+    }
+    Ok(widths)
+}
+
+// Mean silhouette width, the number of clusters maximising it is a common choice
+#[allow(unused)]
+pub fn get_silhouette_score(distances: &Array2<f64>, labels: &Array1<usize>) -> Result<f64, String>
+{
+    Ok(get_silhouette_widths(distances, labels)?.mean().unwrap())
+}
+
+// Inertia of k-means with one up to the maximum number of clusters
+#[allow(unused)]
+pub fn get_elbow_curve(data: &Array2<f64>, max_clusters: usize, seed: u64) -> Result<Array1<f64>, String>
+{
+    let mut inertias = Array1::<f64>::zeros(max_clusters);
+    for k in 1..=max_clusters
+    {
+        inertias[k - 1] = KMeans::new(k, seed).with_num_initializations(10).fit(data)?.inertia;
+    }
+    Ok(inertias)
+}
+
+// Number of clusters whose inertia lies furthest below the chord from the first to the last point of the elbow curve
+#[allow(unused)]
+pub fn get_elbow(inertias: &Array1<f64>) -> Result<usize, String>
+{
+    let num_points = inertias.len();
+    if num_points < 3
+    {
+        return Err(String::from("Need the inertia for at least three numbers of clusters!"));
+    }
+    let (first, last) = (inertias[0], inertias[num_points - 1]);
+    let gaps = inertias.iter().enumerate().map(|(i, &inertia)| first + (last - first) * i as f64 / (num_points - 1) as f64 - inertia);
+    let elbow = gaps.enumerate().fold((0, f64::NEG_INFINITY), |best, (i, gap)| if gap > best.1 { (i, gap) } else { best }).0;
+    Ok(elbow + 1)
+}
+
+// Metrics are evaluated on transformed coordinates in which they become Euclidean (Manhattan for Manhattan) distances,
+// or a monotone function of them, so that spatial indices can prune by bounding boxes
+#[allow(unused)]
+impl DistanceMetric
+{
+    pub(crate) fn get_coordinates(&self, data: &Array2<f64>) -> Result<Array2<f64>, String>
+    {
+        if data.iter().any(|x| x.is_nan())
+        {
+            return Err(String::from("There is at least one NaN in the data!"));
+        }
+        match self
+        {
+            DistanceMetric::Euclidean | DistanceMetric::Manhattan => Ok(data.to_owned()),
+            // Unit vectors, the cosine distance is half their squared Euclidean distance
+            DistanceMetric::Cosine =>
+            {
+                let norms = data.mapv(|x| x * x).sum_axis(Axis(1)).mapv(f64::sqrt);
+                if norms.iter().any(|&norm| norm == 0.0)
+                {
+                    return Err(String::from("Cosine distance is undefined for a zero point!"));
+                }
+                Ok(data / &norms.insert_axis(Axis(1)))
+            },
+            // With S = V L V^T the points X V L^(-1/2) have identity covariance
+            DistanceMetric::Mahalanobis =>
+            {
+                let decomposition = get_symmetric_eigendecomposition(&get_sample_covariance_matrix(data)?)?;
+                let largest = decomposition.eigenvalues.iter().cloned().fold(0.0, f64::max);
+                if decomposition.eigenvalues.iter().any(|&value| value <= 1e-12 * largest)
+                {
+                    return Err(String::from("Covariance matrix is singular!"));
+                }
+                let whitening = decomposition.eigenvectors / &decomposition.eigenvalues.mapv(|value| value.sqrt());
+                get_matrix_product(data, &whitening)
+            },
+        }
+    }
+
+    // Distance between two points in the transformed coordinates
+    pub(crate) fn get_distance(&self, a: ArrayView1<f64>, b: ArrayView1<f64>) -> f64
+    {
+        let differences = a.iter().zip(b.iter()).map(|(x, y)| x - y);
+        match self
+        {
+            DistanceMetric::Manhattan => differences.map(f64::abs).sum(),
+            _ => self.get_metric_distance(differences.map(|d| d * d).sum::<f64>().sqrt()),
+        }
+    }
+
+    // Metric distance from the Euclidean norm of the difference in the transformed coordinates
+    pub(crate) fn get_metric_distance(&self, norm: f64) -> f64
+    {
+        if *self == DistanceMetric::Cosine { 0.5 * norm * norm } else { norm }
+    }
+}
+
+fn check_data(data: &Array2<f64>, num_clusters: usize) -> Result<(), String>
+{
+    if data.iter().any(|x| x.is_nan())
+    {
+        return Err(String::from("There is at least one NaN in the data!"));
+    }
+    if num_clusters == 0 || num_clusters > data.nrows()
+    {
+        return Err(String::from("Number of clusters must be between 1 and the number of points!"));
+    }
+    Ok(())
+}
+
+fn check_distance_matrix(distances: &Array2<f64>) -> Result<usize, String>
+{
+    let num_points = distances.nrows();
+    if num_points == 0 || distances.ncols() != num_points
+    {
+        return Err(String::from("Distance matrix must be square and not empty!"));
+    }
+    if distances.iter().any(|&d| d.is_nan() || d < 0.0) || distances.diag().iter().any(|&d| d != 0.0)
+    {
+        return Err(String::from("Distances must be non-negative with zeros on the diagonal!"));
+    }
+    Ok(num_points)
+}
+
+fn get_squared_distance(a: ArrayView1<f64>, b: ArrayView1<f64>) -> f64
+{
+    a.iter().zip(b.iter()).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+// Index and squared distance of the nearest centroid, ties go to the first
+fn get_nearest_centroid(point: ArrayView1<f64>, centroids: &Array2<f64>) -> (usize, f64)
+{
+    centroids.rows().into_iter().map(|centroid| get_squared_distance(point, centroid)).enumerate()
+        .fold((0, f64::INFINITY), |best, (k, d)| if d < best.1 { (k, d) } else { best })
+}
+
+fn get_assignments(data: &Array2<f64>, centroids: &Array2<f64>) -> Array1<usize>
+{
+    data.rows().into_iter().map(|point| get_nearest_centroid(point, centroids).0).collect()
+}
+
+fn get_inertia(data: &Array2<f64>, centroids: &Array2<f64>, labels: &Array1<usize>) -> f64
+{
+    labels.iter().enumerate().map(|(i, &label)| get_squared_distance(data.row(i), centroids.row(label))).sum()
+}
+
+// Arthur and Vassilvitskii (2007): each further centroid is a point drawn with probability proportional
+// to its squared distance from the nearest centroid chosen so far
+fn get_kmeans_plus_plus_centroids(data: &Array2<f64>, num_clusters: usize, rng: &fastrand::Rng) -> Result<Array2<f64>, String>
+{
+    let num_points = data.nrows();
+    let mut centroids = Array2::<f64>::zeros((num_clusters, data.ncols()));
+    centroids.row_mut(0).assign(&data.row(rng.usize(..num_points)));
+    let mut weights: Array1<f64> = data.rows().into_iter().map(|point| get_squared_distance(point, centroids.row(0))).collect();
+    for k in 1..num_clusters
+    {
+        let total = weights.sum();
+        if total == 0.0
+        {
+            return Err(String::from("Need at least as many distinct points as clusters!"));
+        }
+        let mut target = rng.f64() * total;
+        let mut chosen = weights.iter().rposition(|&w| w > 0.0).unwrap();
+        for (i, &weight) in weights.iter().enumerate()
+        {
+            if target < weight
+            {
+                chosen = i;
+                break;
+            }
+            target -= weight;
+        }
+        centroids.row_mut(k).assign(&data.row(chosen));
+        for (i, weight) in weights.iter_mut().enumerate()
+        {
+            *weight = weight.min(get_squared_distance(data.row(i), centroids.row(k)));
+        }
+    }
+    Ok(centroids)
+}
+
+// Cluster means, an empty cluster takes over the point furthest from its centroid
+fn get_updated_centroids(data: &Array2<f64>, labels: &Array1<usize>, centroids: &Array2<f64>) -> Array2<f64>
+{
+    let num_clusters = centroids.nrows();
+    let mut updated = Array2::<f64>::zeros(centroids.dim());
+    let mut sizes = vec![0usize; num_clusters];
+    for (i, &label) in labels.iter().enumerate()
+    {
+        let mut centroid = updated.row_mut(label);
+        centroid += &data.row(i);
+        sizes[label] += 1;
+    }
+    let mut distances: Vec<(usize, f64)> = labels.iter().enumerate().map(|(i, &label)| (i, get_squared_distance(data.row(i), centroids.row(label)))).collect();
+    distances.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    let mut furthest = distances.iter().map(|&(i, _)| i);
+    for (k, &size) in sizes.iter().enumerate()
+    {
+        if size == 0
+        {
+            updated.row_mut(k).assign(&data.row(furthest.next().unwrap()));
+        }
+        else
+        {
+            updated.row_mut(k).mapv_inplace(|x| x / size as f64);
+        }
+    }
+    updated
+}
+
+fn get_lloyd_clustering(data: &Array2<f64>, mut centroids: Array2<f64>, max_iterations: usize) -> Result<Clustering, String>
+{
+    let mut labels = get_assignments(data, &centroids);
+    for iteration in 1..=max_iterations
+    {
+        centroids = get_updated_centroids(data, &labels, &centroids);
+        let updated = get_assignments(data, &centroids);
+        if updated == labels
+        {
+            let inertia = get_inertia(data, &centroids, &labels);
+            return Ok(Clustering { labels, centroids, inertia, iterations: iteration });
+        }
+        labels = updated;
+    }
+    Err(String::from("K-means did not converge!"))
+}
+
+// Keeps an upper bound on the distance of each point to its centroid and lower bounds on the distances to all
+// centroids, a point is only revisited when its upper bound exceeds half the distance to the nearest other centroid
+fn get_elkan_clustering(data: &Array2<f64>, mut centroids: Array2<f64>, max_iterations: usize) -> Result<Clustering, String>
+{
+    let num_points = data.nrows();
+    let num_clusters = centroids.nrows();
+    let mut lower = Array2::<f64>::zeros((num_points, num_clusters));
+    for i in 0..num_points
+    {
+        for k in 0..num_clusters
+        {
+            lower[[i, k]] = get_squared_distance(data.row(i), centroids.row(k)).sqrt();
+        }
+    }
+    let mut labels = get_assignments(data, &centroids);
+    let mut upper: Array1<f64> = labels.iter().enumerate().map(|(i, &label)| lower[[i, label]]).collect();
+
+    for iteration in 1..=max_iterations
+    {
+        let updated = get_updated_centroids(data, &labels, &centroids);
+        let shifts: Vec<f64> = (0..num_clusters).map(|k| get_squared_distance(updated.row(k), centroids.row(k)).sqrt()).collect();
+        centroids = updated;
+        for i in 0..num_points
+        {
+            upper[i] += shifts[labels[i]];
+            for k in 0..num_clusters
+            {
+                lower[[i, k]] = (lower[[i, k]] - shifts[k]).max(0.0);
+            }
+        }
+        let between = get_distance_matrix(&centroids, DistanceMetric::Euclidean)?;
+        let margins: Vec<f64> = (0..num_clusters).map(|k| 0.5 * (0..num_clusters).filter(|&l| l != k).map(|l| between[[k, l]]).fold(f64::INFINITY, f64::min)).collect();
+
+        let mut changed = false;
+        for i in 0..num_points
+        {
+            let mut label = labels[i];
+            if upper[i] <= margins[label]
+            {
+                continue;
+            }
+            let mut tight = false;
+            for k in 0..num_clusters
+            {
+                if k == label || upper[i] <= lower[[i, k]] || upper[i] <= 0.5 * between[[label, k]]
+                {
+                    continue;
+                }
+                if !tight
+                {
+                    upper[i] = get_squared_distance(data.row(i), centroids.row(label)).sqrt();
+                    lower[[i, label]] = upper[i];
+                    tight = true;
+                    if upper[i] <= lower[[i, k]] || upper[i] <= 0.5 * between[[label, k]]
+                    {
+                        continue;
+                    }
+                }
+                let distance = get_squared_distance(data.row(i), centroids.row(k)).sqrt();
+                lower[[i, k]] = distance;
+                if distance < upper[i]
+                {
+                    label = k;
+                    upper[i] = distance;
+                }
+            }
+            if label != labels[i]
+            {
+                labels[i] = label;
+                changed = true;
+            }
+        }
+        if !changed
+        {
+            let inertia = get_inertia(data, &centroids, &labels);
+            return Ok(Clustering { labels, centroids, inertia, iterations: iteration });
+        }
+    }
+    Err(String::from("K-means did not converge!"))
+}
@@ -0,0 +1,214 @@
+use super::distributions::{get_chi_squared_ppf, get_f_ppf, get_normal_ppf, get_t_ppf};
+use super::exact_tests::{get_binomial_ln_pmf, get_clopper_pearson_interval};
+use super::utilities::{check_vector_for_nans, get_correlation_coefficient, get_mean, get_variance};
+use ndarray::Array1;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct IntervalEstimate
+{
+    pub estimate: f64,
+    pub confidence_interval: (f64, f64),
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProportionIntervalMethod
+{
+    Wald,
+    Wilson,
+    AgrestiCoull,
+    ClopperPearson,
+}
+
+// Known population standard deviation
+#[allow(unused)]
+pub fn get_mean_z_interval(sample: &Array1<f64>, population_std: f64, confidence_level: f64) -> Result<IntervalEstimate, String>
+{
+    if population_std <= 0.0
+    {
+        return Err(String::from("Population standard deviation must be positive!"));
+    }
+    let mean = get_mean(sample)?;
+    let margin = get_z_critical(confidence_level)? * population_std / (sample.len() as f64).sqrt();
+    Ok(IntervalEstimate { estimate: mean, confidence_interval: (mean - margin, mean + margin) })
+}
+
+#[allow(unused)]
+pub fn get_mean_t_interval(sample: &Array1<f64>, confidence_level: f64) -> Result<IntervalEstimate, String>
+{
+    check_sample_size(sample, 2)?;
+    let n = sample.len() as f64;
+    let mean = get_mean(sample)?;
+    let margin = get_t_critical(confidence_level, n - 1.0)? * (get_variance(sample)? / n).sqrt();
+    Ok(IntervalEstimate { estimate: mean, confidence_interval: (mean - margin, mean + margin) })
+}
+
+// Mean of sample_1 minus mean of sample_2, pooled variance with equal_variances, Welch otherwise
+#[allow(unused)]
+pub fn get_mean_difference_interval(sample_1: &Array1<f64>, sample_2: &Array1<f64>, equal_variances: bool, confidence_level: f64)
+-> Result<IntervalEstimate, String>
+{
+    check_sample_size(sample_1, 2)?;
+    check_sample_size(sample_2, 2)?;
+    let (n_1, n_2) = (sample_1.len() as f64, sample_2.len() as f64);
+    let (variance_1, variance_2) = (get_variance(sample_1)?, get_variance(sample_2)?);
+    let difference = get_mean(sample_1)? - get_mean(sample_2)?;
+
+    let (standard_error, degrees_of_freedom) = if equal_variances
+    {
+        let pooled_variance = ((n_1 - 1.0) * variance_1 + (n_2 - 1.0) * variance_2) / (n_1 + n_2 - 2.0);
+        ((pooled_variance * (1.0 / n_1 + 1.0 / n_2)).sqrt(), n_1 + n_2 - 2.0)
+    }
+    else
+    {
+        let (error_1, error_2) = (variance_1 / n_1, variance_2 / n_2);
+        ((error_1 + error_2).sqrt(),
+         (error_1 + error_2).powi(2) / (error_1.powi(2) / (n_1 - 1.0) + error_2.powi(2) / (n_2 - 1.0)))
+    };
+    let margin = get_t_critical(confidence_level, degrees_of_freedom)? * standard_error;
+    Ok(IntervalEstimate { estimate: difference, confidence_interval: (difference - margin, difference + margin) })
+}
+
+#[allow(unused)]
+pub fn get_proportion_interval(successes: u64, trials: u64, method: ProportionIntervalMethod, confidence_level: f64)
+-> Result<IntervalEstimate, String>
+{
+    if successes > trials || trials == 0
+    {
+        return Err(String::from("Successes must not exceed the number of trials, which must be positive!"));
+    }
+    let z = get_z_critical(confidence_level)?;
+    let (x, n) = (successes as f64, trials as f64);
+    let proportion = x / n;
+
+    let confidence_interval = match method
+    {
+        ProportionIntervalMethod::Wald =>
+        {
+            let margin = z * (proportion * (1.0 - proportion) / n).sqrt();
+            (proportion - margin, proportion + margin)
+        },
+        ProportionIntervalMethod::Wilson =>
+        {
+            let center = (proportion + z.powi(2) / (2.0 * n)) / (1.0 + z.powi(2) / n);
+            let margin = z / (1.0 + z.powi(2) / n) * (proportion * (1.0 - proportion) / n + z.powi(2) / (4.0 * n.powi(2))).sqrt();
+            (center - margin, center + margin)
+        },
+        ProportionIntervalMethod::AgrestiCoull =>
+        {
+            // Wald interval after adding z^2 / 2 successes and failures
+            let adjusted_n = n + z.powi(2);
+            let adjusted_proportion = (x + z.powi(2) / 2.0) / adjusted_n;
+            let margin = z * (adjusted_proportion * (1.0 - adjusted_proportion) / adjusted_n).sqrt();
+            (adjusted_proportion - margin, adjusted_proportion + margin)
+        },
+        ProportionIntervalMethod::ClopperPearson => get_clopper_pearson_interval(successes, trials, confidence_level)?,
+    };
+    Ok(IntervalEstimate { estimate: proportion, confidence_interval: (confidence_interval.0.max(0.0), confidence_interval.1.min(1.0)) })
+}
+
+// Assumes normal data, (n - 1) s^2 / sigma^2 follows a chi-squared distribution
+#[allow(unused)]
+pub fn get_variance_interval(sample: &Array1<f64>, confidence_level: f64) -> Result<IntervalEstimate, String>
+{
+    check_sample_size(sample, 2)?;
+    let alpha = get_alpha(confidence_level)?;
+    let degrees_of_freedom = (sample.len() - 1) as f64;
+    let variance = get_variance(sample)?;
+    let lower = degrees_of_freedom * variance / get_chi_squared_ppf(1.0 - alpha / 2.0, degrees_of_freedom)?;
+    let upper = degrees_of_freedom * variance / get_chi_squared_ppf(alpha / 2.0, degrees_of_freedom)?;
+    Ok(IntervalEstimate { estimate: variance, confidence_interval: (lower, upper) })
+}
+
+// Variance of sample_1 over variance of sample_2
+#[allow(unused)]
+pub fn get_variance_ratio_interval(sample_1: &Array1<f64>, sample_2: &Array1<f64>, confidence_level: f64) -> Result<IntervalEstimate, String>
+{
+    check_sample_size(sample_1, 2)?;
+    check_sample_size(sample_2, 2)?;
+    let alpha = get_alpha(confidence_level)?;
+    let (df_1, df_2) = ((sample_1.len() - 1) as f64, (sample_2.len() - 1) as f64);
+    let ratio = get_variance(sample_1)? / get_variance(sample_2)?;
+    let lower = ratio / get_f_ppf(1.0 - alpha / 2.0, df_1, df_2)?;
+    let upper = ratio / get_f_ppf(alpha / 2.0, df_1, df_2)?;
+    Ok(IntervalEstimate { estimate: ratio, confidence_interval: (lower, upper) })
+}
+
+// Fisher z-transform, atanh(r) is approximately normal with standard error 1 / sqrt(n - 3)
+#[allow(unused)]
+pub fn get_correlation_interval(x: &Array1<f64>, y: &Array1<f64>, confidence_level: f64) -> Result<IntervalEstimate, String>
+{
+    if x.len() != y.len()
+    {
+        return Err(String::from("Vector lengths do not match!"));
+    }
+    check_sample_size(x, 4)?;
+    let correlation = get_correlation_coefficient(x, y)?;
+    let margin = get_z_critical(confidence_level)? / (x.len() as f64 - 3.0).sqrt();
+    let z = correlation.atanh();
+    Ok(IntervalEstimate { estimate: correlation, confidence_interval: ((z - margin).tanh(), (z + margin).tanh()) })
+}
+
+// Distribution-free interval between the order statistics x_(k) and x_(n + 1 - k),
+// k is the largest index whose coverage 1 - 2 P(Bin(n, 1/2) < k) still reaches the confidence level
+#[allow(unused)]
+pub fn get_median_interval(sample: &Array1<f64>, confidence_level: f64) -> Result<IntervalEstimate, String>
+{
+    let alpha = get_alpha(confidence_level)?;
+    let sample = check_vector_for_nans(sample)?;
+    let mut sorted = sample.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = sorted.len();
+    if n == 0
+    {
+        return Err(String::from("Vector cannot be empty"));
+    }
+
+    let mut lower_tail = 0.0;
+    let mut k = 0;
+    while k < n / 2
+    {
+        lower_tail += get_binomial_ln_pmf(k as u64, n as u64, 0.5)?.exp();
+        if lower_tail > alpha / 2.0
+        {
+            break;
+        }
+        k += 1;
+    }
+    if k == 0
+    {
+        return Err(String::from("Sample is too small for this confidence level!"));
+    }
+    let median = if n % 2 == 1 { sorted[n / 2] } else { (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0 };
+    Ok(IntervalEstimate { estimate: median, confidence_interval: (sorted[k - 1], sorted[n - k]) })
+}
+
+fn get_alpha(confidence_level: f64) -> Result<f64, String>
+{
+    if confidence_level <= 0.0 || confidence_level >= 1.0
+    {
+        return Err(String::from("Confidence level must be between 0 and 1!"));
+    }
+    Ok(1.0 - confidence_level)
+}
+
+fn get_z_critical(confidence_level: f64) -> Result<f64, String>
+{
+    get_normal_ppf(1.0 - get_alpha(confidence_level)? / 2.0)
+}
+
+fn get_t_critical(confidence_level: f64, degrees_of_freedom: f64) -> Result<f64, String>
+{
+    get_t_ppf(1.0 - get_alpha(confidence_level)? / 2.0, degrees_of_freedom)
+}
+
+fn check_sample_size(sample: &Array1<f64>, minimum: usize) -> Result<(), String>
+{
+    check_vector_for_nans(sample)?;
+    if sample.len() < minimum
+    {
+        return Err(format!("Need at least {} observations!", minimum));
+    }
+    Ok(())
+}
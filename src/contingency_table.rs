@@ -0,0 +1,281 @@
+use super::distributions::get_chi_squared_sf;
+use super::hypothesis_testing::TestResult;
+use super::utilities::check_vector_for_nans;
+use ndarray::{Array1, Array2, Axis};
+
+const MINIMUM_EXPECTED_COUNT: f64 = 5.0;
+const PROPORTION_SUM_TOLERANCE: f64 = 1e-6;
+
+#[allow(unused)]
+#[derive(Debug)]
+pub struct IndependenceTestResult
+{
+    pub test_result: TestResult,
+    pub expected_counts: Array2<f64>,
+    pub warnings: Vec<String>,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct ContingencyTable
+{
+    counts: Array2<f64>,
+    row_labels: Vec<String>,
+    column_labels: Vec<String>,
+}
+
+#[allow(unused)]
+impl ContingencyTable
+{
+    pub fn new(counts: Array2<f64>) -> Result<Self, String>
+    {
+        if counts.nrows() < 2 || counts.ncols() < 2
+        {
+            return Err(String::from("Contingency table needs at least two rows and two columns!"));
+        }
+        if counts.iter().any(|&count| count < 0.0 || count.is_nan())
+        {
+            return Err(String::from("Counts must not be negative!"));
+        }
+        let row_labels = (0..counts.nrows()).map(|i| i.to_string()).collect();
+        let column_labels = (0..counts.ncols()).map(|j| j.to_string()).collect();
+        Ok(ContingencyTable { counts, row_labels, column_labels })
+    }
+
+    // Cross-tabulates two label columns, levels are sorted and labels that cannot be ordered, such as NaN, are rejected
+    pub fn from_labels<T>(row_variable: &[T], column_variable: &[T]) -> Result<Self, String>
+    where T: PartialOrd + Clone + std::fmt::Display
+    {
+        if row_variable.len() != column_variable.len()
+        {
+            return Err(String::from("Label columns must have the same length!"));
+        }
+        let row_levels = get_levels(row_variable)?;
+        let column_levels = get_levels(column_variable)?;
+        let mut counts = Array2::<f64>::zeros((row_levels.len(), column_levels.len()));
+        for (row_label, column_label) in row_variable.iter().zip(column_variable)
+        {
+            let i = row_levels.iter().position(|level| level == row_label);
+            let j = column_levels.iter().position(|level| level == column_label);
+            match (i, j)
+            {
+                (Some(i), Some(j)) => counts[[i, j]] += 1.0,
+                _ => return Err(String::from("Labels must be comparable to themselves!")),
+            }
+        }
+
+        let mut table = ContingencyTable::new(counts)?;
+        table.row_labels = row_levels.iter().map(|level| level.to_string()).collect();
+        table.column_labels = column_levels.iter().map(|level| level.to_string()).collect();
+        Ok(table)
+    }
+
+    pub fn get_counts(&self) -> &Array2<f64>
+    {
+        &self.counts
+    }
+
+    pub fn get_row_labels(&self) -> &[String]
+    {
+        &self.row_labels
+    }
+
+    pub fn get_column_labels(&self) -> &[String]
+    {
+        &self.column_labels
+    }
+
+    pub fn get_total(&self) -> f64
+    {
+        self.counts.sum()
+    }
+
+    pub fn get_degrees_of_freedom(&self) -> f64
+    {
+        ((self.counts.nrows() - 1) * (self.counts.ncols() - 1)) as f64
+    }
+
+    // Row total times column total over grand total
+    pub fn get_expected_counts(&self) -> Array2<f64>
+    {
+        let row_totals = self.counts.sum_axis(Axis(1));
+        let column_totals = self.counts.sum_axis(Axis(0));
+        let total = self.get_total();
+        Array2::from_shape_fn(self.counts.dim(), |(i, j)| row_totals[i] * column_totals[j] / total)
+    }
+
+    pub fn get_small_cell_warnings(&self) -> Vec<String>
+    {
+        let expected_counts = self.get_expected_counts();
+        let num_cells = expected_counts.len() as f64;
+        let num_small = expected_counts.iter().filter(|&&e| e < MINIMUM_EXPECTED_COUNT).count();
+        let mut warnings = Vec::new();
+        if expected_counts.iter().any(|&e| e < 1.0)
+        {
+            warnings.push(String::from("Some expected counts are below 1, the chi-squared approximation is unreliable!"));
+        }
+        if num_small as f64 / num_cells > 0.2
+        {
+            warnings.push(format!("{:.0}% of expected counts are below 5, consider an exact test!", 100.0 * num_small as f64 / num_cells));
+        }
+        warnings
+    }
+
+    // Yates' continuity correction is only applied to tables with one degree of freedom
+    pub fn get_chi_squared_test(&self, yates_correction: bool) -> Result<IndependenceTestResult, String>
+    {
+        self.check_total()?;
+        let expected_counts = self.get_expected_counts();
+        let observed = self.get_corrected_counts(&expected_counts, yates_correction);
+        let statistic: f64 = observed.iter()
+            .zip(expected_counts.iter())
+            .map(|(o, e)| (o - e).powi(2) / e)
+            .sum();
+        self.get_independence_test_result("Pearson chi-squared", statistic, expected_counts)
+    }
+
+    // Likelihood ratio test, G = 2 * sum(O * ln(O / E))
+    pub fn get_g_test(&self, yates_correction: bool) -> Result<IndependenceTestResult, String>
+    {
+        self.check_total()?;
+        let expected_counts = self.get_expected_counts();
+        let observed = self.get_corrected_counts(&expected_counts, yates_correction);
+        let statistic: f64 = 2.0 * observed.iter()
+            .zip(expected_counts.iter())
+            .map(|(&o, &e)| if o == 0.0 { 0.0 } else { o * (o / e).ln() })
+            .sum::<f64>();
+        self.get_independence_test_result("G-test", statistic, expected_counts)
+    }
+
+    pub fn get_cramers_v(&self) -> Result<f64, String>
+    {
+        let chi_squared = self.get_chi_squared_test(false)?.test_result.statistic;
+        let min_dimension = self.counts.nrows().min(self.counts.ncols()) as f64;
+        Ok((chi_squared / (self.get_total() * (min_dimension - 1.0))).sqrt())
+    }
+
+    pub fn get_phi(&self) -> Result<f64, String>
+    {
+        let chi_squared = self.get_chi_squared_test(false)?.test_result.statistic;
+        Ok((chi_squared / self.get_total()).sqrt())
+    }
+
+    pub fn get_contingency_coefficient(&self) -> Result<f64, String>
+    {
+        let chi_squared = self.get_chi_squared_test(false)?.test_result.statistic;
+        Ok((chi_squared / (chi_squared + self.get_total())).sqrt())
+    }
+
+    fn check_total(&self) -> Result<(), String>
+    {
+        let expected_counts = self.get_expected_counts();
+        if expected_counts.iter().any(|&e| e <= 0.0 || e.is_nan())
+        {
+            return Err(String::from("Every row and column needs at least one observation!"));
+        }
+        Ok(())
+    }
+
+    fn get_corrected_counts(&self, expected_counts: &Array2<f64>, yates_correction: bool) -> Array2<f64>
+    {
+        if !yates_correction || self.get_degrees_of_freedom() != 1.0
+        {
+            return self.counts.to_owned();
+        }
+        // Moves every observed count half a unit towards its expectation
+        Array2::from_shape_fn(self.counts.dim(), |index|
+        {
+            let difference = self.counts[index] - expected_counts[index];
+            let correction = difference.abs().min(0.5);
+            self.counts[index] - correction * difference.signum()
+        })
+    }
+
+    fn get_independence_test_result(&self, test_name: &str, statistic: f64, expected_counts: Array2<f64>) -> Result<IndependenceTestResult, String>
+    {
+        let degrees_of_freedom = self.get_degrees_of_freedom();
+        Ok
+        (
+            IndependenceTestResult
+            {
+                test_result: TestResult::new(test_name, statistic, vec![degrees_of_freedom], get_chi_squared_sf(statistic, degrees_of_freedom)?),
+                expected_counts,
+                warnings: self.get_small_cell_warnings(),
+            }
+        )
+    }
+}
+
+fn get_levels<T>(labels: &[T]) -> Result<Vec<T>, String>
+where T: PartialOrd + Clone
+{
+    if labels.iter().any(|label| label.partial_cmp(label).is_none())
+    {
+        return Err(String::from("Labels must be comparable to themselves!"));
+    }
+    let mut levels = labels.to_vec();
+    let mut is_ordered = true;
+    levels.sort_by(|a, b| a.partial_cmp(b).unwrap_or_else(|| { is_ordered = false; std::cmp::Ordering::Equal }));
+    if !is_ordered
+    {
+        return Err(String::from("Labels must be totally ordered!"));
+    }
+    levels.dedup_by(|a, b| a == b);
+    Ok(levels)
+}
+
+// num_estimated_parameters reduces the degrees of freedom when the proportions come from a fitted model
+#[allow(unused)]
+pub fn get_chi_squared_goodness_of_fit(observed: &Array1<f64>, expected_proportions: &Array1<f64>, num_estimated_parameters: usize)
+-> Result<TestResult, String>
+{
+    let observed = check_vector_for_nans(observed)?;
+    let expected_proportions = check_vector_for_nans(expected_proportions)?;
+    if observed.len() != expected_proportions.len()
+    {
+        return Err(String::from("Observed counts and expected proportions must have the same length!"));
+    }
+    if observed.iter().any(|&o| o < 0.0) || expected_proportions.iter().any(|&p| p <= 0.0)
+    {
+        return Err(String::from("Counts must not be negative and proportions must be positive!"));
+    }
+    if (expected_proportions.sum() - 1.0).abs() > PROPORTION_SUM_TOLERANCE
+    {
+        return Err(String::from("Expected proportions must sum to one!"));
+    }
+    if observed.len() <= num_estimated_parameters + 1
+    {
+        return Err(String::from("Not enough categories for the number of estimated parameters!"));
+    }
+
+    let expected = expected_proportions * observed.sum();
+    let statistic: f64 = observed.iter().zip(expected.iter()).map(|(o, e)| (o - e).powi(2) / e).sum();
+    let degrees_of_freedom = (observed.len() - 1 - num_estimated_parameters) as f64;
+    Ok(TestResult::new("Chi-squared goodness of fit", statistic, vec![degrees_of_freedom], get_chi_squared_sf(statistic, degrees_of_freedom)?))
+}
+
+// Bins the data at the given interior edges and compares the counts with the probabilities of a fitted cdf
+#[allow(unused)]
+pub fn get_chi_squared_goodness_of_fit_to_distribution<F>(data: &Array1<f64>, bin_edges: &[f64], cdf: F, num_estimated_parameters: usize)
+-> Result<TestResult, String>
+where F: Fn(f64) -> f64
+{
+    let data = check_vector_for_nans(data)?;
+    if bin_edges.windows(2).any(|edges| edges[0] >= edges[1])
+    {
+        return Err(String::from("Bin edges must be strictly increasing!"));
+    }
+    let num_bins = bin_edges.len() + 1;
+    let mut observed = Array1::<f64>::zeros(num_bins);
+    for &x in data.iter()
+    {
+        let bin = bin_edges.iter().take_while(|&&edge| x >= edge).count();
+        observed[bin] += 1.0;
+    }
+
+    let mut cumulative_probabilities = vec![0.0];
+    cumulative_probabilities.extend(bin_edges.iter().map(|&edge| cdf(edge)));
+    cumulative_probabilities.push(1.0);
+    let expected_proportions: Array1<f64> = cumulative_probabilities.windows(2).map(|p| p[1] - p[0]).collect();
+    get_chi_squared_goodness_of_fit(&observed, &expected_proportions, num_estimated_parameters)
+}
@@ -1,27 +1,522 @@
-use super::utilities::get_factorial;
+use super::big_integer::BigUint;
+use super::distributions::get_ln_gamma;
 
+// Multiplicative formula, C(n, i) = C(n, i - 1) * (n - i + 1) / i stays an integer at every step
 #[allow(unused)]
-pub fn get_combinations(n: u64, r: u64) -> Result<u128, String> 
+pub fn get_combinations(n: u64, r: u64) -> Result<u128, String>
 {
-    if n <= 0 || r <= 0
+    check_r_not_greater_than_n(n, r)?;
+    let r = r.min(n - r);
+    let mut result: u128 = 1;
+    for i in 1..=r as u128
     {
-        Err(String::from ("n and r must be equal or greater than 0!"))
+        // Dividing by the gcd first keeps the intermediate product as small as possible
+        let divisor = get_gcd(result, i);
+        let factor = (n as u128 - r as u128 + i) / (i / divisor);
+        result = (result / divisor).checked_mul(factor).ok_or_else(get_overflow_error)?;
     }
-    else
+    Ok(result)
+}
+
+#[allow(unused)]
+pub fn get_permutations(n: u64, r: u64) -> Result<u128, String>
+{
+    check_r_not_greater_than_n(n, r)?;
+    let mut result: u128 = 1;
+    for i in n - r + 1..=n
     {
-        Ok(get_factorial(n) / (get_factorial(r) * get_factorial(n - r)))
+        result = result.checked_mul(i as u128).ok_or_else(get_overflow_error)?;
     }
+    Ok(result)
 }
 
+// ln(n choose r) via the log-gamma function, never overflows
 #[allow(unused)]
-pub fn get_permutations(n: u64, r: u64) -> Result<u128, String> 
+pub fn get_ln_combinations(n: u64, r: u64) -> Result<f64, String>
 {
-    if n <= 0 || r <= 0
+    if r > n
     {
-        Err(String::from("n and r must be equal or greater than 0!"))
+        Err(String::from("r must not be greater than n!"))
     }
     else
     {
-        Ok(get_factorial(n) / get_factorial(n - r))
+        Ok(get_ln_factorial(n) - get_ln_factorial(r) - get_ln_factorial(n - r))
+    }
+}
+
+#[allow(unused)]
+pub fn get_ln_factorial(n: u64) -> f64
+{
+    get_ln_gamma(n as f64 + 1.0)
+}
+
+#[allow(unused)]
+pub fn get_checked_factorial(n: u64) -> Result<u128, String>
+{
+    get_permutations(n, n)
+}
+
+#[allow(unused)]
+pub fn get_big_factorial(n: u64) -> BigUint
+{
+    get_big_permutations(n, n).unwrap()
+}
+
+#[allow(unused)]
+pub fn get_big_permutations(n: u64, r: u64) -> Result<BigUint, String>
+{
+    check_r_not_greater_than_n(n, r)?;
+    let mut result = BigUint::one();
+    for i in n - r + 1..=n
+    {
+        result.multiply_small(i);
+    }
+    Ok(result)
+}
+
+#[allow(unused)]
+pub fn get_big_combinations(n: u64, r: u64) -> Result<BigUint, String>
+{
+    check_r_not_greater_than_n(n, r)?;
+    let r = r.min(n - r);
+    let mut result = BigUint::one();
+    for i in 1..=r
+    {
+        result.multiply_small(n - r + i);
+        result.divide_small(i)?;
+    }
+    Ok(result)
+}
+
+// n! / (k_1! k_2! ... k_m!) with n = k_1 + ... + k_m, as a product of binomial coefficients
+#[allow(unused)]
+pub fn get_multinomial(counts: &[u64]) -> Result<u128, String>
+{
+    let mut result: u128 = 1;
+    let mut total: u64 = 0;
+    for &count in counts
+    {
+        total = total.checked_add(count).ok_or_else(get_overflow_error)?;
+        result = result.checked_mul(get_combinations(total, count)?).ok_or_else(get_overflow_error)?;
+    }
+    Ok(result)
+}
+
+#[allow(unused)]
+pub fn get_ln_multinomial(counts: &[u64]) -> f64
+{
+    let total: u64 = counts.iter().sum();
+    get_ln_factorial(total) - counts.iter().map(|&count| get_ln_factorial(count)).sum::<f64>()
+}
+
+// Unsigned Stirling numbers of the first kind, permutations of n elements with k cycles
+#[allow(unused)]
+pub fn get_stirling_first_kind(n: u64, k: u64) -> Result<u128, String>
+{
+    // c(i, j) = c(i - 1, j - 1) + (i - 1) c(i - 1, j)
+    get_triangle_entry(n, k, |i, _| (i - 1) as u128)
+}
+
+// Stirling numbers of the second kind, partitions of n elements into k non-empty blocks
+#[allow(unused)]
+pub fn get_stirling_second_kind(n: u64, k: u64) -> Result<u128, String>
+{
+    // S(i, j) = S(i - 1, j - 1) + j S(i - 1, j)
+    get_triangle_entry(n, k, |_, j| j as u128)
+}
+
+// Number of partitions of a set with n elements, from the Bell triangle
+#[allow(unused)]
+pub fn get_bell_number(n: u64) -> Result<u128, String>
+{
+    let mut row: Vec<u128> = vec![1];
+    for _ in 0..n
+    {
+        let mut next = Vec::with_capacity(row.len() + 1);
+        next.push(*row.last().unwrap());
+        for value in &row
+        {
+            let sum = next.last().unwrap().checked_add(*value).ok_or_else(get_overflow_error)?;
+            next.push(sum);
+        }
+        row = next;
+    }
+    Ok(row[0])
+}
+
+// Permutations without fixed points, !n = (n - 1) (!(n - 1) + !(n - 2))
+#[allow(unused)]
+pub fn get_derangements(n: u64) -> Result<u128, String>
+{
+    let (mut previous, mut current): (u128, u128) = (1, 0);
+    if n == 0
+    {
+        return Ok(previous);
+    }
+    for i in 2..=n as u128
+    {
+        let next = (i - 1).checked_mul(current.checked_add(previous).ok_or_else(get_overflow_error)?).ok_or_else(get_overflow_error)?;
+        previous = current;
+        current = next;
+    }
+    Ok(current)
+}
+
+// C(2n, n) / (n + 1)
+#[allow(unused)]
+pub fn get_catalan_number(n: u64) -> Result<u128, String>
+{
+    let mut big = get_big_combinations(2 * n, n)?;
+    big.divide_small(n + 1)?;
+    big.to_u128().ok_or_else(get_overflow_error)
+}
+
+// Lexicographic k-combinations of the indices 0..n
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct CombinationIterator
+{
+    n: usize,
+    next: Option<Vec<usize>>,
+}
+
+#[allow(unused)]
+impl CombinationIterator
+{
+    pub fn new(n: usize, k: usize) -> Self
+    {
+        CombinationIterator { n, next: if k <= n { Some((0..k).collect()) } else { None } }
+    }
+
+    // Starts at the combination with the given lexicographic rank, to split work into chunks
+    pub fn from_rank(n: usize, k: usize, rank: u128) -> Result<Self, String>
+    {
+        Ok(CombinationIterator { n, next: Some(get_combination_from_rank(n, k, rank)?) })
+    }
+}
+
+impl Iterator for CombinationIterator
+{
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>>
+    {
+        let current = self.next.take()?;
+        let k = current.len();
+        // Rightmost position that can still be increased
+        if let Some(i) = (0..k).rev().find(|&i| current[i] < self.n - k + i)
+        {
+            let mut next = current.clone();
+            next[i] += 1;
+            for j in i + 1..k
+            {
+                next[j] = next[j - 1] + 1;
+            }
+            self.next = Some(next);
+        }
+        Some(current)
+    }
+}
+
+// Lexicographic k-permutations, ordered selections of k distinct indices from 0..n
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct PermutationIterator
+{
+    n: usize,
+    next: Option<Vec<usize>>,
+}
+
+#[allow(unused)]
+impl PermutationIterator
+{
+    pub fn new(n: usize, k: usize) -> Self
+    {
+        PermutationIterator { n, next: if k <= n { Some((0..k).collect()) } else { None } }
+    }
+
+    pub fn from_rank(n: usize, k: usize, rank: u128) -> Result<Self, String>
+    {
+        Ok(PermutationIterator { n, next: Some(get_permutation_from_rank(n, k, rank)?) })
+    }
+}
+
+impl Iterator for PermutationIterator
+{
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>>
+    {
+        let current = self.next.take()?;
+        let k = current.len();
+        let mut used = vec![false; self.n];
+        current.iter().for_each(|&index| used[index] = true);
+        // Free the positions from the right until one of them can take a larger unused index
+        for i in (0..k).rev()
+        {
+            used[current[i]] = false;
+            if let Some(value) = (current[i] + 1..self.n).find(|&value| !used[value])
+            {
+                let mut next = current[..i].to_vec();
+                next.push(value);
+                used[value] = true;
+                next.extend((0..self.n).filter(|&value| !used[value]).take(k - i - 1));
+                self.next = Some(next);
+                break;
+            }
+        }
+        Some(current)
+    }
+}
+
+// All subsets of 0..n, by increasing size and lexicographically within a size
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct PowerSetIterator
+{
+    n: usize,
+    size: usize,
+    combinations: CombinationIterator,
+}
+
+#[allow(unused)]
+impl PowerSetIterator
+{
+    pub fn new(n: usize) -> Self
+    {
+        PowerSetIterator { n, size: 0, combinations: CombinationIterator::new(n, 0) }
+    }
+}
+
+impl Iterator for PowerSetIterator
+{
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>>
+    {
+        loop
+        {
+            if let Some(subset) = self.combinations.next()
+            {
+                return Some(subset);
+            }
+            if self.size >= self.n
+            {
+                return None;
+            }
+            self.size += 1;
+            self.combinations = CombinationIterator::new(self.n, self.size);
+        }
     }
-}
\ No newline at end of file
+}
+
+// Lexicographic non-decreasing sequences of length k from 0..n, i.e. multisets
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct MultisetCombinationIterator
+{
+    n: usize,
+    next: Option<Vec<usize>>,
+}
+
+#[allow(unused)]
+impl MultisetCombinationIterator
+{
+    pub fn new(n: usize, k: usize) -> Self
+    {
+        MultisetCombinationIterator { n, next: if n > 0 || k == 0 { Some(vec![0; k]) } else { None } }
+    }
+}
+
+impl Iterator for MultisetCombinationIterator
+{
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>>
+    {
+        let current = self.next.take()?;
+        if let Some(i) = (0..current.len()).rev().find(|&i| current[i] + 1 < self.n)
+        {
+            let mut next = current.clone();
+            let value = next[i] + 1;
+            next[i..].iter_mut().for_each(|index| *index = value);
+            self.next = Some(next);
+        }
+        Some(current)
+    }
+}
+
+// Index tuples of the Cartesian product 0..sizes[0] x 0..sizes[1] x ..., last position changes fastest
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct CartesianProductIterator
+{
+    sizes: Vec<usize>,
+    next: Option<Vec<usize>>,
+}
+
+#[allow(unused)]
+impl CartesianProductIterator
+{
+    pub fn new(sizes: &[usize]) -> Self
+    {
+        let next = if sizes.iter().all(|&size| size > 0) { Some(vec![0; sizes.len()]) } else { None };
+        CartesianProductIterator { sizes: sizes.to_vec(), next }
+    }
+}
+
+impl Iterator for CartesianProductIterator
+{
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>>
+    {
+        let current = self.next.take()?;
+        if let Some(i) = (0..current.len()).rev().find(|&i| current[i] + 1 < self.sizes[i])
+        {
+            let mut next = current.clone();
+            next[i] += 1;
+            next[i + 1..].iter_mut().for_each(|index| *index = 0);
+            self.next = Some(next);
+        }
+        Some(current)
+    }
+}
+
+// Position of a strictly increasing combination in the lexicographic order of CombinationIterator,
+// C(n, k) - 1 minus the combinadic of the complement
+#[allow(unused)]
+pub fn get_combination_rank(combination: &[usize], n: usize) -> Result<u128, String>
+{
+    let k = combination.len();
+    if combination.windows(2).any(|pair| pair[0] >= pair[1]) || combination.iter().any(|&index| index >= n)
+    {
+        return Err(String::from("Combination must be strictly increasing with indices below n!"));
+    }
+    let mut rank = get_combinations(n as u64, k as u64)? - 1;
+    for (i, &index) in combination.iter().enumerate()
+    {
+        if n - 1 - index >= k - i
+        {
+            rank -= get_combinations((n - 1 - index) as u64, (k - i) as u64)?;
+        }
+    }
+    Ok(rank)
+}
+
+#[allow(unused)]
+pub fn get_combination_from_rank(n: usize, k: usize, rank: u128) -> Result<Vec<usize>, String>
+{
+    if rank >= get_combinations(n as u64, k as u64)?
+    {
+        return Err(String::from("Rank must be smaller than the number of combinations!"));
+    }
+    let mut rank = rank;
+    let mut combination = Vec::with_capacity(k);
+    let mut value = 0;
+    for i in 0..k
+    {
+        // Skip all blocks of combinations that start with a smaller index at this position
+        loop
+        {
+            let block = get_combinations((n - 1 - value) as u64, (k - 1 - i) as u64)?;
+            if rank < block
+            {
+                break;
+            }
+            rank -= block;
+            value += 1;
+        }
+        combination.push(value);
+        value += 1;
+    }
+    Ok(combination)
+}
+
+// Position of a k-permutation in the lexicographic order of PermutationIterator
+#[allow(unused)]
+pub fn get_permutation_rank(permutation: &[usize], n: usize) -> Result<u128, String>
+{
+    let k = permutation.len();
+    let mut used = vec![false; n];
+    let mut rank: u128 = 0;
+    for (i, &index) in permutation.iter().enumerate()
+    {
+        if index >= n || used[index]
+        {
+            return Err(String::from("Permutation must consist of distinct indices below n!"));
+        }
+        let num_smaller = used[..index].iter().filter(|&&is_used| !is_used).count() as u128;
+        let block = get_permutations((n - 1 - i) as u64, (k - 1 - i) as u64)?;
+        rank = num_smaller.checked_mul(block).and_then(|value| value.checked_add(rank)).ok_or_else(get_overflow_error)?;
+        used[index] = true;
+    }
+    Ok(rank)
+}
+
+#[allow(unused)]
+pub fn get_permutation_from_rank(n: usize, k: usize, rank: u128) -> Result<Vec<usize>, String>
+{
+    if rank >= get_permutations(n as u64, k as u64)?
+    {
+        return Err(String::from("Rank must be smaller than the number of permutations!"));
+    }
+    let mut rank = rank;
+    let mut unused: Vec<usize> = (0..n).collect();
+    let mut permutation = Vec::with_capacity(k);
+    for i in 0..k
+    {
+        let block = get_permutations((n - 1 - i) as u64, (k - 1 - i) as u64)?;
+        permutation.push(unused.remove((rank / block) as usize));
+        rank %= block;
+    }
+    Ok(permutation)
+}
+
+// Fills the triangle row by row up to row n, entries beyond column k are not needed
+fn get_triangle_entry<F>(n: u64, k: u64, weight: F) -> Result<u128, String>
+where F: Fn(u64, u64) -> u128
+{
+    if k > n
+    {
+        return Ok(0);
+    }
+    let mut row: Vec<u128> = vec![1];
+    for i in 1..=n
+    {
+        let width = (i.min(k) + 1) as usize;
+        let mut next = vec![0u128; width];
+        for j in 1..width
+        {
+            let kept = row.get(j).copied().unwrap_or(0).checked_mul(weight(i, j as u64)).ok_or_else(get_overflow_error)?;
+            next[j] = row[j - 1].checked_add(kept).ok_or_else(get_overflow_error)?;
+        }
+        row = next;
+    }
+    Ok(row[k as usize])
+}
+
+fn check_r_not_greater_than_n(n: u64, r: u64) -> Result<(), String>
+{
+    if r > n
+    {
+        return Err(String::from("r must not be greater than n!"));
+    }
+    Ok(())
+}
+
+fn get_overflow_error() -> String
+{
+    String::from("Result overflows u128, use the big integer or log-space version!")
+}
+
+fn get_gcd(mut a: u128, mut b: u128) -> u128
+{
+    while b != 0
+    {
+        let remainder = a % b;
+        a = b;
+        b = remainder;
+    }
+    a
+}
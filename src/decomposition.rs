@@ -0,0 +1,246 @@
+use super::smoothing::get_centered_moving_average;
+use super::time_series::TimeSeries;
+use super::utilities::{get_median, get_variance};
+use ndarray::Array1;
+
+const ROBUST_OUTER_ITERATIONS: usize = 15;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecompositionKind
+{
+    // series = trend + seasonal + remainder
+    Additive,
+    // series = trend * seasonal * remainder
+    Multiplicative,
+}
+
+// Components are aligned with the series, the classical trend is NaN where the moving average is undefined.
+// Seasonal strength max(0, 1 - Var(remainder) / Var(seasonal + remainder)) lies in [0, 1]
+// (Wang, Smith and Hyndman, 2006), with products instead of sums for multiplicative decompositions
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct Decomposition
+{
+    pub trend: Array1<f64>,
+    pub seasonal: Array1<f64>,
+    pub remainder: Array1<f64>,
+    pub seasonal_strength: f64,
+}
+
+// Seasonal-trend decomposition by LOESS (Cleveland et al., 1990) with local linear fits,
+// windows are odd numbers of observations
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct Stl
+{
+    period: usize,
+    seasonal_window: usize,
+    trend_window: usize,
+    low_pass_window: usize,
+    robust: bool,
+}
+
+// Moving average trend, seasonal figure from the phase means of the detrended series (as R's decompose)
+#[allow(unused)]
+pub fn get_classical_decomposition(series: &TimeSeries, period: usize, kind: DecompositionKind) -> Result<Decomposition, String>
+{
+    let values = series.get_values();
+    let n = values.len();
+    if period < 2 || n < 2 * period
+    {
+        return Err(String::from("Need a period of at least 2 and two full periods!"));
+    }
+    if kind == DecompositionKind::Multiplicative && values.iter().any(|&value| value <= 0.0)
+    {
+        return Err(String::from("Multiplicative decomposition needs positive values!"));
+    }
+
+    let moving_average = get_centered_moving_average(&TimeSeries::from_values(values.clone())?, period)?;
+    let offset = (n - moving_average.len()) / 2;
+    let mut trend = Array1::from_elem(n, f64::NAN);
+    trend.slice_mut(ndarray::s![offset..offset + moving_average.len()]).assign(moving_average.get_values());
+
+    let combine = |a: f64, b: f64| if kind == DecompositionKind::Additive { a + b } else { a * b };
+    let separate = |a: f64, b: f64| if kind == DecompositionKind::Additive { a - b } else { a / b };
+    let mut figure = vec![0.0; period];
+    for (phase, value) in figure.iter_mut().enumerate()
+    {
+        let detrended: Vec<f64> = (phase..n).step_by(period).map(|t| separate(values[t], trend[t])).filter(|d| !d.is_nan()).collect();
+        *value = detrended.iter().sum::<f64>() / detrended.len() as f64;
+    }
+    // Seasonal effects sum to zero, or average one when multiplicative
+    let figure_mean = figure.iter().sum::<f64>() / period as f64;
+    figure.iter_mut().for_each(|value| *value = separate(*value, figure_mean));
+
+    let seasonal: Array1<f64> = (0..n).map(|t| figure[t % period]).collect();
+    let remainder: Array1<f64> = (0..n).map(|t| separate(separate(values[t], trend[t]), seasonal[t])).collect();
+    let seasonal_strength = get_seasonal_strength(&seasonal, &remainder, combine)?;
+    Ok(Decomposition { trend, seasonal, remainder, seasonal_strength })
+}
+
+#[allow(unused)]
+impl Stl
+{
+    // Defaults from R's stl: trend window nextodd(1.5 period / (1 - 1.5 / seasonal window)),
+    // low-pass window nextodd(period), no robustness iterations
+    pub fn new(period: usize, seasonal_window: usize) -> Result<Self, String>
+    {
+        if period < 2
+        {
+            return Err(String::from("Seasonal period must be at least 2!"));
+        }
+        if seasonal_window < 3 || seasonal_window.is_multiple_of(2)
+        {
+            return Err(String::from("Seasonal window must be odd and at least 3!"));
+        }
+        let trend_window = get_next_odd((1.5 * period as f64 / (1.0 - 1.5 / seasonal_window as f64)).ceil() as usize);
+        Ok(Stl { period, seasonal_window, trend_window, low_pass_window: get_next_odd(period), robust: false })
+    }
+
+    pub fn with_trend_window(mut self, trend_window: usize) -> Result<Self, String>
+    {
+        if trend_window < 3 || trend_window.is_multiple_of(2)
+        {
+            return Err(String::from("Trend window must be odd and at least 3!"));
+        }
+        self.trend_window = trend_window;
+        Ok(self)
+    }
+
+    // Bisquare weights of the remainder downweight outliers in 15 outer iterations
+    pub fn with_robust_iterations(mut self, robust: bool) -> Self
+    {
+        self.robust = robust;
+        self
+    }
+
+    pub fn decompose(&self, series: &TimeSeries) -> Result<Decomposition, String>
+    {
+        let values = series.get_values();
+        let n = values.len();
+        let period = self.period;
+        if n < 2 * period
+        {
+            return Err(String::from("Need at least two full periods!"));
+        }
+        let (inner_iterations, outer_iterations) = if self.robust { (1, ROBUST_OUTER_ITERATIONS) } else { (2, 0) };
+
+        let mut trend = Array1::<f64>::zeros(n);
+        let mut seasonal = Array1::<f64>::zeros(n);
+        let mut robustness_weights = Array1::<f64>::ones(n);
+        for outer in 0..=outer_iterations
+        {
+            for _ in 0..inner_iterations
+            {
+                // Cycle-subseries smoothing, extended by one period at both ends
+                let detrended = values - &trend;
+                let mut cycle = vec![0.0; n + 2 * period];
+                for phase in 0..period
+                {
+                    let positions: Vec<usize> = (phase..n).step_by(period).collect();
+                    let subseries: Vec<f64> = positions.iter().map(|&t| detrended[t]).collect();
+                    let weights: Vec<f64> = positions.iter().map(|&t| robustness_weights[t]).collect();
+                    for k in 0..positions.len() + 2
+                    {
+                        cycle[phase + k * period] = get_loess_value(&subseries, &weights, k as f64 - 1.0, self.seasonal_window);
+                    }
+                }
+
+                // Low-pass filter of the cycle-subseries removes what belongs to the trend
+                let low_pass = get_moving_average(&get_moving_average(&get_moving_average(&cycle, period), period), 3);
+                let ones = vec![1.0; n];
+                for t in 0..n
+                {
+                    seasonal[t] = cycle[period + t] - get_loess_value(&low_pass, &ones, t as f64, self.low_pass_window);
+                }
+
+                let deseasonalized = (values - &seasonal).to_vec();
+                let weights = robustness_weights.to_vec();
+                trend = (0..n).map(|t| get_loess_value(&deseasonalized, &weights, t as f64, self.trend_window)).collect();
+            }
+            if outer < outer_iterations
+            {
+                robustness_weights = get_robustness_weights(&(values - &trend - &seasonal))?;
+            }
+        }
+
+        let remainder = values - &trend - &seasonal;
+        let seasonal_strength = get_seasonal_strength(&seasonal, &remainder, |a, b| a + b)?;
+        Ok(Decomposition { trend, seasonal, remainder, seasonal_strength })
+    }
+}
+
+// Local linear fit at position x0 with tricube weights over the window nearest observations at 0, 1, 2, ...,
+// windows longer than the series widen the bandwidth beyond the farthest observation
+fn get_loess_value(values: &[f64], robustness_weights: &[f64], x0: f64, window: usize) -> f64
+{
+    let n = values.len();
+    let (left, right, bandwidth) = if window >= n
+    {
+        (0, n - 1, x0.max(n as f64 - 1.0 - x0) + ((window - n) / 2) as f64)
+    }
+    else
+    {
+        let left = (x0.round() as i64 - (window as i64 - 1) / 2).clamp(0, (n - window) as i64) as usize;
+        let right = left + window - 1;
+        (left, right, (x0 - left as f64).max(right as f64 - x0))
+    };
+    if bandwidth <= 0.0
+    {
+        return values[x0.round() as usize];
+    }
+
+    let weights: Vec<f64> = (left..=right).map(|j|
+    {
+        let distance = (j as f64 - x0).abs() / bandwidth;
+        if distance < 1.0 { (1.0 - distance.powi(3)).powi(3) * robustness_weights[j] } else { 0.0 }
+    }).collect();
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0
+    {
+        return f64::NAN;
+    }
+    let center = (left..=right).zip(&weights).map(|(j, w)| w * j as f64).sum::<f64>() / total;
+    let spread = (left..=right).zip(&weights).map(|(j, w)| w * (j as f64 - center).powi(2)).sum::<f64>() / total;
+    (left..=right).zip(&weights).map(|(j, w)|
+    {
+        let slope_term = if spread > 0.0 { (x0 - center) * (j as f64 - center) / spread } else { 0.0 };
+        w / total * (1.0 + slope_term) * values[j]
+    }).sum()
+}
+
+// Trailing moving average, the result is window - 1 elements shorter
+fn get_moving_average(values: &[f64], window: usize) -> Vec<f64>
+{
+    values.windows(window).map(|slice| slice.iter().sum::<f64>() / window as f64).collect()
+}
+
+// Bisquare of the remainder over six times its median absolute value
+fn get_robustness_weights(remainder: &Array1<f64>) -> Result<Array1<f64>, String>
+{
+    let scale = 6.0 * get_median(&remainder.mapv(f64::abs))?;
+    if scale == 0.0
+    {
+        return Ok(Array1::ones(remainder.len()));
+    }
+    Ok(remainder.mapv(|r| { let u = (r / scale).abs(); if u < 1.0 { (1.0 - u.powi(2)).powi(2) } else { 0.0 } }))
+}
+
+fn get_seasonal_strength<F>(seasonal: &Array1<f64>, remainder: &Array1<f64>, combine: F) -> Result<f64, String>
+where F: Fn(f64, f64) -> f64
+{
+    let defined: Vec<usize> = (0..remainder.len()).filter(|&t| !remainder[t].is_nan()).collect();
+    let remainder_variance = get_variance(&defined.iter().map(|&t| remainder[t]).collect::<Array1<f64>>())?;
+    let detrended_variance = get_variance(&defined.iter().map(|&t| combine(seasonal[t], remainder[t])).collect::<Array1<f64>>())?;
+    if detrended_variance == 0.0
+    {
+        return Ok(0.0);
+    }
+    Ok((1.0 - remainder_variance / detrended_variance).max(0.0))
+}
+
+fn get_next_odd(value: usize) -> usize
+{
+    if value.is_multiple_of(2) { value + 1 } else { value }
+}
@@ -0,0 +1,406 @@
+use super::clustering::DistanceMetric;
+use super::hierarchical_clustering::get_linkage_matrix;
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use std::collections::VecDeque;
+
+const LEAF_SIZE: usize = 16;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct Dbscan
+{
+    radius: f64,
+    min_points: usize,
+    metric: DistanceMetric,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct Hdbscan
+{
+    min_cluster_size: usize,
+    min_points: usize,
+    metric: DistanceMetric,
+}
+
+// Noise points have no label
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct DensityClustering
+{
+    pub labels: Array1<Option<usize>>,
+    pub num_clusters: usize,
+}
+
+// Points are kept in the order of the leaves, every node covers a contiguous range of them
+struct KdTree<'a>
+{
+    points: &'a Array2<f64>,
+    metric: DistanceMetric,
+    order: Vec<usize>,
+    nodes: Vec<KdNode>,
+}
+
+struct KdNode
+{
+    start: usize,
+    end: usize,
+    lower: Array1<f64>,
+    upper: Array1<f64>,
+    children: Option<(usize, usize)>,
+}
+
+// A cluster of the condensed tree with the inverse distance at which it appears
+struct CondensedCluster
+{
+    parent: Option<usize>,
+    birth: f64,
+    stability: f64,
+    children: Vec<usize>,
+}
+
+#[allow(unused)]
+impl Dbscan
+{
+    // Points with at least min_points points, themselves included, within the radius are core points
+    pub fn new(radius: f64, min_points: usize) -> Self
+    {
+        Dbscan { radius, min_points, metric: DistanceMetric::Euclidean }
+    }
+
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self
+    {
+        self.metric = metric;
+        self
+    }
+
+    // Ester et al. (1996): clusters are the core points connected through neighbourhoods together with the
+    // points in their neighbourhoods, border points join the first cluster reaching them
+    pub fn fit(&self, data: &Array2<f64>) -> Result<DensityClustering, String>
+    {
+        if self.radius <= 0.0 || self.min_points == 0
+        {
+            return Err(String::from("Radius and minimum number of points must be positive!"));
+        }
+        let coordinates = self.metric.get_coordinates(data)?;
+        let tree = KdTree::new(&coordinates, self.metric);
+        let neighbourhoods: Vec<Vec<usize>> = coordinates.rows().into_iter().map(|point| tree.get_neighbours(point, self.radius)).collect();
+
+        let mut labels: Vec<Option<usize>> = vec![None; data.nrows()];
+        let mut num_clusters = 0;
+        for start in 0..data.nrows()
+        {
+            if labels[start].is_some() || neighbourhoods[start].len() < self.min_points
+            {
+                continue;
+            }
+            labels[start] = Some(num_clusters);
+            let mut queue = vec![start];
+            while let Some(point) = queue.pop()
+            {
+                if neighbourhoods[point].len() < self.min_points
+                {
+                    continue;
+                }
+                for &neighbour in neighbourhoods[point].iter()
+                {
+                    if labels[neighbour].is_none()
+                    {
+                        labels[neighbour] = Some(num_clusters);
+                        queue.push(neighbour);
+                    }
+                }
+            }
+            num_clusters += 1;
+        }
+        Ok(DensityClustering { labels: Array1::from(labels), num_clusters })
+    }
+}
+
+#[allow(unused)]
+impl Hdbscan
+{
+    // The core distance of a point is the distance to its min_points-th nearest point, itself included,
+    // min_points defaults to the minimum cluster size
+    pub fn new(min_cluster_size: usize) -> Self
+    {
+        Hdbscan { min_cluster_size, min_points: min_cluster_size, metric: DistanceMetric::Euclidean }
+    }
+
+    pub fn with_min_points(mut self, min_points: usize) -> Self
+    {
+        self.min_points = min_points;
+        self
+    }
+
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self
+    {
+        self.metric = metric;
+        self
+    }
+
+    // Campello, Moulavi and Sander (2013): single linkage on the mutual reachability distances
+    // max(core distance a, core distance b, d(a, b)), condensed by dropping splits that shed fewer points than
+    // the minimum cluster size, from which the clusters with the largest total stability are selected
+    pub fn fit(&self, data: &Array2<f64>) -> Result<DensityClustering, String>
+    {
+        let num_points = data.nrows();
+        if self.min_cluster_size < 2 || self.min_cluster_size > num_points
+        {
+            return Err(String::from("Minimum cluster size must be between 2 and the number of points!"));
+        }
+        if self.min_points == 0 || self.min_points > num_points
+        {
+            return Err(String::from("Minimum number of points must be between 1 and the number of points!"));
+        }
+        let coordinates = self.metric.get_coordinates(data)?;
+        let tree = KdTree::new(&coordinates, self.metric);
+        let core_distances: Vec<f64> = coordinates.rows().into_iter().map(|point| tree.get_nearest_distance(point, self.min_points)).collect();
+
+        // Prim's algorithm for the minimum spanning tree of the mutual reachability distances
+        let mut in_tree = vec![false; num_points];
+        let mut reachability = vec![f64::INFINITY; num_points];
+        let mut sources = vec![0usize; num_points];
+        let mut edges: Vec<(usize, usize, f64)> = Vec::with_capacity(num_points - 1);
+        let mut current = 0;
+        in_tree[0] = true;
+        for _ in 1..num_points
+        {
+            for i in (0..num_points).filter(|&i| !in_tree[i])
+            {
+                let distance = self.metric.get_distance(coordinates.row(current), coordinates.row(i)).max(core_distances[current]).max(core_distances[i]);
+                if distance < reachability[i]
+                {
+                    reachability[i] = distance;
+                    sources[i] = current;
+                }
+            }
+            current = (0..num_points).filter(|&i| !in_tree[i]).min_by(|&a, &b| reachability[a].partial_cmp(&reachability[b]).unwrap()).unwrap();
+            in_tree[current] = true;
+            edges.push((sources[current], current, reachability[current]));
+        }
+        let linkage_matrix = get_linkage_matrix(num_points, edges);
+        Ok(get_stable_clusters(&linkage_matrix, self.min_cluster_size))
+    }
+}
+
+// Walks the single linkage tree from the root, recording when points fall out of clusters with lambda = 1 / distance,
+// and selecting clusters by excess of mass, the root itself is never selected
+fn get_stable_clusters(linkage_matrix: &Array2<f64>, min_cluster_size: usize) -> DensityClustering
+{
+    let num_points = linkage_matrix.nrows() + 1;
+    let size = |node: usize| if node < num_points { 1 } else { linkage_matrix[[node - num_points, 3]] as usize };
+    let points_below = |node: usize|
+    {
+        let mut points = Vec::new();
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop()
+        {
+            if node < num_points
+            {
+                points.push(node);
+            }
+            else
+            {
+                stack.push(linkage_matrix[[node - num_points, 0]] as usize);
+                stack.push(linkage_matrix[[node - num_points, 1]] as usize);
+            }
+        }
+        points
+    };
+
+    // Inverse distance and cluster at which every point falls out
+    let mut exits = vec![(0.0, 0usize); num_points];
+    let mut clusters = vec![CondensedCluster { parent: None, birth: 0.0, stability: 0.0, children: Vec::new() }];
+    // Breadth first, so that clusters are numbered level by level as in the hdbscan library
+    let mut queue = VecDeque::from(vec![(2 * num_points - 2, 0usize)]);
+    while let Some((node, cluster)) = queue.pop_front()
+    {
+        let merge = linkage_matrix.row(node - num_points);
+        let lambda = if merge[2] > 0.0 { 1.0 / merge[2] } else { f64::MAX };
+        let (left, right) = (merge[0] as usize, merge[1] as usize);
+        let large = [left, right].iter().filter(|&&child| size(child) >= min_cluster_size).count();
+        for &child in [left, right].iter()
+        {
+            if size(child) < min_cluster_size
+            {
+                for point in points_below(child)
+                {
+                    exits[point] = (lambda, cluster);
+                }
+            }
+            else if large == 2
+            {
+                // The points of the child leave the parent together
+                clusters[cluster].stability += size(child) as f64 * (lambda - clusters[cluster].birth);
+                let child_cluster = clusters.len();
+                clusters.push(CondensedCluster { parent: Some(cluster), birth: lambda, stability: 0.0, children: Vec::new() });
+                clusters[cluster].children.push(child_cluster);
+                queue.push_back((child, child_cluster));
+            }
+            else
+            {
+                queue.push_back((child, cluster));
+            }
+        }
+    }
+
+    // Stability sums lambda - birth over the points of a cluster, leaving at their exit or at the split into children
+    for &(lambda, cluster) in exits.iter()
+    {
+        clusters[cluster].stability += lambda - clusters[cluster].birth;
+    }
+
+    // Children are created after their parents, so a reverse pass visits them first
+    let mut selected = vec![false; clusters.len()];
+    let mut best_stability: Vec<f64> = clusters.iter().map(|cluster| cluster.stability).collect();
+    for cluster in (1..clusters.len()).rev()
+    {
+        let children_stability: f64 = clusters[cluster].children.iter().map(|&child| best_stability[child]).sum();
+        if !clusters[cluster].children.is_empty() && children_stability > clusters[cluster].stability
+        {
+            best_stability[cluster] = children_stability;
+        }
+        else
+        {
+            selected[cluster] = true;
+            let mut descendants = clusters[cluster].children.clone();
+            while let Some(descendant) = descendants.pop()
+            {
+                selected[descendant] = false;
+                descendants.extend(clusters[descendant].children.iter());
+            }
+        }
+    }
+
+    let mut cluster_labels = vec![None; clusters.len()];
+    let mut num_clusters = 0;
+    for cluster in (1..clusters.len()).filter(|&cluster| selected[cluster])
+    {
+        cluster_labels[cluster] = Some(num_clusters);
+        num_clusters += 1;
+    }
+    let labels = exits.iter().map(|&(_, cluster)|
+    {
+        let mut cluster = Some(cluster);
+        while let Some(current) = cluster
+        {
+            if selected[current]
+            {
+                return cluster_labels[current];
+            }
+            cluster = clusters[current].parent;
+        }
+        None
+    }).collect();
+    DensityClustering { labels, num_clusters }
+}
+
+impl<'a> KdTree<'a>
+{
+    fn new(points: &'a Array2<f64>, metric: DistanceMetric) -> Self
+    {
+        let mut tree = KdTree { points, metric, order: (0..points.nrows()).collect(), nodes: Vec::new() };
+        tree.build(0, points.nrows());
+        tree
+    }
+
+    // Splits at the median of the dimension with the largest spread
+    fn build(&mut self, start: usize, end: usize) -> usize
+    {
+        let members = self.points.select(Axis(0), &self.order[start..end]);
+        let lower = members.fold_axis(Axis(0), f64::INFINITY, |a, &b| a.min(b));
+        let upper = members.fold_axis(Axis(0), f64::NEG_INFINITY, |a, &b| a.max(b));
+        let index = self.nodes.len();
+        self.nodes.push(KdNode { start, end, lower: lower.clone(), upper: upper.clone(), children: None });
+        if end - start > LEAF_SIZE
+        {
+            let spreads = &upper - &lower;
+            let dimension = (0..spreads.len()).fold(0, |best, d| if spreads[d] > spreads[best] { d } else { best });
+            let middle = (start + end) / 2;
+            let points = self.points;
+            self.order[start..end].select_nth_unstable_by(middle - start, |&a, &b| points[[a, dimension]].partial_cmp(&points[[b, dimension]]).unwrap());
+            let left = self.build(start, middle);
+            let right = self.build(middle, end);
+            self.nodes[index].children = Some((left, right));
+        }
+        index
+    }
+
+    // Lower bound on the metric distance from the point to anything in the bounding box of the node
+    fn get_box_distance(&self, node: &KdNode, point: ArrayView1<f64>) -> f64
+    {
+        let gaps = point.iter().zip(node.lower.iter().zip(node.upper.iter())).map(|(&x, (&lower, &upper))| (lower - x).max(x - upper).max(0.0));
+        match self.metric
+        {
+            DistanceMetric::Manhattan => gaps.sum(),
+            _ => self.metric.get_metric_distance(gaps.map(|gap| gap * gap).sum::<f64>().sqrt()),
+        }
+    }
+
+    // Indices in increasing order of the points within the radius, the point itself included
+    fn get_neighbours(&self, point: ArrayView1<f64>, radius: f64) -> Vec<usize>
+    {
+        let mut neighbours = Vec::new();
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop()
+        {
+            let node = &self.nodes[index];
+            if self.get_box_distance(node, point) > radius
+            {
+                continue;
+            }
+            match node.children
+            {
+                Some((left, right)) => stack.extend([left, right].iter()),
+                None => neighbours.extend(self.order[node.start..node.end].iter().filter(|&&i| self.metric.get_distance(point, self.points.row(i)) <= radius)),
+            }
+        }
+        neighbours.sort_unstable();
+        neighbours
+    }
+
+    // Distance to the k-th nearest point, the point itself included
+    fn get_nearest_distance(&self, point: ArrayView1<f64>, k: usize) -> f64
+    {
+        let mut nearest: Vec<f64> = Vec::with_capacity(k + 1);
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop()
+        {
+            let node = &self.nodes[index];
+            if nearest.len() == k && self.get_box_distance(node, point) >= nearest[k - 1]
+            {
+                continue;
+            }
+            match node.children
+            {
+                // Visit the closer child first
+                Some((left, right)) =>
+                {
+                    if self.get_box_distance(&self.nodes[left], point) < self.get_box_distance(&self.nodes[right], point)
+                    {
+                        stack.extend([right, left].iter());
+                    }
+                    else
+                    {
+                        stack.extend([left, right].iter());
+                    }
+                },
+                None =>
+                {
+                    for &i in self.order[node.start..node.end].iter()
+                    {
+                        let distance = self.metric.get_distance(point, self.points.row(i));
+                        let position = nearest.iter().take_while(|&&d| d <= distance).count();
+                        if position < k
+                        {
+                            nearest.insert(position, distance);
+                            nearest.truncate(k);
+                        }
+                    }
+                },
+            }
+        }
+        nearest[k - 1]
+    }
+}
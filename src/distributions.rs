@@ -0,0 +1,725 @@
+const LANCZOS_G: f64 = 7.0;
+const LANCZOS_COEFFICIENTS: [f64; 9] =
+[
+    0.999_999_999_999_809_9,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_1,
+    -176.615_029_162_140_6,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_572e-6,
+    1.505_632_735_149_311_6e-7,
+];
+const MAX_ITERATIONS: usize = 1000;
+const EPSILON: f64 = 1e-15;
+
+#[allow(unused)]
+pub fn get_ln_gamma(x: f64) -> f64
+{
+    if x < 0.5
+    {
+        // Reflection formula
+        std::f64::consts::PI.ln() - (std::f64::consts::PI * x).sin().abs().ln() - get_ln_gamma(1.0 - x)
+    }
+    else
+    {
+        let x = x - 1.0;
+        let mut sum = LANCZOS_COEFFICIENTS[0];
+        for (i, coefficient) in LANCZOS_COEFFICIENTS.iter().enumerate().skip(1)
+        {
+            sum += coefficient / (x + i as f64);
+        }
+        let t = x + LANCZOS_G + 0.5;
+        0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+    }
+}
+
+#[allow(unused)]
+pub fn get_regularized_lower_gamma(a: f64, x: f64) -> Result<f64, String>
+{
+    if a <= 0.0 || x < 0.0
+    {
+        return Err(String::from("a must be positive and x must not be negative!"));
+    }
+    if x == 0.0
+    {
+        Ok(0.0)
+    }
+    else if x < a + 1.0
+    {
+        Ok(get_lower_gamma_series(a, x))
+    }
+    else
+    {
+        Ok(1.0 - get_upper_gamma_continued_fraction(a, x))
+    }
+}
+
+#[allow(unused)]
+pub fn get_regularized_upper_gamma(a: f64, x: f64) -> Result<f64, String>
+{
+    if a <= 0.0 || x < 0.0
+    {
+        return Err(String::from("a must be positive and x must not be negative!"));
+    }
+    if x == 0.0
+    {
+        Ok(1.0)
+    }
+    else if x < a + 1.0
+    {
+        Ok(1.0 - get_lower_gamma_series(a, x))
+    }
+    else
+    {
+        Ok(get_upper_gamma_continued_fraction(a, x))
+    }
+}
+
+fn get_lower_gamma_series(a: f64, x: f64) -> f64
+{
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    let mut denominator = a;
+    for _ in 0..MAX_ITERATIONS
+    {
+        denominator += 1.0;
+        term *= x / denominator;
+        sum += term;
+        if term.abs() < sum.abs() * EPSILON
+        {
+            break;
+        }
+    }
+    sum * (-x + a * x.ln() - get_ln_gamma(a)).exp()
+}
+
+// Modified Lentz's method
+fn get_upper_gamma_continued_fraction(a: f64, x: f64) -> f64
+{
+    let tiny = 1e-300;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / tiny;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..MAX_ITERATIONS
+    {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < tiny
+        {
+            d = tiny;
+        }
+        c = b + an / c;
+        if c.abs() < tiny
+        {
+            c = tiny;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON
+        {
+            break;
+        }
+    }
+    (-x + a * x.ln() - get_ln_gamma(a)).exp() * h
+}
+
+#[allow(unused)]
+pub fn get_normal_pdf(x: f64) -> f64
+{
+    (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+#[allow(unused)]
+pub fn get_normal_cdf(x: f64) -> f64
+{
+    // Phi(x) = erfc(-x / sqrt(2)) / 2 and erfc(t) = Q(1/2, t^2) for t >= 0
+    let t = x / std::f64::consts::SQRT_2;
+    let tail = 0.5 * get_regularized_upper_gamma(0.5, t * t).unwrap();
+    if x < 0.0
+    {
+        tail
+    }
+    else
+    {
+        1.0 - tail
+    }
+}
+
+#[allow(unused)]
+pub fn get_normal_sf(x: f64) -> f64
+{
+    get_normal_cdf(-x)
+}
+
+// Acklam's rational approximation, refined with one Halley step
+#[allow(unused)]
+pub fn get_normal_ppf(p: f64) -> Result<f64, String>
+{
+    if !(0.0..=1.0).contains(&p)
+    {
+        return Err(String::from("Probability must be between 0 and 1!"));
+    }
+    if p == 0.0
+    {
+        return Ok(f64::NEG_INFINITY);
+    }
+    if p == 1.0
+    {
+        return Ok(f64::INFINITY);
+    }
+
+    let a = [-3.969_683_028_665_376e1, 2.209_460_984_245_205e2, -2.759_285_104_469_687e2,
+             1.383_577_518_672_69e2, -3.066_479_806_614_716e1, 2.506_628_277_459_239];
+    let b = [-5.447_609_879_822_406e1, 1.615_858_368_580_409e2, -1.556_989_798_598_866e2,
+             6.680_131_188_771_972e1, -1.328_068_155_288_572e1];
+    let c = [-7.784_894_002_430_293e-3, -3.223_964_580_411_365e-1, -2.400_758_277_161_838,
+             -2.549_732_539_343_734, 4.374_664_141_464_968, 2.938_163_982_698_783];
+    let d = [7.784_695_709_041_462e-3, 3.224_671_290_700_398e-1, 2.445_134_137_142_996,
+             3.754_408_661_907_416];
+    let p_low = 0.02425;
+
+    let x = if p < p_low
+    {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((c[0] * q + c[1]) * q + c[2]) * q + c[3]) * q + c[4]) * q + c[5])
+        / ((((d[0] * q + d[1]) * q + d[2]) * q + d[3]) * q + 1.0)
+    }
+    else if p <= 1.0 - p_low
+    {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((a[0] * r + a[1]) * r + a[2]) * r + a[3]) * r + a[4]) * r + a[5]) * q
+        / (((((b[0] * r + b[1]) * r + b[2]) * r + b[3]) * r + b[4]) * r + 1.0)
+    }
+    else
+    {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -(((((c[0] * q + c[1]) * q + c[2]) * q + c[3]) * q + c[4]) * q + c[5])
+        / ((((d[0] * q + d[1]) * q + d[2]) * q + d[3]) * q + 1.0)
+    };
+
+    let error = get_normal_cdf(x) - p;
+    let u = error * (2.0 * std::f64::consts::PI).sqrt() * (x * x / 2.0).exp();
+    Ok(x - u / (1.0 + x * u / 2.0))
+}
+
+#[allow(unused)]
+pub fn get_chi_squared_cdf(x: f64, degrees_of_freedom: f64) -> Result<f64, String>
+{
+    if degrees_of_freedom <= 0.0
+    {
+        return Err(String::from("Degrees of freedom must be positive!"));
+    }
+    if x <= 0.0
+    {
+        return Ok(0.0);
+    }
+    get_regularized_lower_gamma(degrees_of_freedom / 2.0, x / 2.0)
+}
+
+#[allow(unused)]
+pub fn get_chi_squared_sf(x: f64, degrees_of_freedom: f64) -> Result<f64, String>
+{
+    if degrees_of_freedom <= 0.0
+    {
+        return Err(String::from("Degrees of freedom must be positive!"));
+    }
+    if x <= 0.0
+    {
+        return Ok(1.0);
+    }
+    get_regularized_upper_gamma(degrees_of_freedom / 2.0, x / 2.0)
+}
+
+#[allow(unused)]
+pub fn get_chi_squared_ppf(p: f64, degrees_of_freedom: f64) -> Result<f64, String>
+{
+    get_inverse_cdf(|x| get_chi_squared_cdf(x, degrees_of_freedom), p, 0.0, f64::INFINITY, degrees_of_freedom)
+}
+
+#[allow(unused)]
+pub fn get_ln_beta(a: f64, b: f64) -> f64
+{
+    get_ln_gamma(a) + get_ln_gamma(b) - get_ln_gamma(a + b)
+}
+
+#[allow(unused)]
+pub fn get_regularized_incomplete_beta(a: f64, b: f64, x: f64) -> Result<f64, String>
+{
+    if a <= 0.0 || b <= 0.0
+    {
+        return Err(String::from("a and b must be positive!"));
+    }
+    if !(0.0..=1.0).contains(&x)
+    {
+        return Err(String::from("x must be between 0 and 1!"));
+    }
+    if x == 0.0 || x == 1.0
+    {
+        return Ok(x);
+    }
+    let front = (a * x.ln() + b * (1.0 - x).ln() - get_ln_beta(a, b)).exp();
+    // The continued fraction converges quickly only on one side of the mean
+    if x < (a + 1.0) / (a + b + 2.0)
+    {
+        Ok(front * get_beta_continued_fraction(a, b, x) / a)
+    }
+    else
+    {
+        Ok(1.0 - front * get_beta_continued_fraction(b, a, 1.0 - x) / b)
+    }
+}
+
+// Modified Lentz's method
+fn get_beta_continued_fraction(a: f64, b: f64, x: f64) -> f64
+{
+    let tiny = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < tiny
+    {
+        d = tiny;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..MAX_ITERATIONS
+    {
+        let m = m as f64;
+        let even_term = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 + even_term * d;
+        if d.abs() < tiny
+        {
+            d = tiny;
+        }
+        c = 1.0 + even_term / c;
+        if c.abs() < tiny
+        {
+            c = tiny;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+
+        let odd_term = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 + odd_term * d;
+        if d.abs() < tiny
+        {
+            d = tiny;
+        }
+        c = 1.0 + odd_term / c;
+        if c.abs() < tiny
+        {
+            c = tiny;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON
+        {
+            break;
+        }
+    }
+    h
+}
+
+#[allow(unused)]
+pub fn get_beta_cdf(x: f64, a: f64, b: f64) -> Result<f64, String>
+{
+    get_regularized_incomplete_beta(a, b, x.clamp(0.0, 1.0))
+}
+
+#[allow(unused)]
+pub fn get_beta_ppf(p: f64, a: f64, b: f64) -> Result<f64, String>
+{
+    get_inverse_cdf(|x| get_beta_cdf(x, a, b), p, 0.0, 1.0, a / (a + b))
+}
+
+#[allow(unused)]
+pub fn get_t_cdf(t: f64, degrees_of_freedom: f64) -> Result<f64, String>
+{
+    if degrees_of_freedom <= 0.0
+    {
+        return Err(String::from("Degrees of freedom must be positive!"));
+    }
+    let tail = 0.5 * get_regularized_incomplete_beta(degrees_of_freedom / 2.0, 0.5, degrees_of_freedom / (degrees_of_freedom + t * t))?;
+    if t > 0.0
+    {
+        Ok(1.0 - tail)
+    }
+    else
+    {
+        Ok(tail)
+    }
+}
+
+#[allow(unused)]
+pub fn get_t_sf(t: f64, degrees_of_freedom: f64) -> Result<f64, String>
+{
+    get_t_cdf(-t, degrees_of_freedom)
+}
+
+#[allow(unused)]
+pub fn get_t_ppf(p: f64, degrees_of_freedom: f64) -> Result<f64, String>
+{
+    if degrees_of_freedom <= 0.0
+    {
+        return Err(String::from("Degrees of freedom must be positive!"));
+    }
+    let start = get_normal_ppf(p)?;
+    if !start.is_finite()
+    {
+        return Ok(start);
+    }
+    get_inverse_cdf(|t| get_t_cdf(t, degrees_of_freedom), p, f64::NEG_INFINITY, f64::INFINITY, start)
+}
+
+#[allow(unused)]
+pub fn get_f_cdf(f: f64, numerator_degrees_of_freedom: f64, denominator_degrees_of_freedom: f64) -> Result<f64, String>
+{
+    if numerator_degrees_of_freedom <= 0.0 || denominator_degrees_of_freedom <= 0.0
+    {
+        return Err(String::from("Degrees of freedom must be positive!"));
+    }
+    if f <= 0.0
+    {
+        return Ok(0.0);
+    }
+    let x = numerator_degrees_of_freedom * f / (numerator_degrees_of_freedom * f + denominator_degrees_of_freedom);
+    get_regularized_incomplete_beta(numerator_degrees_of_freedom / 2.0, denominator_degrees_of_freedom / 2.0, x)
+}
+
+#[allow(unused)]
+pub fn get_f_sf(f: f64, numerator_degrees_of_freedom: f64, denominator_degrees_of_freedom: f64) -> Result<f64, String>
+{
+    if numerator_degrees_of_freedom <= 0.0 || denominator_degrees_of_freedom <= 0.0
+    {
+        return Err(String::from("Degrees of freedom must be positive!"));
+    }
+    if f <= 0.0
+    {
+        return Ok(1.0);
+    }
+    // Evaluated directly instead of 1 - cdf to keep precision for small p-values
+    let x = denominator_degrees_of_freedom / (denominator_degrees_of_freedom + numerator_degrees_of_freedom * f);
+    get_regularized_incomplete_beta(denominator_degrees_of_freedom / 2.0, numerator_degrees_of_freedom / 2.0, x)
+}
+
+#[allow(unused)]
+pub fn get_f_ppf(p: f64, numerator_degrees_of_freedom: f64, denominator_degrees_of_freedom: f64) -> Result<f64, String>
+{
+    get_inverse_cdf(|f| get_f_cdf(f, numerator_degrees_of_freedom, denominator_degrees_of_freedom), p, 0.0, f64::INFINITY, 1.0)
+}
+
+// Poisson mixture of central chi-squared distributions with mean noncentrality / 2
+#[allow(unused)]
+pub fn get_noncentral_chi_squared_cdf(x: f64, degrees_of_freedom: f64, noncentrality: f64) -> Result<f64, String>
+{
+    if noncentrality < 0.0
+    {
+        return Err(String::from("Noncentrality must not be negative!"));
+    }
+    if x <= 0.0
+    {
+        return Ok(0.0);
+    }
+    get_poisson_mixture(noncentrality / 2.0, |j| get_chi_squared_cdf(x, degrees_of_freedom + 2.0 * j as f64))
+}
+
+#[allow(unused)]
+pub fn get_noncentral_chi_squared_sf(x: f64, degrees_of_freedom: f64, noncentrality: f64) -> Result<f64, String>
+{
+    if noncentrality < 0.0
+    {
+        return Err(String::from("Noncentrality must not be negative!"));
+    }
+    if x <= 0.0
+    {
+        return Ok(1.0);
+    }
+    get_poisson_mixture(noncentrality / 2.0, |j| get_chi_squared_sf(x, degrees_of_freedom + 2.0 * j as f64))
+}
+
+// Poisson mixture of incomplete beta functions
+#[allow(unused)]
+pub fn get_noncentral_f_cdf(f: f64, numerator_degrees_of_freedom: f64, denominator_degrees_of_freedom: f64, noncentrality: f64)
+-> Result<f64, String>
+{
+    if numerator_degrees_of_freedom <= 0.0 || denominator_degrees_of_freedom <= 0.0 || noncentrality < 0.0
+    {
+        return Err(String::from("Degrees of freedom must be positive and noncentrality must not be negative!"));
+    }
+    if f <= 0.0
+    {
+        return Ok(0.0);
+    }
+    let x = numerator_degrees_of_freedom * f / (numerator_degrees_of_freedom * f + denominator_degrees_of_freedom);
+    get_poisson_mixture(noncentrality / 2.0, |j|
+    {
+        get_regularized_incomplete_beta(numerator_degrees_of_freedom / 2.0 + j as f64, denominator_degrees_of_freedom / 2.0, x)
+    })
+}
+
+#[allow(unused)]
+pub fn get_noncentral_f_sf(f: f64, numerator_degrees_of_freedom: f64, denominator_degrees_of_freedom: f64, noncentrality: f64)
+-> Result<f64, String>
+{
+    if numerator_degrees_of_freedom <= 0.0 || denominator_degrees_of_freedom <= 0.0 || noncentrality < 0.0
+    {
+        return Err(String::from("Degrees of freedom must be positive and noncentrality must not be negative!"));
+    }
+    if f <= 0.0
+    {
+        return Ok(1.0);
+    }
+    let x = denominator_degrees_of_freedom / (numerator_degrees_of_freedom * f + denominator_degrees_of_freedom);
+    get_poisson_mixture(noncentrality / 2.0, |j|
+    {
+        get_regularized_incomplete_beta(denominator_degrees_of_freedom / 2.0, numerator_degrees_of_freedom / 2.0 + j as f64, x)
+    })
+}
+
+// Series of Lenth (1989, AS 243), valid for t >= 0, negative t use the reflection
+// P(T <= t; delta) = 1 - P(T <= -t; -delta)
+#[allow(unused)]
+pub fn get_noncentral_t_cdf(t: f64, degrees_of_freedom: f64, noncentrality: f64) -> Result<f64, String>
+{
+    if degrees_of_freedom <= 0.0
+    {
+        return Err(String::from("Degrees of freedom must be positive!"));
+    }
+    if noncentrality == 0.0
+    {
+        return get_t_cdf(t, degrees_of_freedom);
+    }
+    if t < 0.0
+    {
+        return Ok(1.0 - get_noncentral_t_cdf(-t, degrees_of_freedom, -noncentrality)?);
+    }
+
+    let x = t * t / (t * t + degrees_of_freedom);
+    let half_delta_squared = noncentrality.powi(2) / 2.0;
+    let ln_scale = (noncentrality.abs() / 2.0f64.sqrt()).ln();
+    let sum = get_sum_from_mode(half_delta_squared, |j|
+    {
+        let j_f64 = j as f64;
+        let ln_power = if j == 0 { 0.0 } else { j_f64 * half_delta_squared.ln() };
+        let p_j = (-half_delta_squared + ln_power - get_ln_gamma(j_f64 + 1.0)).exp();
+        let q_j = noncentrality.signum() * (ln_scale - half_delta_squared + ln_power - get_ln_gamma(j_f64 + 1.5)).exp();
+        let term = p_j * get_regularized_incomplete_beta(j_f64 + 0.5, degrees_of_freedom / 2.0, x)?
+                   + q_j * get_regularized_incomplete_beta(j_f64 + 1.0, degrees_of_freedom / 2.0, x)?;
+        Ok((p_j.abs() + q_j.abs(), term))
+    })?;
+    Ok((get_normal_cdf(-noncentrality) + sum / 2.0).clamp(0.0, 1.0))
+}
+
+#[allow(unused)]
+pub fn get_noncentral_t_sf(t: f64, degrees_of_freedom: f64, noncentrality: f64) -> Result<f64, String>
+{
+    Ok(1.0 - get_noncentral_t_cdf(t, degrees_of_freedom, noncentrality)?)
+}
+
+// Sums Poisson(mean) weights times f(j)
+fn get_poisson_mixture<F>(mean: f64, f: F) -> Result<f64, String>
+where F: Fn(usize) -> Result<f64, String>
+{
+    if mean == 0.0
+    {
+        return f(0);
+    }
+    let sum = get_sum_from_mode(mean, |j|
+    {
+        let weight = (-mean + j as f64 * mean.ln() - get_ln_gamma(j as f64 + 1.0)).exp();
+        Ok((weight, weight * f(j)?))
+    })?;
+    Ok(sum.clamp(0.0, 1.0))
+}
+
+// Sums the terms of a series with Poisson(mean) like weights outward from the mode, as in R's pnt,
+// since for a large mean the weights near zero underflow and a sum from zero would stop too early.
+// The closure returns the weight and the term of index j
+fn get_sum_from_mode<F>(mean: f64, term: F) -> Result<f64, String>
+where F: Fn(usize) -> Result<(f64, f64), String>
+{
+    let mode = mean.floor() as usize;
+    let mut sum = 0.0;
+    let mut converged_upwards = false;
+    for j in mode..mode + MAX_ITERATIONS
+    {
+        let (weight, value) = term(j)?;
+        sum += value;
+        if weight < EPSILON
+        {
+            converged_upwards = true;
+            break;
+        }
+    }
+    // Downwards the series ends at zero at the latest
+    let mut converged_downwards = mode <= MAX_ITERATIONS;
+    for j in (mode.saturating_sub(MAX_ITERATIONS)..mode).rev()
+    {
+        let (weight, value) = term(j)?;
+        sum += value;
+        if weight < EPSILON
+        {
+            converged_downwards = true;
+            break;
+        }
+    }
+    if !converged_upwards || !converged_downwards
+    {
+        return Err(String::from("Poisson weighted series did not converge!"));
+    }
+    Ok(sum)
+}
+
+// Distribution of the range of k standard normals divided by an independent sqrt(chi^2_df / df),
+// computed by integrating the normal range distribution over the density of the scale
+#[allow(unused)]
+pub fn get_studentized_range_cdf(q: f64, num_groups: usize, degrees_of_freedom: f64) -> Result<f64, String>
+{
+    if num_groups < 2 || degrees_of_freedom <= 0.0
+    {
+        return Err(String::from("Need at least two groups and positive degrees of freedom!"));
+    }
+    if q <= 0.0
+    {
+        return Ok(0.0);
+    }
+    if degrees_of_freedom > 5000.0
+    {
+        return Ok(get_normal_range_cdf(q, num_groups));
+    }
+
+    let nu = degrees_of_freedom;
+    let ln_normalization = (nu / 2.0) * nu.ln() - get_ln_gamma(nu / 2.0) - (nu / 2.0 - 1.0) * 2.0f64.ln();
+    let scale_density = |s: f64| -> f64
+    {
+        if s <= 0.0 { 0.0 } else { (ln_normalization + (nu - 1.0) * s.ln() - nu * s * s / 2.0).exp() }
+    };
+    let upper = 1.0 + 12.0 / nu.sqrt();
+    let result = get_simpson_integral(|s| scale_density(s) * get_normal_range_cdf(q * s, num_groups), 0.0, upper, 200);
+    Ok(result.clamp(0.0, 1.0))
+}
+
+#[allow(unused)]
+pub fn get_studentized_range_sf(q: f64, num_groups: usize, degrees_of_freedom: f64) -> Result<f64, String>
+{
+    Ok(1.0 - get_studentized_range_cdf(q, num_groups, degrees_of_freedom)?)
+}
+
+#[allow(unused)]
+pub fn get_studentized_range_ppf(p: f64, num_groups: usize, degrees_of_freedom: f64) -> Result<f64, String>
+{
+    get_inverse_cdf(|q| get_studentized_range_cdf(q, num_groups, degrees_of_freedom), p, 0.0, f64::INFINITY, 3.0)
+}
+
+// P(range of k standard normals < w) = k * integral phi(z) (Phi(z) - Phi(z - w))^(k - 1) dz
+fn get_normal_range_cdf(w: f64, num_groups: usize) -> f64
+{
+    if w <= 0.0
+    {
+        return 0.0;
+    }
+    let k = num_groups as f64;
+    let integrand = |z: f64| get_normal_pdf(z) * (get_normal_cdf(z) - get_normal_cdf(z - w)).powf(k - 1.0);
+    (k * get_simpson_integral(integrand, -8.0, 8.0, 100)).min(1.0)
+}
+
+fn get_simpson_integral<F>(f: F, lower: f64, upper: f64, num_intervals: usize) -> f64
+where F: Fn(f64) -> f64
+{
+    let num_intervals = num_intervals + num_intervals % 2;
+    let h = (upper - lower) / num_intervals as f64;
+    let mut sum = f(lower) + f(upper);
+    for i in 1..num_intervals
+    {
+        let weight = if i % 2 == 1 { 4.0 } else { 2.0 };
+        sum += weight * f(lower + i as f64 * h);
+    }
+    sum * h / 3.0
+}
+
+// Inverts a continuous, increasing cdf by bracketing around a starting guess and bisecting
+fn get_inverse_cdf<F>(cdf: F, p: f64, support_lower: f64, support_upper: f64, start: f64) -> Result<f64, String>
+where F: Fn(f64) -> Result<f64, String>
+{
+    if !(0.0..=1.0).contains(&p)
+    {
+        return Err(String::from("Probability must be between 0 and 1!"));
+    }
+    if p == 0.0
+    {
+        return Ok(support_lower);
+    }
+    if p == 1.0
+    {
+        return Ok(support_upper);
+    }
+
+    let mut step = start.abs().max(1.0);
+    let mut lower = start;
+    let mut upper = start;
+    while cdf(lower)? > p
+    {
+        lower = (lower - step).max(support_lower);
+        step *= 2.0;
+        if lower == support_lower
+        {
+            break;
+        }
+    }
+    step = start.abs().max(1.0);
+    while cdf(upper)? < p
+    {
+        upper = (upper + step).min(support_upper);
+        step *= 2.0;
+        if upper == support_upper
+        {
+            break;
+        }
+    }
+
+    // Illinois variant of regula falsi
+    let mut f_lower = cdf(lower)? - p;
+    let mut f_upper = cdf(upper)? - p;
+    let mut estimate = 0.5 * (lower + upper);
+    let mut last_side = 0;
+    for _ in 0..200
+    {
+        if f_upper == f_lower || (upper - lower).abs() <= 1e-12 * (1.0 + estimate.abs())
+        {
+            break;
+        }
+        estimate = upper - f_upper * (upper - lower) / (f_upper - f_lower);
+        let f_estimate = cdf(estimate)? - p;
+        if f_estimate.abs() < 1e-15
+        {
+            break;
+        }
+        if f_estimate < 0.0
+        {
+            lower = estimate;
+            f_lower = f_estimate;
+            if last_side == -1
+            {
+                f_upper /= 2.0;
+            }
+            last_side = -1;
+        }
+        else
+        {
+            upper = estimate;
+            f_upper = f_estimate;
+            if last_side == 1
+            {
+                f_lower /= 2.0;
+            }
+            last_side = 1;
+        }
+    }
+    Ok(estimate)
+}
@@ -0,0 +1,253 @@
+use super::analysis_of_variance::{AnovaRow, AnovaTable};
+use super::contingency_table::ContingencyTable;
+use super::distributions::{get_ln_gamma, get_noncentral_f_sf, get_noncentral_t_cdf, get_normal_ppf};
+use super::utilities::{check_vector_for_nans, get_mean, get_variance};
+use ndarray::Array1;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct EffectSize
+{
+    pub estimate: f64,
+    pub confidence_interval: (f64, f64),
+}
+
+// Standardized by the pooled standard deviation,
+// the interval inverts the noncentral t distribution of the two-sample t statistic
+#[allow(unused)]
+pub fn get_cohens_d(sample_1: &Array1<f64>, sample_2: &Array1<f64>, confidence_level: f64) -> Result<EffectSize, String>
+{
+    let (n_1, n_2) = check_two_samples(sample_1, sample_2)?;
+    let degrees_of_freedom = n_1 + n_2 - 2.0;
+    let pooled_variance = ((n_1 - 1.0) * get_variance(sample_1)? + (n_2 - 1.0) * get_variance(sample_2)?) / degrees_of_freedom;
+    let estimate = (get_mean(sample_1)? - get_mean(sample_2)?) / check_scale(pooled_variance)?;
+
+    let scale = (n_1 * n_2 / (n_1 + n_2)).sqrt();
+    let (lower, upper) = get_noncentral_t_interval(estimate * scale, degrees_of_freedom, confidence_level)?;
+    Ok(EffectSize { estimate, confidence_interval: (lower / scale, upper / scale) })
+}
+
+// Cohen's d times the exact small sample correction J = Gamma(df / 2) / (sqrt(df / 2) Gamma((df - 1) / 2))
+#[allow(unused)]
+pub fn get_hedges_g(sample_1: &Array1<f64>, sample_2: &Array1<f64>, confidence_level: f64) -> Result<EffectSize, String>
+{
+    let cohens_d = get_cohens_d(sample_1, sample_2, confidence_level)?;
+    let degrees_of_freedom = (sample_1.len() + sample_2.len() - 2) as f64;
+    let correction = (get_ln_gamma(degrees_of_freedom / 2.0) - get_ln_gamma((degrees_of_freedom - 1.0) / 2.0)).exp() / (degrees_of_freedom / 2.0).sqrt();
+    let (lower, upper) = cohens_d.confidence_interval;
+    Ok(EffectSize { estimate: correction * cohens_d.estimate, confidence_interval: (correction * lower, correction * upper) })
+}
+
+// Standardized by the standard deviation of the control group sample_2
+#[allow(unused)]
+pub fn get_glass_delta(sample_1: &Array1<f64>, sample_2: &Array1<f64>, confidence_level: f64) -> Result<EffectSize, String>
+{
+    let (n_1, n_2) = check_two_samples(sample_1, sample_2)?;
+    let estimate = (get_mean(sample_1)? - get_mean(sample_2)?) / check_scale(get_variance(sample_2)?)?;
+
+    let scale = (n_1 * n_2 / (n_1 + n_2)).sqrt();
+    let (lower, upper) = get_noncentral_t_interval(estimate * scale, n_2 - 1.0, confidence_level)?;
+    Ok(EffectSize { estimate, confidence_interval: (lower / scale, upper / scale) })
+}
+
+#[allow(unused)]
+pub fn get_eta_squared(table: &AnovaTable, source: &str) -> Result<f64, String>
+{
+    get_effect_row(table, source)?.eta_squared.ok_or(String::from("Row has no eta squared!"))
+}
+
+#[allow(unused)]
+pub fn get_omega_squared(table: &AnovaTable, source: &str) -> Result<f64, String>
+{
+    get_effect_row(table, source)?.omega_squared.ok_or(String::from("Row has no omega squared!"))
+}
+
+// Effect sum of squares over effect plus residual sum of squares, the interval maps the
+// noncentral F bounds of lambda through lambda / (lambda + N)
+#[allow(unused)]
+pub fn get_partial_eta_squared(table: &AnovaTable, source: &str, confidence_level: f64) -> Result<EffectSize, String>
+{
+    check_confidence_level(confidence_level)?;
+    let row = get_effect_row(table, source)?;
+    let residual = table.get_row("Residual").ok_or(String::from("Table has no residual row!"))?;
+    let f_statistic = row.f_statistic.ok_or(String::from("Row has no F statistic!"))?;
+    let estimate = row.sum_of_squares / (row.sum_of_squares + residual.sum_of_squares);
+
+    let (df_1, df_2) = (row.degrees_of_freedom, residual.degrees_of_freedom);
+    let alpha = 1.0 - confidence_level;
+    let noncentral_sf = |lambda: f64| get_noncentral_f_sf(f_statistic, df_1, df_2, lambda);
+    let lower = solve_noncentrality(noncentral_sf, alpha / 2.0, 0.0)?;
+    let upper = solve_noncentrality(noncentral_sf, 1.0 - alpha / 2.0, 0.0)?;
+    let total = df_1 + df_2 + 1.0;
+    Ok(EffectSize { estimate, confidence_interval: (lower / (lower + total), upper / (upper + total)) })
+}
+
+// 2x2 table with exposure in rows and outcome in columns, cell [0, 0] counts exposed cases.
+// Zero cells get the Haldane-Anscombe correction of one half
+#[allow(unused)]
+pub fn get_odds_ratio(table: &ContingencyTable, confidence_level: f64) -> Result<EffectSize, String>
+{
+    let (a, b, c, d) = get_corrected_cells(table)?;
+    let estimate = a * d / (b * c);
+    let standard_error = (1.0 / a + 1.0 / b + 1.0 / c + 1.0 / d).sqrt();
+    Ok(EffectSize { estimate, confidence_interval: get_log_interval(estimate, standard_error, confidence_level)? })
+}
+
+// Risk of the outcome in the first row over the risk in the second row
+#[allow(unused)]
+pub fn get_risk_ratio(table: &ContingencyTable, confidence_level: f64) -> Result<EffectSize, String>
+{
+    let (a, b, c, d) = get_corrected_cells(table)?;
+    let estimate = (a / (a + b)) / (c / (c + d));
+    let standard_error = (1.0 / a - 1.0 / (a + b) + 1.0 / c - 1.0 / (c + d)).sqrt();
+    Ok(EffectSize { estimate, confidence_interval: get_log_interval(estimate, standard_error, confidence_level)? })
+}
+
+// P(X > Y) - P(X < Y) with Cliff's (1996) asymmetric interval
+#[allow(unused)]
+pub fn get_cliffs_delta(sample_1: &Array1<f64>, sample_2: &Array1<f64>, confidence_level: f64) -> Result<EffectSize, String>
+{
+    check_confidence_level(confidence_level)?;
+    let (n_1, n_2) = check_two_samples(sample_1, sample_2)?;
+    let dominance = |x: f64, y: f64| if x > y { 1.0 } else if x < y { -1.0 } else { 0.0 };
+    let row_means: Vec<f64> = sample_1.iter().map(|&x| sample_2.iter().map(|&y| dominance(x, y)).sum::<f64>() / n_2).collect();
+    let column_means: Vec<f64> = sample_2.iter().map(|&y| sample_1.iter().map(|&x| dominance(x, y)).sum::<f64>() / n_1).collect();
+    let estimate = row_means.iter().sum::<f64>() / n_1;
+
+    let within_sum: f64 = sample_1.iter().flat_map(|&x| sample_2.iter().map(move |&y| (dominance(x, y) - estimate).powi(2))).sum();
+    let row_sum: f64 = row_means.iter().map(|d| (d - estimate).powi(2)).sum();
+    let column_sum: f64 = column_means.iter().map(|d| (d - estimate).powi(2)).sum();
+    let variance = ((n_2.powi(2) * row_sum + n_1.powi(2) * column_sum - within_sum) / (n_1 * n_2 * (n_1 - 1.0) * (n_2 - 1.0))).max(0.0);
+
+    let z = get_normal_ppf(1.0 - (1.0 - confidence_level) / 2.0)?;
+    let standard_error = variance.sqrt();
+    let center = estimate - estimate.powi(3);
+    let width = z * standard_error * ((1.0 - estimate.powi(2)).powi(2) + z.powi(2) * variance).sqrt();
+    let denominator = 1.0 - estimate.powi(2) + z.powi(2) * variance;
+    let confidence_interval = if denominator > 0.0
+    {
+        (((center - width) / denominator).max(-1.0), ((center + width) / denominator).min(1.0))
+    }
+    else
+    {
+        (estimate, estimate)
+    };
+    Ok(EffectSize { estimate, confidence_interval })
+}
+
+// Probability that an observation of sample_1 exceeds one of sample_2, ties counting half.
+// This is (delta + 1) / 2 for Cliff's delta, so the interval follows from Cliff's
+#[allow(unused)]
+pub fn get_common_language_effect_size(sample_1: &Array1<f64>, sample_2: &Array1<f64>, confidence_level: f64) -> Result<EffectSize, String>
+{
+    let delta = get_cliffs_delta(sample_1, sample_2, confidence_level)?;
+    let (lower, upper) = delta.confidence_interval;
+    Ok(EffectSize { estimate: (delta.estimate + 1.0) / 2.0, confidence_interval: ((lower + 1.0) / 2.0, (upper + 1.0) / 2.0) })
+}
+
+// Bounds on the noncentrality parameter of an observed t statistic
+fn get_noncentral_t_interval(t: f64, degrees_of_freedom: f64, confidence_level: f64) -> Result<(f64, f64), String>
+{
+    check_confidence_level(confidence_level)?;
+    let alpha = 1.0 - confidence_level;
+    // The cdf at t decreases in the noncentrality, so the survival function increases
+    let sf = |noncentrality: f64| Ok(1.0 - get_noncentral_t_cdf(t, degrees_of_freedom, noncentrality)?);
+    let lower = solve_noncentrality(sf, alpha / 2.0, f64::NEG_INFINITY)?;
+    let upper = solve_noncentrality(sf, 1.0 - alpha / 2.0, f64::NEG_INFINITY)?;
+    Ok((lower, upper))
+}
+
+// Finds the noncentrality where an increasing survival function reaches the target,
+// returns the support bound if the target lies below it
+fn solve_noncentrality<F>(sf: F, target: f64, support_lower: f64) -> Result<f64, String>
+where F: Fn(f64) -> Result<f64, String>
+{
+    let (mut lower, mut upper) = if support_lower.is_finite() { (support_lower, support_lower + 1.0) } else { (-1.0, 1.0) };
+    if support_lower.is_finite() && sf(support_lower)? >= target
+    {
+        return Ok(support_lower);
+    }
+    while sf(lower)? > target
+    {
+        upper = lower;
+        lower = 2.0 * lower - 1.0;
+    }
+    while sf(upper)? < target
+    {
+        lower = upper;
+        upper = 2.0 * upper + 1.0;
+        if upper > 1e6
+        {
+            return Err(String::from("Noncentrality bound not found!"));
+        }
+    }
+    for _ in 0..200
+    {
+        let middle = 0.5 * (lower + upper);
+        if sf(middle)? < target
+        {
+            lower = middle;
+        }
+        else
+        {
+            upper = middle;
+        }
+        if upper - lower <= 1e-10 * (1.0 + middle.abs())
+        {
+            break;
+        }
+    }
+    Ok(0.5 * (lower + upper))
+}
+
+fn get_log_interval(estimate: f64, standard_error: f64, confidence_level: f64) -> Result<(f64, f64), String>
+{
+    check_confidence_level(confidence_level)?;
+    let z = get_normal_ppf(1.0 - (1.0 - confidence_level) / 2.0)?;
+    Ok(((estimate.ln() - z * standard_error).exp(), (estimate.ln() + z * standard_error).exp()))
+}
+
+fn get_corrected_cells(table: &ContingencyTable) -> Result<(f64, f64, f64, f64), String>
+{
+    let counts = table.get_counts();
+    if counts.dim() != (2, 2)
+    {
+        return Err(String::from("Odds and risk ratios need a 2x2 table!"));
+    }
+    let correction = if counts.iter().any(|&count| count == 0.0) { 0.5 } else { 0.0 };
+    Ok((counts[[0, 0]] + correction, counts[[0, 1]] + correction, counts[[1, 0]] + correction, counts[[1, 1]] + correction))
+}
+
+fn get_effect_row<'a>(table: &'a AnovaTable, source: &str) -> Result<&'a AnovaRow, String>
+{
+    table.get_row(source).ok_or(format!("Table has no row {}!", source))
+}
+
+fn check_two_samples(sample_1: &Array1<f64>, sample_2: &Array1<f64>) -> Result<(f64, f64), String>
+{
+    check_vector_for_nans(sample_1)?;
+    check_vector_for_nans(sample_2)?;
+    if sample_1.len() < 2 || sample_2.len() < 2
+    {
+        return Err(String::from("Each sample needs at least two observations!"));
+    }
+    Ok((sample_1.len() as f64, sample_2.len() as f64))
+}
+
+fn check_scale(variance: f64) -> Result<f64, String>
+{
+    if variance <= 0.0
+    {
+        return Err(String::from("Standard deviation must be positive!"));
+    }
+    Ok(variance.sqrt())
+}
+
+fn check_confidence_level(confidence_level: f64) -> Result<(), String>
+{
+    if confidence_level <= 0.0 || confidence_level >= 1.0
+    {
+        return Err(String::from("Confidence level must be between 0 and 1!"));
+    }
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use super::distributions::get_t_ppf;
+use super::simple_linear_regression::{get_weighted_best_fitting_intercept, get_weighted_best_fitting_slope};
+use super::utilities::{check_vector_for_nans, get_mean, get_median, get_quantile};
+use ndarray::Array1;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub enum IntervalMethod
+{
+    Jackknife,
+    Bootstrap { num_resamples: usize, seed: u64 },
+}
+
+#[allow(unused)]
+#[derive(Debug)]
+pub struct RegressionWithIntervals
+{
+    pub slope: f64,
+    pub intercept: f64,
+    pub slope_interval: (f64, f64),
+    pub intercept_interval: (f64, f64),
+}
+
+#[allow(unused)]
+pub fn get_weighted_least_squares_regression(independent_variable: &Array1<f64>,
+                                              dependent_variable: &Array1<f64>,
+                                              weights: &Array1<f64>,
+                                              interval_method: IntervalMethod,
+                                              confidence_level: f64) -> Result<RegressionWithIntervals, String>
+{
+    check_inputs(independent_variable, dependent_variable)?;
+    if weights.len() != independent_variable.len()
+    {
+        return Err(String::from("Vector lengths do not match!"));
+    }
+    let estimator = |indices: &[usize]| -> Result<(f64, f64), String>
+    {
+        let x = select(independent_variable, indices);
+        let y = select(dependent_variable, indices);
+        let w = select(weights, indices);
+        Ok((get_weighted_best_fitting_slope(&x, &y, &w)?, get_weighted_best_fitting_intercept(&x, &y, &w)?))
+    };
+    get_regression_with_intervals(independent_variable.len(), estimator, interval_method, confidence_level)
+}
+
+// error_variance_ratio is var(error in y) / var(error in x)
+#[allow(unused)]
+pub fn get_deming_regression(independent_variable: &Array1<f64>,
+                             dependent_variable: &Array1<f64>,
+                             error_variance_ratio: f64,
+                             interval_method: IntervalMethod,
+                             confidence_level: f64) -> Result<RegressionWithIntervals, String>
+{
+    check_inputs(independent_variable, dependent_variable)?;
+    if error_variance_ratio <= 0.0
+    {
+        return Err(String::from("Error variance ratio must be positive!"));
+    }
+    let estimator = |indices: &[usize]|
+    {
+        get_deming_estimate(&select(independent_variable, indices), &select(dependent_variable, indices), error_variance_ratio)
+    };
+    get_regression_with_intervals(independent_variable.len(), estimator, interval_method, confidence_level)
+}
+
+// Total least squares is Deming regression with equal error variances
+#[allow(unused)]
+pub fn get_orthogonal_regression(independent_variable: &Array1<f64>,
+                                 dependent_variable: &Array1<f64>,
+                                 interval_method: IntervalMethod,
+                                 confidence_level: f64) -> Result<RegressionWithIntervals, String>
+{
+    get_deming_regression(independent_variable, dependent_variable, 1.0, interval_method, confidence_level)
+}
+
+#[allow(unused)]
+pub fn get_passing_bablok_regression(independent_variable: &Array1<f64>,
+                                     dependent_variable: &Array1<f64>,
+                                     interval_method: IntervalMethod,
+                                     confidence_level: f64) -> Result<RegressionWithIntervals, String>
+{
+    check_inputs(independent_variable, dependent_variable)?;
+    let estimator = |indices: &[usize]|
+    {
+        get_passing_bablok_estimate(&select(independent_variable, indices), &select(dependent_variable, indices))
+    };
+    get_regression_with_intervals(independent_variable.len(), estimator, interval_method, confidence_level)
+}
+
+fn get_deming_estimate(x: &Array1<f64>, y: &Array1<f64>, error_variance_ratio: f64) -> Result<(f64, f64), String>
+{
+    let mean_x = get_mean(x)?;
+    let mean_y = get_mean(y)?;
+    let s_xx = x.iter().map(|x_i| (x_i - mean_x).powi(2)).sum::<f64>();
+    let s_yy = y.iter().map(|y_i| (y_i - mean_y).powi(2)).sum::<f64>();
+    let s_xy = x.iter().zip(y).map(|(x_i, y_i)| (x_i - mean_x) * (y_i - mean_y)).sum::<f64>();
+    if s_xy == 0.0
+    {
+        return Err(String::from("Covariance of x and y is zero, slope is undefined!"));
+    }
+    let difference = s_yy - error_variance_ratio * s_xx;
+    let slope = (difference + (difference.powi(2) + 4.0 * error_variance_ratio * s_xy.powi(2)).sqrt()) / (2.0 * s_xy);
+    Ok((slope, mean_y - slope * mean_x))
+}
+
+fn get_passing_bablok_estimate(x: &Array1<f64>, y: &Array1<f64>) -> Result<(f64, f64), String>
+{
+    let n = x.len();
+    let mut slopes = Vec::with_capacity(n * (n - 1) / 2);
+    for i in 0..n
+    {
+        for j in i + 1..n
+        {
+            let dx = x[j] - x[i];
+            let dy = y[j] - y[i];
+            if dx == 0.0 && dy == 0.0
+            {
+                continue;
+            }
+            let slope = if dx == 0.0 { dy.signum() * f64::INFINITY } else { dy / dx };
+            if slope != -1.0
+            {
+                slopes.push(slope);
+            }
+        }
+    }
+    if slopes.is_empty()
+    {
+        return Err(String::from("Not enough distinct points to estimate a slope!"));
+    }
+    slopes.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    // The median is shifted by the number of slopes below -1 to keep the estimator scale invariant
+    let offset = slopes.iter().filter(|&&slope| slope < -1.0).count();
+    let num_slopes = slopes.len();
+    let slope = if num_slopes % 2 == 1
+    {
+        slopes.get(num_slopes / 2 + offset).copied()
+    }
+    else
+    {
+        match (slopes.get(num_slopes / 2 + offset - 1), slopes.get(num_slopes / 2 + offset))
+        {
+            (Some(lower), Some(upper)) => Some((lower + upper) / 2.0),
+            _ => None,
+        }
+    };
+    let slope = match slope
+    {
+        Some(slope) if slope.is_finite() => slope,
+        _ => return Err(String::from("Slope is undefined for this data!")),
+    };
+    let intercept = get_median(&x.iter().zip(y).map(|(x_i, y_i)| y_i - slope * x_i).collect())?;
+    Ok((slope, intercept))
+}
+
+fn get_regression_with_intervals<F>(n: usize, estimator: F, interval_method: IntervalMethod, confidence_level: f64)
+-> Result<RegressionWithIntervals, String>
+where F: Fn(&[usize]) -> Result<(f64, f64), String>
+{
+    if confidence_level <= 0.0 || confidence_level >= 1.0
+    {
+        return Err(String::from("Confidence level must be between 0 and 1!"));
+    }
+    let all_indices: Vec<usize> = (0..n).collect();
+    let (slope, intercept) = estimator(&all_indices)?;
+    let alpha = 1.0 - confidence_level;
+
+    let (slope_interval, intercept_interval) = match interval_method
+    {
+        IntervalMethod::Jackknife =>
+        {
+            let mut slopes = Vec::with_capacity(n);
+            let mut intercepts = Vec::with_capacity(n);
+            for left_out in 0..n
+            {
+                let indices: Vec<usize> = (0..n).filter(|&i| i != left_out).collect();
+                let (slope, intercept) = estimator(&indices)?;
+                slopes.push(slope);
+                intercepts.push(intercept);
+            }
+            let t_critical = get_t_ppf(1.0 - alpha / 2.0, (n - 1) as f64)?;
+            let slope_error = t_critical * get_jackknife_standard_error(&slopes);
+            let intercept_error = t_critical * get_jackknife_standard_error(&intercepts);
+            ((slope - slope_error, slope + slope_error), (intercept - intercept_error, intercept + intercept_error))
+        },
+        IntervalMethod::Bootstrap { num_resamples, seed } =>
+        {
+            let rng = fastrand::Rng::with_seed(seed);
+            let mut slopes = Vec::with_capacity(num_resamples);
+            let mut intercepts = Vec::with_capacity(num_resamples);
+            for _ in 0..num_resamples
+            {
+                let indices: Vec<usize> = (0..n).map(|_| rng.usize(0..n)).collect();
+                // Degenerate resamples, e.g. with a single distinct x, are skipped
+                if let Ok((slope, intercept)) = estimator(&indices)
+                {
+                    if slope.is_finite() && intercept.is_finite()
+                    {
+                        slopes.push(slope);
+                        intercepts.push(intercept);
+                    }
+                }
+            }
+            if slopes.is_empty()
+            {
+                return Err(String::from("All bootstrap resamples were degenerate!"));
+            }
+            let slopes = Array1::from(slopes);
+            let intercepts = Array1::from(intercepts);
+            ((get_quantile(&slopes, alpha / 2.0)?, get_quantile(&slopes, 1.0 - alpha / 2.0)?),
+             (get_quantile(&intercepts, alpha / 2.0)?, get_quantile(&intercepts, 1.0 - alpha / 2.0)?))
+        },
+    };
+
+    Ok(RegressionWithIntervals { slope, intercept, slope_interval, intercept_interval })
+}
+
+fn get_jackknife_standard_error(estimates: &[f64]) -> f64
+{
+    let n = estimates.len() as f64;
+    let mean = estimates.iter().sum::<f64>() / n;
+    ((n - 1.0) / n * estimates.iter().map(|estimate| (estimate - mean).powi(2)).sum::<f64>()).sqrt()
+}
+
+fn check_inputs(independent_variable: &Array1<f64>, dependent_variable: &Array1<f64>) -> Result<(), String>
+{
+    check_vector_for_nans(independent_variable)?;
+    check_vector_for_nans(dependent_variable)?;
+    if independent_variable.len() != dependent_variable.len()
+    {
+        return Err(String::from("Vector lengths do not match!"));
+    }
+    if independent_variable.len() < 3
+    {
+        return Err(String::from("Need at least three observations!"));
+    }
+    Ok(())
+}
+
+fn select(vector: &Array1<f64>, indices: &[usize]) -> Array1<f64>
+{
+    indices.iter().map(|&i| vector[i]).collect()
+}
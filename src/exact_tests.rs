@@ -0,0 +1,412 @@
+use super::contingency_table::ContingencyTable;
+use super::counting::{get_ln_combinations, get_ln_factorial};
+use super::distributions::{get_beta_ppf, get_chi_squared_sf};
+use super::hypothesis_testing::{TestKind, TestResult};
+use ndarray::{Array2, Axis};
+use std::collections::HashMap;
+
+// Relative tolerance when comparing table probabilities, as in R's fisher.test and binom.test
+const PROBABILITY_TOLERANCE: f64 = 1e-7;
+// Partial tables the network algorithm may extend before giving up on an exact p-value
+const MAX_NETWORK_PATHS: usize = 10_000_000;
+// Path lengths closer than this are merged, they belong to tables with the same probability
+const PATH_LENGTH_RESOLUTION: f64 = 1e-9;
+
+#[allow(unused)]
+#[derive(Debug)]
+pub struct FisherExactResult
+{
+    pub test_result: TestResult,
+    pub odds_ratio: f64,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub enum FisherMethod
+{
+    // Enumerates every table with the observed margins
+    Exact,
+    MonteCarlo { num_simulations: usize, seed: u64 },
+}
+
+#[allow(unused)]
+#[derive(Debug)]
+pub struct BinomialTestResult
+{
+    pub test_result: TestResult,
+    pub estimate: f64,
+    pub confidence_interval: (f64, f64),
+}
+
+// OneTailedUpper tests whether the odds ratio is greater than one
+#[allow(unused)]
+pub fn get_fisher_exact_test(table: &ContingencyTable, test_kind: TestKind) -> Result<FisherExactResult, String>
+{
+    let counts = get_integer_counts(table)?;
+    if counts.dim() != (2, 2)
+    {
+        return Err(String::from("Fisher's exact test for a given tail needs a 2x2 table, use the r x c version!"));
+    }
+    let (a, b, c, d) = (counts[[0, 0]], counts[[0, 1]], counts[[1, 0]], counts[[1, 1]]);
+    let row_1 = a + b;
+    let column_1 = a + c;
+    let total = a + b + c + d;
+
+    // The top left cell follows a hypergeometric distribution given the margins
+    let minimum = column_1.saturating_sub(total - row_1);
+    let maximum = row_1.min(column_1);
+    let ln_denominator = get_ln_combinations(total, column_1)?;
+    let mut ln_probabilities = Vec::with_capacity((maximum - minimum + 1) as usize);
+    for x in minimum..=maximum
+    {
+        ln_probabilities.push((x, get_ln_combinations(row_1, x)? + get_ln_combinations(total - row_1, column_1 - x)? - ln_denominator));
+    }
+    let probabilities: Vec<(u64, f64)> = ln_probabilities.iter().map(|&(x, ln_probability)| (x, ln_probability.exp())).collect();
+    let observed_probability = probabilities.iter().find(|(x, _)| *x == a).unwrap().1;
+
+    let p_value = match test_kind
+    {
+        TestKind::OneTailedUpper => probabilities.iter().filter(|(x, _)| *x >= a).map(|(_, p)| p).sum(),
+        TestKind::OneTailedLower => probabilities.iter().filter(|(x, _)| *x <= a).map(|(_, p)| p).sum(),
+        TestKind::TwoTailed =>
+        {
+            probabilities.iter()
+                .filter(|(_, p)| *p <= observed_probability * (1.0 + PROBABILITY_TOLERANCE))
+                .map(|(_, p)| p)
+                .sum()
+        },
+    };
+    let odds_ratio = get_conditional_odds_ratio(&ln_probabilities, a);
+    Ok(FisherExactResult { test_result: TestResult::new("Fisher's exact test", odds_ratio, vec![], p_value), odds_ratio })
+}
+
+// Conditional maximum likelihood estimate as in R's fisher.test: the odds ratio of the noncentral hypergeometric
+// distribution whose mean is the observed top left cell. Unlike the sample odds ratio ad / bc it is defined with zero
+// cells, being zero or infinite when the observed cell is at the bottom or top of its range
+fn get_conditional_odds_ratio(ln_probabilities: &[(u64, f64)], observed: u64) -> f64
+{
+    let (minimum, maximum) = (ln_probabilities[0].0, ln_probabilities[ln_probabilities.len() - 1].0);
+    if observed == minimum
+    {
+        return 0.0;
+    }
+    if observed == maximum
+    {
+        return f64::INFINITY;
+    }
+    let get_mean = |ln_odds_ratio: f64|
+    {
+        let ln_weights: Vec<f64> = ln_probabilities.iter().map(|&(x, ln_probability)| ln_probability + x as f64 * ln_odds_ratio).collect();
+        let largest = ln_weights.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = ln_weights.iter().map(|&ln_weight| (ln_weight - largest).exp()).collect();
+        ln_probabilities.iter().zip(&weights).map(|(&(x, _), weight)| x as f64 * weight).sum::<f64>() / weights.iter().sum::<f64>()
+    };
+
+    // The mean increases with the log odds ratio, bracket the observed cell and bisect
+    let observed = observed as f64;
+    let (mut lower, mut upper) = (-1.0, 1.0);
+    while get_mean(lower) > observed
+    {
+        lower *= 2.0;
+    }
+    while get_mean(upper) < observed
+    {
+        upper *= 2.0;
+    }
+    for _ in 0..100
+    {
+        let middle = 0.5 * (lower + upper);
+        if get_mean(middle) < observed { lower = middle; } else { upper = middle; }
+    }
+    (0.5 * (lower + upper)).exp()
+}
+
+// Two-sided test on tables of any size, the p-value sums the probabilities of all tables
+// with the observed margins that are no more likely than the observed one
+#[allow(unused)]
+pub fn get_fisher_exact_test_r_by_c(table: &ContingencyTable, method: FisherMethod) -> Result<TestResult, String>
+{
+    let counts = get_integer_counts(table)?;
+    let row_totals: Vec<u64> = counts.sum_axis(Axis(1)).to_vec();
+    let column_totals: Vec<u64> = counts.sum_axis(Axis(0)).to_vec();
+    let total: u64 = row_totals.iter().sum();
+    let ln_factorials: Vec<f64> = (0..=total).map(get_ln_factorial).collect();
+    let ln_margins = row_totals.iter().chain(column_totals.iter()).map(|&m| ln_factorials[m as usize]).sum::<f64>() - ln_factorials[total as usize];
+    let observed_ln_probability = ln_margins - counts.iter().map(|&count| ln_factorials[count as usize]).sum::<f64>();
+    let threshold = observed_ln_probability + PROBABILITY_TOLERANCE.ln_1p();
+
+    let p_value = match method
+    {
+        FisherMethod::Exact => get_network_p_value(&counts, &ln_factorials, threshold - ln_margins)?,
+        FisherMethod::MonteCarlo { num_simulations, seed } =>
+        {
+            if num_simulations == 0
+            {
+                return Err(String::from("Need at least one simulation!"));
+            }
+            // Shuffling the column labels against the row labels keeps both margins fixed
+            let rng = fastrand::Rng::with_seed(seed);
+            let row_labels: Vec<usize> = row_totals.iter().enumerate().flat_map(|(i, &n)| std::iter::repeat_n(i, n as usize)).collect();
+            let mut column_labels: Vec<usize> = column_totals.iter().enumerate().flat_map(|(j, &n)| std::iter::repeat_n(j, n as usize)).collect();
+            let mut num_as_extreme = 0;
+            for _ in 0..num_simulations
+            {
+                rng.shuffle(&mut column_labels);
+                let mut simulated = Array2::<u64>::zeros(counts.dim());
+                for (&i, &j) in row_labels.iter().zip(&column_labels)
+                {
+                    simulated[[i, j]] += 1;
+                }
+                let ln_probability = ln_margins - simulated.iter().map(|&count| ln_factorials[count as usize]).sum::<f64>();
+                if ln_probability <= threshold
+                {
+                    num_as_extreme += 1;
+                }
+            }
+            (1 + num_as_extreme) as f64 / (1 + num_simulations) as f64
+        },
+    };
+    Ok(TestResult::new("Fisher's exact test", observed_ln_probability.exp(), vec![], p_value))
+}
+
+// Bounds on the path lengths -sum ln x! from a node of the network to its end, and the log of the sum of
+// exp(length) over all paths, which is (sum of rows)! / (prod rows! prod columns!)
+#[derive(Debug, Clone, Copy)]
+struct NetworkNode
+{
+    shortest: f64,
+    longest: f64,
+    ln_total: f64,
+}
+
+// Mehta and Patel (1983): the tables are paths through a network whose stages are the columns and whose nodes are the
+// sorted remaining row totals. Paths with equal lengths into a node are merged, and a partial path is settled at once
+// when its bounds put all or none of its completions at or below the threshold
+fn get_network_p_value(counts: &Array2<u64>, ln_factorials: &[f64], threshold: f64) -> Result<f64, String>
+{
+    // The nodes are kept small by letting the shorter side be the rows
+    let counts = if counts.nrows() > counts.ncols() { counts.t().to_owned() } else { counts.to_owned() };
+    let mut row_totals: Vec<u64> = counts.sum_axis(Axis(1)).to_vec();
+    row_totals.sort_unstable_by(|a, b| b.cmp(a));
+    let mut column_totals: Vec<u64> = counts.sum_axis(Axis(0)).to_vec();
+    column_totals.sort_unstable_by(|a, b| b.cmp(a));
+    let ln_margins = row_totals.iter().chain(column_totals.iter()).map(|&m| ln_factorials[m as usize]).sum::<f64>()
+        - ln_factorials[row_totals.iter().sum::<u64>() as usize];
+
+    let mut nodes: HashMap<(usize, Vec<u64>), NetworkNode> = HashMap::new();
+    // Paths into each node of the current stage, keyed by their rounded length, with their length and multiplicity
+    let mut stage: HashMap<Vec<u64>, HashMap<i64, (f64, f64)>> = HashMap::new();
+    stage.insert(row_totals, HashMap::from([(0, (0.0, 1.0))]));
+    let (mut p_value, mut num_paths) = (0.0, 0usize);
+    for (k, &column_total) in column_totals.iter().enumerate()
+    {
+        let mut next_stage: HashMap<Vec<u64>, HashMap<i64, (f64, f64)>> = HashMap::new();
+        for (rows, paths) in stage
+        {
+            get_column_fillings(&rows, column_total, 0, &mut vec![0; rows.len()], &mut |column|
+            {
+                count_network_path(&mut num_paths)?;
+                let step = -column.iter().map(|&x| ln_factorials[x as usize]).sum::<f64>();
+                let child = get_child_rows(&rows, column);
+                let node = *nodes.entry((k + 1, child.clone()))
+                    .or_insert_with(|| get_network_node(&child, &column_totals[k + 1..], ln_factorials));
+                for &(length, multiplicity) in paths.values()
+                {
+                    count_network_path(&mut num_paths)?;
+                    let length = length + step;
+                    if length + node.longest <= threshold
+                    {
+                        p_value += multiplicity * (ln_margins + length + node.ln_total).exp();
+                    }
+                    else if length + node.shortest <= threshold
+                    {
+                        let merged = next_stage.entry(child.clone()).or_default()
+                            .entry((length / PATH_LENGTH_RESOLUTION).round() as i64)
+                            .or_insert((length, 0.0));
+                        merged.1 += multiplicity;
+                    }
+                }
+                Ok(())
+            })?;
+        }
+        stage = next_stage;
+    }
+    Ok(p_value.min(1.0))
+}
+
+// Relaxes the row totals to upper bounds on each column separately: spreading a column evenly maximises -sum ln x!,
+// piling it into the largest rows minimises it
+fn get_network_node(rows: &[u64], column_totals: &[u64], ln_factorials: &[f64]) -> NetworkNode
+{
+    let (mut shortest, mut longest) = (0.0, 0.0);
+    for &column_total in column_totals
+    {
+        // Fill the smallest rows up to their totals while they are below an even share of what is left
+        let mut remaining = column_total;
+        for (k, &row) in rows.iter().rev().enumerate()
+        {
+            let num_left = (rows.len() - k) as u64;
+            if row * num_left < remaining
+            {
+                longest -= ln_factorials[row as usize];
+                remaining -= row;
+                continue;
+            }
+            let (share, extra) = (remaining / num_left, remaining % num_left);
+            longest -= extra as f64 * ln_factorials[share as usize + 1] + (num_left - extra) as f64 * ln_factorials[share as usize];
+            break;
+        }
+        let mut remaining = column_total;
+        for &row in rows
+        {
+            let x = remaining.min(row);
+            shortest -= ln_factorials[x as usize];
+            remaining -= x;
+        }
+    }
+    let ln_total = ln_factorials[rows.iter().sum::<u64>() as usize]
+        - rows.iter().chain(column_totals).map(|&m| ln_factorials[m as usize]).sum::<f64>();
+    NetworkNode { shortest, longest, ln_total }
+}
+
+fn get_child_rows(rows: &[u64], column: &[u64]) -> Vec<u64>
+{
+    let mut child: Vec<u64> = rows.iter().zip(column).map(|(r, x)| r - x).collect();
+    child.sort_unstable_by(|a, b| b.cmp(a));
+    child
+}
+
+fn count_network_path(num_paths: &mut usize) -> Result<(), String>
+{
+    *num_paths += 1;
+    if *num_paths > MAX_NETWORK_PATHS
+    {
+        return Err(String::from("Too many tables for an exact p-value, use FisherMethod::MonteCarlo!"));
+    }
+    Ok(())
+}
+
+// Visits all ways of splitting a column total over rows with the given remaining totals
+fn get_column_fillings<F>(rows: &[u64], remaining: u64, i: usize, column: &mut [u64], visit: &mut F) -> Result<(), String>
+where F: FnMut(&[u64]) -> Result<(), String>
+{
+    if i == rows.len() - 1
+    {
+        if remaining <= rows[i]
+        {
+            column[i] = remaining;
+            visit(column)?;
+        }
+        return Ok(());
+    }
+    let capacity_below: u64 = rows[i + 1..].iter().sum();
+    for x in remaining.saturating_sub(capacity_below)..=remaining.min(rows[i])
+    {
+        column[i] = x;
+        get_column_fillings(rows, remaining - x, i + 1, column, visit)?;
+    }
+    Ok(())
+}
+
+// Clopper-Pearson interval from beta quantiles
+#[allow(unused)]
+pub fn get_binomial_test(successes: u64, trials: u64, probability: f64, test_kind: TestKind, confidence_level: f64)
+-> Result<BinomialTestResult, String>
+{
+    if successes > trials || trials == 0
+    {
+        return Err(String::from("Successes must not exceed the number of trials, which must be positive!"));
+    }
+    if probability <= 0.0 || probability >= 1.0
+    {
+        return Err(String::from("Probability must be between 0 and 1!"));
+    }
+    let probabilities: Vec<f64> = (0..=trials)
+        .map(|k| get_binomial_ln_pmf(k, trials, probability).map(f64::exp))
+        .collect::<Result<Vec<f64>, String>>()?;
+    let observed_probability = probabilities[successes as usize];
+
+    let p_value = match test_kind
+    {
+        TestKind::OneTailedUpper => probabilities[successes as usize..].iter().sum(),
+        TestKind::OneTailedLower => probabilities[..=successes as usize].iter().sum(),
+        TestKind::TwoTailed =>
+        {
+            probabilities.iter().filter(|&&p| p <= observed_probability * (1.0 + PROBABILITY_TOLERANCE)).sum()
+        },
+    };
+
+    Ok
+    (
+        BinomialTestResult
+        {
+            test_result: TestResult::new("Exact binomial test", successes as f64, vec![], p_value),
+            estimate: successes as f64 / trials as f64,
+            confidence_interval: get_clopper_pearson_interval(successes, trials, confidence_level)?,
+        }
+    )
+}
+
+#[allow(unused)]
+pub fn get_clopper_pearson_interval(successes: u64, trials: u64, confidence_level: f64) -> Result<(f64, f64), String>
+{
+    if confidence_level <= 0.0 || confidence_level >= 1.0
+    {
+        return Err(String::from("Confidence level must be between 0 and 1!"));
+    }
+    if successes > trials || trials == 0
+    {
+        return Err(String::from("Successes must not exceed the number of trials, which must be positive!"));
+    }
+    let alpha = 1.0 - confidence_level;
+    let (x, n) = (successes as f64, trials as f64);
+    let lower = if successes == 0 { 0.0 } else { get_beta_ppf(alpha / 2.0, x, n - x + 1.0)? };
+    let upper = if successes == trials { 1.0 } else { get_beta_ppf(1.0 - alpha / 2.0, x + 1.0, n - x)? };
+    Ok((lower, upper))
+}
+
+#[allow(unused)]
+pub fn get_binomial_ln_pmf(k: u64, n: u64, probability: f64) -> Result<f64, String>
+{
+    Ok(get_ln_combinations(n, k)? + k as f64 * probability.ln() + (n - k) as f64 * (1.0 - probability).ln())
+}
+
+// Paired 2x2 table, only the discordant cells [0, 1] and [1, 0] carry information
+#[allow(unused)]
+pub fn get_mcnemar_test(table: &ContingencyTable, exact: bool, continuity_correction: bool) -> Result<TestResult, String>
+{
+    let counts = get_integer_counts(table)?;
+    if counts.dim() != (2, 2)
+    {
+        return Err(String::from("McNemar's test needs a 2x2 table!"));
+    }
+    let (b, c) = (counts[[0, 1]], counts[[1, 0]]);
+    if b + c == 0
+    {
+        return Err(String::from("McNemar's test needs at least one discordant pair!"));
+    }
+
+    if exact
+    {
+        let result = get_binomial_test(b, b + c, 0.5, TestKind::TwoTailed, 0.95)?;
+        Ok(TestResult::new("McNemar exact", b as f64, vec![], result.test_result.p_value))
+    }
+    else
+    {
+        let correction = if continuity_correction { 1.0 } else { 0.0 };
+        let difference = ((b as f64 - c as f64).abs() - correction).max(0.0);
+        let statistic = difference.powi(2) / (b + c) as f64;
+        Ok(TestResult::new("McNemar", statistic, vec![1.0], get_chi_squared_sf(statistic, 1.0)?))
+    }
+}
+
+fn get_integer_counts(table: &ContingencyTable) -> Result<Array2<u64>, String>
+{
+    let counts = table.get_counts();
+    if counts.iter().any(|&count| count.fract() != 0.0)
+    {
+        return Err(String::from("Exact tests need integer counts!"));
+    }
+    Ok(counts.mapv(|count| count as u64))
+}
+
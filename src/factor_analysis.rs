@@ -0,0 +1,422 @@
+use super::distributions::get_chi_squared_sf;
+use super::hypothesis_testing::TestResult;
+use super::linear_algebra::{get_inverse, get_least_squares, get_matrix_product, get_singular_value_decomposition, get_symmetric_eigendecomposition};
+use ndarray::{Array1, Array2, Axis};
+
+const MAX_EXTRACTION_ITERATIONS: usize = 100000;
+const EXTRACTION_TOLERANCE: f64 = 1e-10;
+const MAX_ROTATION_ITERATIONS: usize = 1000;
+const ROTATION_TOLERANCE: f64 = 1e-6;
+// Lower bound for the uniquenesses in maximum likelihood, as in R's factanal
+const MIN_UNIQUENESS: f64 = 0.005;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FactorExtraction
+{
+    MaximumLikelihood,
+    // Iterated to convergence from squared multiple correlations as initial communalities
+    PrincipalAxis,
+}
+
+// Orthogonal rotations keep the factors uncorrelated, promax and oblimin let them correlate.
+// Promax raises the varimax loadings to power (4 in R), oblimin with gamma 0 is quartimin
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rotation
+{
+    None,
+    Varimax,
+    Quartimax,
+    Promax { power: f64 },
+    Oblimin { gamma: f64 },
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct FactorAnalysis
+{
+    num_factors: usize,
+    extraction: FactorExtraction,
+    rotation: Rotation,
+}
+
+// Loadings are the pattern matrix with one factor per column, factors are ordered by their sum of squared
+// loadings and reflected to positive column sums. Communalities are the variances explained by the
+// common factors and do not change under rotation
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct FactorSolution
+{
+    pub loadings: Array2<f64>,
+    pub communalities: Array1<f64>,
+    pub uniquenesses: Array1<f64>,
+    pub factor_correlations: Array2<f64>,
+}
+
+// Kaiser-Meyer-Olkin measure of sampling adequacy, overall and for each variable
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct SamplingAdequacy
+{
+    pub overall: f64,
+    pub variables: Array1<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RotationCriterion
+{
+    Varimax,
+    Quartimax,
+    Oblimin(f64),
+}
+
+#[allow(unused)]
+impl FactorAnalysis
+{
+    pub fn new(num_factors: usize, extraction: FactorExtraction) -> Self
+    {
+        FactorAnalysis { num_factors, extraction, rotation: Rotation::None }
+    }
+
+    pub fn with_rotation(mut self, rotation: Rotation) -> Self
+    {
+        self.rotation = rotation;
+        self
+    }
+
+    // Works on a correlation matrix, e.g. from utilities::get_correlation_coefficient_matrix
+    pub fn fit(&self, correlation: &Array2<f64>) -> Result<FactorSolution, String>
+    {
+        let num_variables = check_correlation_matrix(correlation)?;
+        if self.num_factors == 0 || self.num_factors >= num_variables
+        {
+            return Err(String::from("Number of factors must be between 1 and the number of variables minus one!"));
+        }
+
+        let unrotated = match self.extraction
+        {
+            FactorExtraction::MaximumLikelihood => get_maximum_likelihood_loadings(correlation, self.num_factors)?,
+            FactorExtraction::PrincipalAxis => get_principal_axis_loadings(correlation, self.num_factors)?,
+        };
+        let (loadings, factor_correlations) = if self.num_factors == 1
+        {
+            (unrotated, Array2::eye(1))
+        }
+        else
+        {
+            match self.rotation
+            {
+                Rotation::None => (unrotated, Array2::eye(self.num_factors)),
+                Rotation::Varimax => (get_orthogonal_rotation(&unrotated, RotationCriterion::Varimax)?.0, Array2::eye(self.num_factors)),
+                Rotation::Quartimax => (get_orthogonal_rotation(&unrotated, RotationCriterion::Quartimax)?.0, Array2::eye(self.num_factors)),
+                Rotation::Promax { power } => get_promax_rotation(&unrotated, power)?,
+                Rotation::Oblimin { gamma } => get_oblique_rotation(&unrotated, RotationCriterion::Oblimin(gamma))?,
+            }
+        };
+        let (loadings, factor_correlations) = get_ordered_factors(&loadings, &factor_correlations);
+
+        // h_i^2 = (L Phi L^T)_ii
+        let common_part = get_matrix_product(&loadings, &factor_correlations)?;
+        let communalities: Array1<f64> = (common_part * &loadings).sum_axis(Axis(1));
+        let uniquenesses = communalities.mapv(|h| 1.0 - h);
+        Ok(FactorSolution { loadings, communalities, uniquenesses, factor_correlations })
+    }
+}
+
+// KMO compares the correlations with the partial correlations given all other variables,
+// values below 0.5 are commonly deemed unacceptable for factor analysis
+#[allow(unused)]
+pub fn get_kmo(correlation: &Array2<f64>) -> Result<SamplingAdequacy, String>
+{
+    let num_variables = check_correlation_matrix(correlation)?;
+    let inverse = get_inverse(correlation)?;
+    let mut squared_correlations = Array1::<f64>::zeros(num_variables);
+    let mut squared_partial_correlations = Array1::<f64>::zeros(num_variables);
+    for i in 0..num_variables
+    {
+        for j in (0..num_variables).filter(|&j| j != i)
+        {
+            squared_correlations[i] += correlation[[i, j]].powi(2);
+            squared_partial_correlations[i] += inverse[[i, j]].powi(2) / (inverse[[i, i]] * inverse[[j, j]]);
+        }
+    }
+    let total_correlation = squared_correlations.sum();
+    let overall = total_correlation / (total_correlation + squared_partial_correlations.sum());
+    let variables = &squared_correlations / &(&squared_correlations + &squared_partial_correlations);
+    Ok(SamplingAdequacy { overall, variables })
+}
+
+// Null hypothesis: the correlation matrix is the identity, -(n - 1 - (2p + 5) / 6) ln|R| is chi-squared
+// with p (p - 1) / 2 degrees of freedom
+#[allow(unused)]
+pub fn get_bartlett_sphericity_test(correlation: &Array2<f64>, num_observations: usize) -> Result<TestResult, String>
+{
+    let num_variables = check_correlation_matrix(correlation)? as f64;
+    let eigenvalues = get_symmetric_eigendecomposition(correlation)?.eigenvalues;
+    if eigenvalues.iter().any(|&eigenvalue| eigenvalue <= 0.0)
+    {
+        return Err(String::from("Correlation matrix must be positive definite!"));
+    }
+    let factor = num_observations as f64 - 1.0 - (2.0 * num_variables + 5.0) / 6.0;
+    if factor <= 0.0
+    {
+        return Err(String::from("Not enough observations for this number of variables!"));
+    }
+    let statistic = -factor * eigenvalues.mapv(f64::ln).sum();
+    let degrees_of_freedom = num_variables * (num_variables - 1.0) / 2.0;
+    Ok(TestResult::new("Bartlett's test of sphericity", statistic, vec![degrees_of_freedom], get_chi_squared_sf(statistic, degrees_of_freedom)?))
+}
+
+fn check_correlation_matrix(correlation: &Array2<f64>) -> Result<usize, String>
+{
+    let num_variables = correlation.nrows();
+    if num_variables < 2 || correlation.ncols() != num_variables || correlation.diag().iter().any(|&d| (d - 1.0).abs() > 1e-8)
+    {
+        return Err(String::from("Need a square correlation matrix of at least two variables with unit diagonal!"));
+    }
+    Ok(num_variables)
+}
+
+// Leading eigenvectors scaled by the square roots of their eigenvalues, negative eigenvalues give zero loadings
+fn get_leading_loadings(matrix: &Array2<f64>, num_factors: usize) -> Result<Array2<f64>, String>
+{
+    let decomposition = get_symmetric_eigendecomposition(matrix)?;
+    let mut loadings = decomposition.eigenvectors.slice(ndarray::s![.., ..num_factors]).to_owned();
+    for (k, mut column) in loadings.columns_mut().into_iter().enumerate()
+    {
+        column *= decomposition.eigenvalues[k].max(0.0).sqrt();
+    }
+    Ok(loadings)
+}
+
+// Replaces the diagonal by communalities h^2 and refactors until they settle
+fn get_principal_axis_loadings(correlation: &Array2<f64>, num_factors: usize) -> Result<Array2<f64>, String>
+{
+    let inverse = get_inverse(correlation)?;
+    let mut communalities = inverse.diag().mapv(|d| 1.0 - 1.0 / d);
+    let mut reduced = correlation.to_owned();
+    for _ in 0..MAX_EXTRACTION_ITERATIONS
+    {
+        reduced.diag_mut().assign(&communalities);
+        let loadings = get_leading_loadings(&reduced, num_factors)?;
+        let updated = loadings.mapv(|l| l * l).sum_axis(Axis(1));
+        // A communality above one implies a negative unique variance, iterating further only creeps towards it
+        if updated.iter().any(|&h| h > 1.0)
+        {
+            return Err(String::from("Heywood case, a communality exceeds one!"));
+        }
+        let change = (&updated - &communalities).iter().fold(0.0f64, |a, b| a.max(b.abs()));
+        communalities = updated;
+        if change < EXTRACTION_TOLERANCE
+        {
+            return Ok(loadings);
+        }
+    }
+    Err(String::from("Principal axis factoring did not converge!"))
+}
+
+// EM algorithm (Rubin and Thayer, 1982) for the uniquenesses Psi, the loadings
+// Psi^(1/2) E (Theta - I)^(1/2) from the leading eigenpairs of Psi^(-1/2) R Psi^(-1/2) then are those of factanal
+fn get_maximum_likelihood_loadings(correlation: &Array2<f64>, num_factors: usize) -> Result<Array2<f64>, String>
+{
+    let mut uniquenesses = get_inverse(correlation)?.diag().mapv(|d| (1.0 / d).max(MIN_UNIQUENESS));
+    let mut loadings = get_scaled_loadings(correlation, &uniquenesses, num_factors)?;
+    let mut converged = false;
+    for _ in 0..MAX_EXTRACTION_ITERATIONS
+    {
+        let mut model = get_matrix_product(&loadings, &loadings.t().to_owned())?;
+        model.diag_mut().zip_mut_with(&uniquenesses, |m, u| *m += u);
+        // Regression of the factors on the variables and their expected second moments
+        let beta = get_matrix_product(&loadings.t().to_owned(), &get_inverse(&model)?)?;
+        let beta_correlation = get_matrix_product(&beta, correlation)?;
+        let second_moments = Array2::<f64>::eye(num_factors) - get_matrix_product(&beta, &loadings)? + get_matrix_product(&beta_correlation, &beta.t().to_owned())?;
+        loadings = get_matrix_product(&beta_correlation.t().to_owned(), &get_inverse(&second_moments)?)?;
+        let updated = (correlation.diag().to_owned() - (get_matrix_product(&loadings, &beta_correlation)?).diag()).mapv(|u| u.max(MIN_UNIQUENESS));
+        let change = (&updated - &uniquenesses).iter().fold(0.0f64, |a, b| a.max(b.abs()));
+        uniquenesses = updated;
+        if change < EXTRACTION_TOLERANCE
+        {
+            converged = true;
+            break;
+        }
+    }
+    if !converged
+    {
+        return Err(String::from("Maximum likelihood factor analysis did not converge!"));
+    }
+    get_scaled_loadings(correlation, &uniquenesses, num_factors)
+}
+
+fn get_scaled_loadings(correlation: &Array2<f64>, uniquenesses: &Array1<f64>, num_factors: usize) -> Result<Array2<f64>, String>
+{
+    let scales = uniquenesses.mapv(f64::sqrt);
+    let scaled = correlation / &scales / scales.view().insert_axis(Axis(1));
+    let decomposition = get_symmetric_eigendecomposition(&scaled)?;
+    let mut loadings = decomposition.eigenvectors.slice(ndarray::s![.., ..num_factors]).to_owned() * scales.view().insert_axis(Axis(1));
+    for (k, mut column) in loadings.columns_mut().into_iter().enumerate()
+    {
+        column *= (decomposition.eigenvalues[k] - 1.0).max(0.0).sqrt();
+    }
+    Ok(loadings)
+}
+
+// Criterion value and gradient with respect to the loadings, as in GPArotation
+fn get_rotation_criterion(loadings: &Array2<f64>, criterion: RotationCriterion) -> (f64, Array2<f64>)
+{
+    let squared = loadings.mapv(|l| l * l);
+    match criterion
+    {
+        RotationCriterion::Varimax =>
+        {
+            let centered = &squared - &squared.mean_axis(Axis(0)).unwrap();
+            (-centered.mapv(|x| x * x).sum() / 4.0, -(loadings * &centered))
+        },
+        RotationCriterion::Quartimax => (-squared.mapv(|x| x * x).sum() / 4.0, -(loadings * &squared)),
+        RotationCriterion::Oblimin(gamma) =>
+        {
+            // Squared loadings on the other factors, centred over the variables with weight gamma
+            let others = &squared.sum_axis(Axis(1)).insert_axis(Axis(1)) - &squared;
+            let weighted = &others - &(others.mean_axis(Axis(0)).unwrap() * gamma);
+            ((&squared * &weighted).sum() / 4.0, loadings * &weighted)
+        },
+    }
+}
+
+// Rows are divided by the square roots of their communalities during rotation
+fn get_kaiser_normalization(loadings: &Array2<f64>) -> Array1<f64>
+{
+    loadings.mapv(|l| l * l).sum_axis(Axis(1)).mapv(|h| if h > 0.0 { h.sqrt() } else { 1.0 })
+}
+
+// Gradient projection (Jennrich, 2001) over orthogonal matrices T with rotated loadings A T,
+// returns the rotated loadings and T
+fn get_orthogonal_rotation(loadings: &Array2<f64>, criterion: RotationCriterion) -> Result<(Array2<f64>, Array2<f64>), String>
+{
+    let weights = get_kaiser_normalization(loadings);
+    let normalized = loadings / &weights.view().insert_axis(Axis(1));
+    let transposed = normalized.t().to_owned();
+    let mut rotation = Array2::<f64>::eye(loadings.ncols());
+    let (mut value, mut gradient) = get_rotation_criterion(&normalized, criterion);
+    gradient = get_matrix_product(&transposed, &gradient)?;
+    let mut step = 1.0;
+    for _ in 0..MAX_ROTATION_ITERATIONS
+    {
+        let m = get_matrix_product(&rotation.t().to_owned(), &gradient)?;
+        let symmetric = (&m + &m.t()) / 2.0;
+        let projected = &gradient - &get_matrix_product(&rotation, &symmetric)?;
+        let norm = projected.mapv(|x| x * x).sum().sqrt();
+        if norm < ROTATION_TOLERANCE
+        {
+            let rotated = get_matrix_product(&normalized, &rotation)? * weights.view().insert_axis(Axis(1));
+            return Ok((rotated, rotation));
+        }
+        step *= 2.0;
+        let mut candidate = rotation.clone();
+        let mut candidate_value = value;
+        let mut candidate_gradient = gradient.clone();
+        for _ in 0..=10
+        {
+            // Nearest orthogonal matrix to the step
+            let svd = get_singular_value_decomposition(&(&rotation - &(&projected * step)))?;
+            candidate = get_matrix_product(&svd.u, &svd.v.t().to_owned())?;
+            let (new_value, new_gradient) = get_rotation_criterion(&get_matrix_product(&normalized, &candidate)?, criterion);
+            candidate_value = new_value;
+            candidate_gradient = new_gradient;
+            if value - candidate_value > 0.5 * norm * norm * step
+            {
+                break;
+            }
+            step /= 2.0;
+        }
+        rotation = candidate;
+        value = candidate_value;
+        gradient = get_matrix_product(&transposed, &candidate_gradient)?;
+    }
+    Err(String::from("Rotation did not converge!"))
+}
+
+// Gradient projection over matrices T with unit columns, rotated loadings A (T^T)^(-1) and factor correlations T^T T
+fn get_oblique_rotation(loadings: &Array2<f64>, criterion: RotationCriterion) -> Result<(Array2<f64>, Array2<f64>), String>
+{
+    let weights = get_kaiser_normalization(loadings);
+    let normalized = loadings / &weights.view().insert_axis(Axis(1));
+    // Value, rotated loadings and gradient with respect to T
+    let evaluate = |rotation: &Array2<f64>| -> Result<(f64, Array2<f64>, Array2<f64>), String>
+    {
+        let inverse = get_inverse(rotation)?;
+        let rotated = get_matrix_product(&normalized, &inverse.t().to_owned())?;
+        let (value, gradient) = get_rotation_criterion(&rotated, criterion);
+        let gradient = -get_matrix_product(&get_matrix_product(&rotated.t().to_owned(), &gradient)?, &inverse)?.t().to_owned();
+        Ok((value, rotated, gradient))
+    };
+    let mut rotation = Array2::<f64>::eye(loadings.ncols());
+    let (mut value, mut rotated, mut gradient) = evaluate(&rotation)?;
+    let mut step = 1.0;
+    for _ in 0..MAX_ROTATION_ITERATIONS
+    {
+        let column_products = (&rotation * &gradient).sum_axis(Axis(0));
+        let projected = &gradient - &(&rotation * &column_products);
+        let norm = projected.mapv(|x| x * x).sum().sqrt();
+        if norm < ROTATION_TOLERANCE
+        {
+            let factor_correlations = get_matrix_product(&rotation.t().to_owned(), &rotation)?;
+            return Ok((rotated * weights.view().insert_axis(Axis(1)), factor_correlations));
+        }
+        step *= 2.0;
+        let mut candidate = (rotation.clone(), value, rotated.clone(), gradient.clone());
+        for _ in 0..=10
+        {
+            let stepped = &rotation - &(&projected * step);
+            let column_norms = stepped.mapv(|x| x * x).sum_axis(Axis(0)).mapv(f64::sqrt);
+            let normalized_step = &stepped / &column_norms;
+            let (new_value, new_rotated, new_gradient) = evaluate(&normalized_step)?;
+            candidate = (normalized_step, new_value, new_rotated, new_gradient);
+            if value - candidate.1 > 0.5 * norm * norm * step
+            {
+                break;
+            }
+            step /= 2.0;
+        }
+        rotation = candidate.0;
+        value = candidate.1;
+        rotated = candidate.2;
+        gradient = candidate.3;
+    }
+    Err(String::from("Rotation did not converge!"))
+}
+
+// Hendrickson and White (1964) as R's promax: regress the target V |V|^(power - 1) of the varimax
+// loadings V on V, rescale so that the factors have unit variance, factor correlations (U^T U)^(-1)
+fn get_promax_rotation(loadings: &Array2<f64>, power: f64) -> Result<(Array2<f64>, Array2<f64>), String>
+{
+    if power <= 1.0
+    {
+        return Err(String::from("Promax power must be greater than one!"));
+    }
+    let (varimax, varimax_rotation) = get_orthogonal_rotation(loadings, RotationCriterion::Varimax)?;
+    let target = varimax.mapv(|v| v * v.abs().powf(power - 1.0));
+    let num_factors = loadings.ncols();
+    let mut transformation = Array2::<f64>::zeros((num_factors, num_factors));
+    for k in 0..num_factors
+    {
+        transformation.column_mut(k).assign(&get_least_squares(&varimax, &target.column(k).to_owned())?);
+    }
+    let scales = get_inverse(&get_matrix_product(&transformation.t().to_owned(), &transformation)?)?.diag().mapv(f64::sqrt);
+    let transformation = transformation * &scales;
+    let rotated = get_matrix_product(&varimax, &transformation)?;
+    let total = get_matrix_product(&varimax_rotation, &transformation)?;
+    let factor_correlations = get_inverse(&get_matrix_product(&total.t().to_owned(), &total)?)?;
+    Ok((rotated, factor_correlations))
+}
+
+fn get_ordered_factors(loadings: &Array2<f64>, factor_correlations: &Array2<f64>) -> (Array2<f64>, Array2<f64>)
+{
+    let sums_of_squares = loadings.mapv(|l| l * l).sum_axis(Axis(0));
+    let mut order: Vec<usize> = (0..loadings.ncols()).collect();
+    order.sort_by(|&i, &j| sums_of_squares[j].partial_cmp(&sums_of_squares[i]).unwrap());
+    let signs: Vec<f64> = order.iter().map(|&k| if loadings.column(k).sum() < 0.0 { -1.0 } else { 1.0 }).collect();
+    let ordered_loadings = Array2::from_shape_fn(loadings.dim(), |(i, k)| signs[k] * loadings[[i, order[k]]]);
+    let ordered_correlations = Array2::from_shape_fn(factor_correlations.dim(), |(k, l)| signs[k] * signs[l] * factor_correlations[[order[k], order[l]]]);
+    (ordered_loadings, ordered_correlations)
+}
@@ -0,0 +1,466 @@
+use super::distributions::{get_normal_cdf, get_normal_pdf, get_normal_ppf};
+use super::linear_algebra::{add_intercept_column, get_inverse, get_matrix_product, get_matrix_vector_product, get_weighted_least_squares};
+use ndarray::{Array1, Array2};
+
+const PROBABILITY_EPSILON: f64 = 1e-10;
+
+// link and derivative fail for means outside the domain of the link, e.g. counts above one with a probit link
+pub trait Link
+{
+    fn link(&self, mu: f64) -> Result<f64, String>;
+    fn inverse(&self, eta: f64) -> f64;
+    // d eta / d mu
+    fn derivative(&self, mu: f64) -> Result<f64, String>;
+}
+
+pub trait Family
+{
+    fn variance(&self, mu: f64) -> f64;
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64;
+    fn initialize_mu(&self, y: f64) -> f64;
+
+    fn has_fixed_dispersion(&self) -> bool
+    {
+        false
+    }
+
+    fn clamp_mu(&self, mu: f64) -> f64
+    {
+        mu
+    }
+
+    fn check_observations(&self, _observations: &Array1<f64>) -> Result<(), String>
+    {
+        Ok(())
+    }
+}
+
+#[allow(unused)]
+pub struct IdentityLink;
+
+#[allow(unused)]
+pub struct LogLink;
+
+#[allow(unused)]
+pub struct LogitLink;
+
+#[allow(unused)]
+pub struct ProbitLink;
+
+#[allow(unused)]
+pub struct CLogLogLink;
+
+#[allow(unused)]
+pub struct InverseLink;
+
+fn check_positive_mean(mu: f64) -> Result<f64, String>
+{
+    if mu > 0.0 { Ok(mu) } else { Err(String::from("Mean must be positive for this link!")) }
+}
+
+fn check_probability_mean(mu: f64) -> Result<f64, String>
+{
+    if mu > 0.0 && mu < 1.0 { Ok(mu) } else { Err(String::from("Mean must be between 0 and 1 for this link!")) }
+}
+
+impl Link for IdentityLink
+{
+    fn link(&self, mu: f64) -> Result<f64, String> { Ok(mu) }
+    fn inverse(&self, eta: f64) -> f64 { eta }
+    fn derivative(&self, _mu: f64) -> Result<f64, String> { Ok(1.0) }
+}
+
+impl Link for LogLink
+{
+    fn link(&self, mu: f64) -> Result<f64, String> { Ok(check_positive_mean(mu)?.ln()) }
+    fn inverse(&self, eta: f64) -> f64 { eta.exp() }
+    fn derivative(&self, mu: f64) -> Result<f64, String> { Ok(1.0 / check_positive_mean(mu)?) }
+}
+
+impl Link for LogitLink
+{
+    fn link(&self, mu: f64) -> Result<f64, String> { check_probability_mean(mu).map(|mu| (mu / (1.0 - mu)).ln()) }
+    fn inverse(&self, eta: f64) -> f64 { 1.0 / (1.0 + (-eta).exp()) }
+    fn derivative(&self, mu: f64) -> Result<f64, String> { check_probability_mean(mu).map(|mu| 1.0 / (mu * (1.0 - mu))) }
+}
+
+impl Link for ProbitLink
+{
+    fn link(&self, mu: f64) -> Result<f64, String> { get_normal_ppf(check_probability_mean(mu)?) }
+    fn inverse(&self, eta: f64) -> f64 { get_normal_cdf(eta) }
+    fn derivative(&self, mu: f64) -> Result<f64, String> { Ok(1.0 / get_normal_pdf(self.link(mu)?)) }
+}
+
+impl Link for CLogLogLink
+{
+    fn link(&self, mu: f64) -> Result<f64, String> { check_probability_mean(mu).map(|mu| (-(1.0 - mu).ln()).ln()) }
+    fn inverse(&self, eta: f64) -> f64 { 1.0 - (-eta.exp()).exp() }
+    fn derivative(&self, mu: f64) -> Result<f64, String> { check_probability_mean(mu).map(|mu| 1.0 / ((1.0 - mu) * -(1.0 - mu).ln())) }
+}
+
+impl Link for InverseLink
+{
+    fn link(&self, mu: f64) -> Result<f64, String> { Ok(1.0 / check_positive_mean(mu)?) }
+    fn inverse(&self, eta: f64) -> f64 { 1.0 / eta }
+    fn derivative(&self, mu: f64) -> Result<f64, String> { Ok(-1.0 / check_positive_mean(mu)?.powi(2)) }
+}
+
+#[allow(unused)]
+pub struct Gaussian;
+
+// Observations are proportions of successes, prior weights are the number of trials
+#[allow(unused)]
+pub struct Binomial;
+
+#[allow(unused)]
+pub struct Poisson;
+
+#[allow(unused)]
+pub struct Gamma;
+
+#[allow(unused)]
+pub struct InverseGaussian;
+
+// Variance is mu + alpha * mu^2
+#[allow(unused)]
+pub struct NegativeBinomial
+{
+    pub alpha: f64
+}
+
+// y * ln(y / mu) with the convention 0 * ln(0) = 0
+fn get_y_log_y_over_mu(y: f64, mu: f64) -> f64
+{
+    if y == 0.0 { 0.0 } else { y * (y / mu).ln() }
+}
+
+impl Family for Gaussian
+{
+    fn variance(&self, _mu: f64) -> f64 { 1.0 }
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64 { (y - mu).powi(2) }
+    fn initialize_mu(&self, y: f64) -> f64 { y }
+}
+
+impl Family for Binomial
+{
+    fn variance(&self, mu: f64) -> f64 { mu * (1.0 - mu) }
+
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64
+    {
+        2.0 * (get_y_log_y_over_mu(y, mu) + get_y_log_y_over_mu(1.0 - y, 1.0 - mu))
+    }
+
+    fn initialize_mu(&self, y: f64) -> f64 { (y + 0.5) / 2.0 }
+    fn has_fixed_dispersion(&self) -> bool { true }
+    fn clamp_mu(&self, mu: f64) -> f64 { mu.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON) }
+
+    fn check_observations(&self, observations: &Array1<f64>) -> Result<(), String>
+    {
+        if observations.iter().any(|&y| !(0.0..=1.0).contains(&y))
+        {
+            return Err(String::from("Binomial observations must be between 0 and 1!"));
+        }
+        Ok(())
+    }
+}
+
+impl Family for Poisson
+{
+    fn variance(&self, mu: f64) -> f64 { mu }
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64 { 2.0 * (get_y_log_y_over_mu(y, mu) - (y - mu)) }
+    fn initialize_mu(&self, y: f64) -> f64 { y + 0.1 }
+    fn has_fixed_dispersion(&self) -> bool { true }
+    fn clamp_mu(&self, mu: f64) -> f64 { mu.max(PROBABILITY_EPSILON) }
+
+    fn check_observations(&self, observations: &Array1<f64>) -> Result<(), String>
+    {
+        if observations.iter().any(|&y| y < 0.0)
+        {
+            return Err(String::from("Poisson observations must not be negative!"));
+        }
+        Ok(())
+    }
+}
+
+impl Family for Gamma
+{
+    fn variance(&self, mu: f64) -> f64 { mu * mu }
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64 { 2.0 * (-(y / mu).ln() + (y - mu) / mu) }
+    fn initialize_mu(&self, y: f64) -> f64 { y }
+    fn clamp_mu(&self, mu: f64) -> f64 { mu.max(PROBABILITY_EPSILON) }
+
+    fn check_observations(&self, observations: &Array1<f64>) -> Result<(), String>
+    {
+        if observations.iter().any(|&y| y <= 0.0)
+        {
+            return Err(String::from("Gamma observations must be positive!"));
+        }
+        Ok(())
+    }
+}
+
+impl Family for InverseGaussian
+{
+    fn variance(&self, mu: f64) -> f64 { mu.powi(3) }
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64 { (y - mu).powi(2) / (y * mu * mu) }
+    fn initialize_mu(&self, y: f64) -> f64 { y }
+    fn clamp_mu(&self, mu: f64) -> f64 { mu.max(PROBABILITY_EPSILON) }
+
+    fn check_observations(&self, observations: &Array1<f64>) -> Result<(), String>
+    {
+        if observations.iter().any(|&y| y <= 0.0)
+        {
+            return Err(String::from("Inverse Gaussian observations must be positive!"));
+        }
+        Ok(())
+    }
+}
+
+impl Family for NegativeBinomial
+{
+    fn variance(&self, mu: f64) -> f64 { mu + self.alpha * mu * mu }
+
+    fn unit_deviance(&self, y: f64, mu: f64) -> f64
+    {
+        2.0 * (get_y_log_y_over_mu(y, mu)
+               - (y + 1.0 / self.alpha) * ((1.0 + self.alpha * y) / (1.0 + self.alpha * mu)).ln())
+    }
+
+    fn initialize_mu(&self, y: f64) -> f64 { y + 0.1 }
+    fn has_fixed_dispersion(&self) -> bool { true }
+    fn clamp_mu(&self, mu: f64) -> f64 { mu.max(PROBABILITY_EPSILON) }
+
+    fn check_observations(&self, observations: &Array1<f64>) -> Result<(), String>
+    {
+        if self.alpha <= 0.0
+        {
+            return Err(String::from("Negative binomial alpha must be positive!"));
+        }
+        if observations.iter().any(|&y| y < 0.0)
+        {
+            return Err(String::from("Negative binomial observations must not be negative!"));
+        }
+        Ok(())
+    }
+}
+
+#[allow(unused)]
+#[derive(Debug)]
+pub struct GlmResult
+{
+    pub coefficients: Array1<f64>,
+    pub standard_errors: Array1<f64>,
+    pub fitted_values: Array1<f64>,
+    pub linear_predictor: Array1<f64>,
+    pub deviance_residuals: Array1<f64>,
+    pub pearson_residuals: Array1<f64>,
+    pub deviance: f64,
+    pub null_deviance: f64,
+    pub dispersion: f64,
+    pub degrees_of_freedom: usize,
+    pub iterations: usize,
+    pub converged: bool,
+}
+
+#[allow(unused)]
+pub struct Glm
+{
+    family: Box<dyn Family>,
+    link: Box<dyn Link>,
+    add_intercept: bool,
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+#[allow(unused)]
+impl Glm
+{
+    pub fn new(family: Box<dyn Family>, link: Box<dyn Link>, add_intercept: bool) -> Self
+    {
+        Glm
+        {
+            family,
+            link,
+            add_intercept,
+            max_iterations: 100,
+            tolerance: 1e-8,
+        }
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self
+    {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self
+    {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn fit(&self,
+               features: &Array2<f64>,
+               observations: &Array1<f64>,
+               offset: Option<&Array1<f64>>,
+               prior_weights: Option<&Array1<f64>>) -> Result<GlmResult, String>
+    {
+        let n = observations.len();
+        if features.nrows() != n
+        {
+            return Err(String::from("Features and observations must have the same number of rows!"));
+        }
+        let offset = offset.cloned().unwrap_or_else(|| Array1::<f64>::zeros(n));
+        let prior_weights = prior_weights.cloned().unwrap_or_else(|| Array1::<f64>::ones(n));
+        if offset.len() != n || prior_weights.len() != n
+        {
+            return Err(String::from("Offset and prior weights must have as many elements as observations!"));
+        }
+        if prior_weights.iter().any(|&w| w < 0.0)
+        {
+            return Err(String::from("Prior weights must not be negative!"));
+        }
+        self.family.check_observations(observations)?;
+
+        let design_matrix = if self.add_intercept { add_intercept_column(features) } else { features.to_owned() };
+        let num_coefficients = design_matrix.ncols();
+        if n <= num_coefficients
+        {
+            return Err(String::from("Number of observations must be greater than the number of coefficients!"));
+        }
+
+        let (coefficients, mu, iterations, converged) = self.run_irls(&design_matrix, observations, &offset, &prior_weights)?;
+        let deviance = self.get_deviance(observations, &mu, &prior_weights);
+
+        let null_deviance = if self.add_intercept
+        {
+            let intercept_only = Array2::<f64>::ones((n, 1));
+            let (_, null_mu, _, _) = self.run_irls(&intercept_only, observations, &offset, &prior_weights)?;
+            self.get_deviance(observations, &null_mu, &prior_weights)
+        }
+        else
+        {
+            let null_mu = offset.mapv(|eta| self.family.clamp_mu(self.link.inverse(eta)));
+            self.get_deviance(observations, &null_mu, &prior_weights)
+        };
+
+        let pearson_residuals: Array1<f64> = (0..n)
+            .map(|i| (observations[i] - mu[i]) * (prior_weights[i] / self.family.variance(mu[i])).sqrt())
+            .collect();
+        let deviance_residuals: Array1<f64> = (0..n)
+            .map(|i|
+            {
+                let unit_deviance = (prior_weights[i] * self.family.unit_deviance(observations[i], mu[i])).max(0.0);
+                (observations[i] - mu[i]).signum() * unit_deviance.sqrt()
+            })
+            .collect();
+
+        let degrees_of_freedom = n - num_coefficients;
+        let dispersion = if self.family.has_fixed_dispersion()
+        {
+            1.0
+        }
+        else
+        {
+            pearson_residuals.mapv(|r| r * r).sum() / degrees_of_freedom as f64
+        };
+
+        let working_weights = self.get_working_weights(&mu, &prior_weights)?;
+        let weighted_design = &design_matrix * &working_weights.view().insert_axis(ndarray::Axis(1));
+        let covariance = get_inverse(&get_matrix_product(&design_matrix.t().to_owned(), &weighted_design)?)? * dispersion;
+        let standard_errors = covariance.diag().mapv(f64::sqrt);
+        let linear_predictor = get_matrix_vector_product(&design_matrix, &coefficients)? + &offset;
+
+        Ok
+        (
+            GlmResult
+            {
+                coefficients,
+                standard_errors,
+                fitted_values: mu,
+                linear_predictor,
+                deviance_residuals,
+                pearson_residuals,
+                deviance,
+                null_deviance,
+                dispersion,
+                degrees_of_freedom,
+                iterations,
+                converged,
+            }
+        )
+    }
+
+    pub fn predict(&self, features: &Array2<f64>, coefficients: &Array1<f64>, offset: Option<&Array1<f64>>)
+    -> Result<Array1<f64>, String>
+    {
+        let design_matrix = if self.add_intercept { add_intercept_column(features) } else { features.to_owned() };
+        if design_matrix.ncols() != coefficients.len()
+        {
+            return Err(String::from("Number of features does not match the number of coefficients!"));
+        }
+        let mut eta = get_matrix_vector_product(&design_matrix, coefficients)?;
+        if let Some(offset) = offset
+        {
+            if offset.len() != eta.len()
+            {
+                return Err(String::from("Offset must have as many elements as rows of features!"));
+            }
+            eta += offset;
+        }
+        Ok(eta.mapv(|eta| self.link.inverse(eta)))
+    }
+
+    fn run_irls(&self,
+                design_matrix: &Array2<f64>,
+                observations: &Array1<f64>,
+                offset: &Array1<f64>,
+                prior_weights: &Array1<f64>) -> Result<(Array1<f64>, Array1<f64>, usize, bool), String>
+    {
+        let mut mu = observations.mapv(|y| self.family.clamp_mu(self.family.initialize_mu(y)));
+        let mut eta = mu.iter().map(|&mu| self.link.link(mu)).collect::<Result<Array1<f64>, String>>()?;
+        let mut coefficients = Array1::<f64>::zeros(design_matrix.ncols());
+        let mut deviance = self.get_deviance(observations, &mu, prior_weights);
+
+        for iteration in 1..=self.max_iterations
+        {
+            let working_response: Array1<f64> = (0..observations.len())
+                .map(|i| Ok(eta[i] - offset[i] + (observations[i] - mu[i]) * self.link.derivative(mu[i])?))
+                .collect::<Result<_, String>>()?;
+            let working_weights = self.get_working_weights(&mu, prior_weights)?;
+
+            coefficients = get_weighted_least_squares(design_matrix, &working_response, &working_weights)?;
+            eta = get_matrix_vector_product(design_matrix, &coefficients)? + offset;
+            mu = eta.mapv(|eta| self.family.clamp_mu(self.link.inverse(eta)));
+
+            let new_deviance = self.get_deviance(observations, &mu, prior_weights);
+            if !new_deviance.is_finite()
+            {
+                return Err(String::from("Deviance is not finite, try a different link function!"));
+            }
+            if (new_deviance - deviance).abs() / (new_deviance.abs() + 0.1) < self.tolerance
+            {
+                return Ok((coefficients, mu, iteration, true));
+            }
+            deviance = new_deviance;
+        }
+        Ok((coefficients, mu, self.max_iterations, false))
+    }
+
+    fn get_working_weights(&self, mu: &Array1<f64>, prior_weights: &Array1<f64>) -> Result<Array1<f64>, String>
+    {
+        mu.iter()
+            .zip(prior_weights)
+            .map(|(&mu, &w)| Ok(w / (self.family.variance(mu) * self.link.derivative(mu)?.powi(2))))
+            .collect()
+    }
+
+    fn get_deviance(&self, observations: &Array1<f64>, mu: &Array1<f64>, prior_weights: &Array1<f64>) -> f64
+    {
+        observations.iter()
+            .zip(mu)
+            .zip(prior_weights)
+            .map(|((&y, &mu), &w)| w * self.family.unit_deviance(y, mu))
+            .sum()
+    }
+}
@@ -0,0 +1,197 @@
+use super::clustering::{get_distance_matrix, DistanceMetric};
+use ndarray::{Array1, Array2};
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Linkage
+{
+    // Distance between the closest points of two clusters
+    Single,
+    // Distance between the furthest points of two clusters
+    Complete,
+    // Mean distance between the points of two clusters (UPGMA)
+    Average,
+    // Merges the clusters that least increase the within-cluster sum of squares, needs Euclidean distances
+    Ward,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct AgglomerativeClustering
+{
+    linkage: Linkage,
+    metric: DistanceMetric,
+}
+
+// Linkage matrix in the layout of SciPy: row i merges clusters a < b at the given height into a cluster
+// of the given size, labelled n + i, where labels below n are the points
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct Dendrogram
+{
+    pub linkage_matrix: Array2<f64>,
+}
+
+#[allow(unused)]
+impl AgglomerativeClustering
+{
+    pub fn new(linkage: Linkage) -> Self
+    {
+        AgglomerativeClustering { linkage, metric: DistanceMetric::Euclidean }
+    }
+
+    pub fn with_metric(mut self, metric: DistanceMetric) -> Self
+    {
+        self.metric = metric;
+        self
+    }
+
+    // Nearest-neighbour chain algorithm with Lance-Williams updates of the distances, O(n^2) for all four linkages
+    pub fn fit(&self, data: &Array2<f64>) -> Result<Dendrogram, String>
+    {
+        let num_points = data.nrows();
+        if num_points < 2
+        {
+            return Err(String::from("Need at least two points!"));
+        }
+        if self.linkage == Linkage::Ward && self.metric != DistanceMetric::Euclidean
+        {
+            return Err(String::from("Ward linkage needs the Euclidean metric!"));
+        }
+        let mut distances = get_distance_matrix(data, self.metric)?;
+        let mut sizes = vec![1usize; num_points];
+        let mut active = vec![true; num_points];
+        let mut chain: Vec<usize> = Vec::with_capacity(num_points);
+        let mut merges: Vec<(usize, usize, f64)> = Vec::with_capacity(num_points - 1);
+        for _ in 1..num_points
+        {
+            if chain.is_empty()
+            {
+                chain.push(active.iter().position(|&a| a).unwrap());
+            }
+            // Follow nearest neighbours until two clusters are each other's nearest neighbour
+            let (x, y, height) = loop
+            {
+                let x = *chain.last().unwrap();
+                let (mut y, mut nearest) = if chain.len() > 1 { (chain[chain.len() - 2], distances[[x, chain[chain.len() - 2]]]) } else { (x, f64::INFINITY) };
+                for i in (0..num_points).filter(|&i| active[i] && i != x)
+                {
+                    if distances[[x, i]] < nearest
+                    {
+                        nearest = distances[[x, i]];
+                        y = i;
+                    }
+                }
+                if chain.len() > 1 && y == chain[chain.len() - 2]
+                {
+                    chain.truncate(chain.len() - 2);
+                    break (x.min(y), x.max(y), nearest);
+                }
+                chain.push(y);
+            };
+
+            // The merged cluster takes the place of y
+            let (size_x, size_y) = (sizes[x] as f64, sizes[y] as f64);
+            for i in (0..num_points).filter(|&i| active[i] && i != x && i != y)
+            {
+                let (d_x, d_y) = (distances[[i, x]], distances[[i, y]]);
+                let size_i = sizes[i] as f64;
+                let updated = match self.linkage
+                {
+                    Linkage::Single => d_x.min(d_y),
+                    Linkage::Complete => d_x.max(d_y),
+                    Linkage::Average => (size_x * d_x + size_y * d_y) / (size_x + size_y),
+                    Linkage::Ward => (((size_i + size_x) * d_x * d_x + (size_i + size_y) * d_y * d_y - size_i * height * height) / (size_i + size_x + size_y)).sqrt(),
+                };
+                distances[[i, y]] = updated;
+                distances[[y, i]] = updated;
+            }
+            active[x] = false;
+            sizes[y] += sizes[x];
+            merges.push((x, y, height));
+        }
+        Ok(Dendrogram { linkage_matrix: get_linkage_matrix(num_points, merges) })
+    }
+}
+
+#[allow(unused)]
+impl Dendrogram
+{
+    // Clusters joined at heights up to the given one, labelled from zero in order of their first point
+    pub fn cut_at_height(&self, height: f64) -> Array1<usize>
+    {
+        let num_merges = self.linkage_matrix.column(2).iter().take_while(|&&h| h <= height).count();
+        self.get_labels(num_merges)
+    }
+
+    pub fn cut_into_clusters(&self, num_clusters: usize) -> Result<Array1<usize>, String>
+    {
+        let num_points = self.linkage_matrix.nrows() + 1;
+        if num_clusters == 0 || num_clusters > num_points
+        {
+            return Err(String::from("Number of clusters must be between 1 and the number of points!"));
+        }
+        Ok(self.get_labels(num_points - num_clusters))
+    }
+
+    fn get_labels(&self, num_merges: usize) -> Array1<usize>
+    {
+        let num_points = self.linkage_matrix.nrows() + 1;
+        // Every cluster label points to the cluster it is merged into
+        let mut parents: Vec<usize> = (0..num_points + num_merges).collect();
+        for (i, merge) in self.linkage_matrix.rows().into_iter().take(num_merges).enumerate()
+        {
+            parents[merge[0] as usize] = num_points + i;
+            parents[merge[1] as usize] = num_points + i;
+        }
+        let mut roots: Vec<usize> = Vec::new();
+        (0..num_points).map(|point|
+        {
+            let mut root = point;
+            while parents[root] != root
+            {
+                root = parents[root];
+            }
+            match roots.iter().position(|&r| r == root)
+            {
+                Some(label) => label,
+                None =>
+                {
+                    roots.push(root);
+                    roots.len() - 1
+                },
+            }
+        }).collect()
+    }
+}
+
+// Merges given by any point of each cluster, sorted stably by height and relabelled as in SciPy
+pub(crate) fn get_linkage_matrix(num_points: usize, mut merges: Vec<(usize, usize, f64)>) -> Array2<f64>
+{
+    merges.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+    // Union-find over the points, tracking the current label and size of each root
+    let mut parents: Vec<usize> = (0..num_points).collect();
+    let mut labels: Vec<usize> = (0..num_points).collect();
+    let mut sizes = vec![1usize; num_points];
+    let find = |parents: &mut Vec<usize>, mut point: usize|
+    {
+        while parents[point] != point
+        {
+            parents[point] = parents[parents[point]];
+            point = parents[point];
+        }
+        point
+    };
+    let mut linkage_matrix = Array2::<f64>::zeros((merges.len(), 4));
+    for (i, &(a, b, height)) in merges.iter().enumerate()
+    {
+        let (root_a, root_b) = (find(&mut parents, a), find(&mut parents, b));
+        let (label_a, label_b) = (labels[root_a], labels[root_b]);
+        let size = sizes[root_a] + sizes[root_b];
+        parents[root_a] = root_b;
+        labels[root_b] = num_points + i;
+        sizes[root_b] = size;
+        linkage_matrix.row_mut(i).assign(&Array1::from(vec![label_a.min(label_b) as f64, label_a.max(label_b) as f64, height, size as f64]));
+    }
+    linkage_matrix
+}
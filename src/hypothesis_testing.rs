@@ -1,4 +1,6 @@
-use super::utilities::{get_mean, get_variance};
+use super::utilities::{get_mean, get_median, get_variance, check_vector_for_nans};
+use super::distributions::{get_chi_squared_sf, get_f_cdf, get_f_sf, get_normal_cdf, get_normal_sf, get_t_cdf, get_t_sf};
+use super::analysis_of_variance::get_one_way_anova;
 use ndarray::{Array1};
 use prettytable::{Table, Attr, row, cell, color, Row, Cell};
 use std::collections::HashMap;
@@ -16,7 +18,9 @@ pub struct ZTest
 {
     z_test_kind: ZTestKind,
     alpha_level: f64,
-    z_critical: f64
+    z_critical: f64,
+    // Hypothesized mean and population standard deviation, run needs them
+    null_hypothesis: Option<(f64, f64)>,
 }
 
 #[allow(unused)]
@@ -105,10 +109,42 @@ impl ZTest
             {
                 z_test_kind: z_test_kind,
                 alpha_level: alpha_level,
-                z_critical: z_critical.unwrap().to_owned()
+                z_critical: z_critical.unwrap().to_owned(),
+                null_hypothesis: None,
             }
         )
     }
+
+    // Null hypothesis used by run, which fails without it
+    pub fn with_null_hypothesis(mut self, hypothesized_mean: f64, population_std: f64) -> Result<Self, String>
+    {
+        if population_std <= 0.0
+        {
+            return Err(String::from("Population standard deviation must be positive!"));
+        }
+        self.null_hypothesis = Some((hypothesized_mean, population_std));
+        Ok(self)
+    }
+}
+
+// The effect size is Cohen's d against the population standard deviation
+impl HypothesisTest for ZTest
+{
+    type Data = Array1<f64>;
+
+    fn run(&self, data: &Array1<f64>) -> Result<TestResult, String>
+    {
+        let (hypothesized_mean, population_std) = self.null_hypothesis.ok_or_else(|| String::from("Null hypothesis must be set!"))?;
+        let z_value = self.calculate_z_score(hypothesized_mean, population_std, data)?;
+        let p_value = match self.z_test_kind
+        {
+            ZTestKind::OneTailedUpper => get_normal_sf(z_value),
+            ZTestKind::OneTailedLower => get_normal_cdf(z_value),
+            ZTestKind::TwoTailed => 2.0 * get_normal_sf(z_value.abs()),
+        };
+        let cohens_d = (get_mean(data)? - hypothesized_mean) / population_std;
+        Ok(TestResult::new("Z-test", z_value, vec![], p_value).with_effect_size(cohens_d))
+    }
 }
 
 #[allow(unused)]
@@ -152,34 +188,294 @@ fn get_z_table(z_test_kind: &ZTestKind) -> HashMap::<[u8; 8], f64>
 }
 
 #[allow(unused)]
-enum TTestKind
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TTestKind
 {
-    OneTailedUpper,
-    OneTailedLower,
-    TwoTailed
+    OneSample { hypothesized_mean: f64 },
+    // Differences between the first and the second sample
+    Paired,
+    // Student's pooled variance with equal_variances, Welch's test otherwise
+    TwoSample { equal_variances: bool },
 }
 
 #[allow(unused)]
-struct Ttest
+pub struct Ttest
 {
     t_test_kind: TTestKind,
-    alpha_level: f64,
-    t_critical: f64
+    test_kind: TestKind,
 }
 
+#[allow(unused)]
+impl Ttest
+{
+    pub fn new(t_test_kind: TTestKind, test_kind: TestKind) -> Self
+    {
+        Ttest { t_test_kind, test_kind }
+    }
+}
+
+// Data holds one sample for the one-sample test and two otherwise,
+// the effect size is Cohen's d
+impl HypothesisTest for Ttest
+{
+    type Data = [Array1<f64>];
 
+    fn run(&self, data: &[Array1<f64>]) -> Result<TestResult, String>
+    {
+        let num_samples = if let TTestKind::OneSample { .. } = self.t_test_kind { 1 } else { 2 };
+        if data.len() != num_samples
+        {
+            return Err(format!("This t-test needs exactly {} sample(s)!", num_samples));
+        }
+        if data.iter().any(|sample| sample.len() < 2)
+        {
+            return Err(String::from("Each sample needs at least two observations!"));
+        }
+
+        let (test_name, t, degrees_of_freedom, cohens_d) = match self.t_test_kind
+        {
+            TTestKind::OneSample { hypothesized_mean } =>
+            {
+                let (t, degrees_of_freedom, cohens_d) = get_one_sample_t(&data[0], hypothesized_mean)?;
+                ("One-sample t-test", t, degrees_of_freedom, cohens_d)
+            },
+            TTestKind::Paired =>
+            {
+                if data[0].len() != data[1].len()
+                {
+                    return Err(String::from("Vector lengths do not match!"));
+                }
+                let (t, degrees_of_freedom, cohens_d) = get_one_sample_t(&(&data[0] - &data[1]), 0.0)?;
+                ("Paired t-test", t, degrees_of_freedom, cohens_d)
+            },
+            TTestKind::TwoSample { equal_variances } =>
+            {
+                let (n_1, n_2) = (data[0].len() as f64, data[1].len() as f64);
+                let (variance_1, variance_2) = (get_variance(&data[0])?, get_variance(&data[1])?);
+                let difference = get_mean(&data[0])? - get_mean(&data[1])?;
+                let pooled_variance = ((n_1 - 1.0) * variance_1 + (n_2 - 1.0) * variance_2) / (n_1 + n_2 - 2.0);
+                let cohens_d = difference / pooled_variance.sqrt();
+                if equal_variances
+                {
+                    let t = difference / (pooled_variance * (1.0 / n_1 + 1.0 / n_2)).sqrt();
+                    ("Student's t-test", t, n_1 + n_2 - 2.0, cohens_d)
+                }
+                else
+                {
+                    let (error_1, error_2) = (variance_1 / n_1, variance_2 / n_2);
+                    let t = difference / (error_1 + error_2).sqrt();
+                    // Welch-Satterthwaite degrees of freedom
+                    let degrees_of_freedom = (error_1 + error_2).powi(2) / (error_1.powi(2) / (n_1 - 1.0) + error_2.powi(2) / (n_2 - 1.0));
+                    ("Welch's t-test", t, degrees_of_freedom, cohens_d)
+                }
+            },
+        };
+        if !t.is_finite()
+        {
+            return Err(String::from("Samples have zero variance!"));
+        }
+
+        let p_value = match self.test_kind
+        {
+            TestKind::OneTailedUpper => get_t_sf(t, degrees_of_freedom)?,
+            TestKind::OneTailedLower => get_t_cdf(t, degrees_of_freedom)?,
+            TestKind::TwoTailed => 2.0 * get_t_sf(t.abs(), degrees_of_freedom)?,
+        };
+        Ok(TestResult::new(test_name, t, vec![degrees_of_freedom], p_value).with_effect_size(cohens_d))
+    }
+}
+
+fn get_one_sample_t(sample: &Array1<f64>, hypothesized_mean: f64) -> Result<(f64, f64, f64), String>
+{
+    let n = sample.len() as f64;
+    let standard_deviation = get_variance(sample)?.sqrt();
+    let difference = get_mean(sample)? - hypothesized_mean;
+    Ok((difference / (standard_deviation / n.sqrt()), n - 1.0, difference / standard_deviation))
+}
+
+// Larger variance over smaller variance
 #[allow(unused)]
 pub fn get_f_statistic(sample_1: &Array1<f64>, sample_2: &Array1<f64>) -> Result<f64, String>
 {
-    let sample_1_length = sample_1.shape()[0] - 1;
-    let sample_2_length = sample_2.shape()[0] - 1;
+    let variance_1 = get_variance(sample_1)?;
+    let variance_2 = get_variance(sample_2)?;
 
-    if sample_1_length >= sample_2_length
+    if variance_1 >= variance_2
     {
-        Ok(get_variance(sample_1)? / get_variance(sample_2)?)
+        Ok(variance_1 / variance_2)
     }
     else
     {
-        Ok(get_variance(sample_2)? / get_variance(sample_1)?)
+        Ok(variance_2 / variance_1)
+    }
+}
+
+// Common interface of tests that reduce a data set to a TestResult
+#[allow(unused)]
+pub trait HypothesisTest
+{
+    type Data: ?Sized;
+
+    fn run(&self, data: &Self::Data) -> Result<TestResult, String>;
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TestKind
+{
+    OneTailedUpper,
+    OneTailedLower,
+    TwoTailed,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestResult
+{
+    pub test_name: String,
+    pub statistic: f64,
+    pub degrees_of_freedom: Vec<f64>,
+    pub p_value: f64,
+    pub effect_size: Option<f64>,
+}
+
+#[allow(unused)]
+impl TestResult
+{
+    pub fn new(test_name: &str, statistic: f64, degrees_of_freedom: Vec<f64>, p_value: f64) -> Self
+    {
+        TestResult
+        {
+            test_name: String::from(test_name),
+            statistic,
+            degrees_of_freedom,
+            p_value: p_value.clamp(0.0, 1.0),
+            effect_size: None,
+        }
+    }
+
+    pub fn with_effect_size(mut self, effect_size: f64) -> Self
+    {
+        self.effect_size = Some(effect_size);
+        self
+    }
+
+    pub fn is_significant(&self, alpha_level: f64) -> bool
+    {
+        self.p_value < alpha_level
+    }
+
+    pub fn print(&self, alpha_level: f64)
+    {
+        let result = self.is_significant(alpha_level);
+        let style_attribute = if result {Attr::ForegroundColor(color::RED)} else {Attr::ForegroundColor(color::GREEN)};
+        let comment = if result {"Reject null hypothesis"} else {"Failed to reject null hypothesis"};
+        let degrees_of_freedom: Vec<String> = self.degrees_of_freedom.iter().map(|df| df.to_string()).collect();
+
+        let mut result_table = Table::new();
+        result_table.add_row(row!["Test", "Alpha Level", "Statistic", "Degrees of Freedom", "P-Value", "Effect Size", "Comment"]);
+        result_table.add_row(Row::new
+                                    (vec![
+                                        Cell::new(&self.test_name),
+                                        Cell::new(&alpha_level.to_string()),
+                                        Cell::new(&self.statistic.to_string()),
+                                        Cell::new(&degrees_of_freedom.join(", ")),
+                                        Cell::new(&self.p_value.to_string()).with_style(style_attribute),
+                                        Cell::new(&self.effect_size.map(|e| e.to_string()).unwrap_or_default()),
+                                        Cell::new(comment)
+                                        ]
+                                    )
+                            );
+        result_table.printstd();
     }
+}
+
+// F = s_1^2 / s_2^2, OneTailedUpper tests whether sample_1 has the larger variance
+#[allow(unused)]
+pub fn get_f_test(sample_1: &Array1<f64>, sample_2: &Array1<f64>, test_kind: TestKind) -> Result<TestResult, String>
+{
+    if sample_1.len() < 2 || sample_2.len() < 2
+    {
+        return Err(String::from("Each sample needs at least two observations!"));
+    }
+    let f = get_variance(sample_1)? / get_variance(sample_2)?;
+    let numerator_degrees_of_freedom = (sample_1.len() - 1) as f64;
+    let denominator_degrees_of_freedom = (sample_2.len() - 1) as f64;
+    let lower_tail = get_f_cdf(f, numerator_degrees_of_freedom, denominator_degrees_of_freedom)?;
+    let upper_tail = get_f_sf(f, numerator_degrees_of_freedom, denominator_degrees_of_freedom)?;
+
+    let p_value = match test_kind
+    {
+        TestKind::OneTailedUpper => upper_tail,
+        TestKind::OneTailedLower => lower_tail,
+        TestKind::TwoTailed => 2.0 * lower_tail.min(upper_tail),
+    };
+    Ok(TestResult::new("F-test", f, vec![numerator_degrees_of_freedom, denominator_degrees_of_freedom], p_value))
+}
+
+#[allow(unused)]
+pub fn get_levene_test(groups: &[Array1<f64>]) -> Result<TestResult, String>
+{
+    get_absolute_deviation_anova("Levene", groups, get_mean)
+}
+
+// Levene's test centered on the median, robust to non-normal data
+#[allow(unused)]
+pub fn get_brown_forsythe_test(groups: &[Array1<f64>]) -> Result<TestResult, String>
+{
+    get_absolute_deviation_anova("Brown-Forsythe", groups, get_median)
+}
+
+fn get_absolute_deviation_anova(test_name: &str, groups: &[Array1<f64>], get_center: fn(&Array1<f64>) -> Result<f64, String>)
+-> Result<TestResult, String>
+{
+    let mut absolute_deviations = Vec::with_capacity(groups.len());
+    for group in groups
+    {
+        let center = get_center(group)?;
+        absolute_deviations.push(group.mapv(|x| (x - center).abs()));
+    }
+    let table = get_one_way_anova(&absolute_deviations)?;
+    let (between, residual) = (&table.rows[0], &table.rows[1]);
+    Ok(TestResult::new(test_name,
+                       between.f_statistic.unwrap(),
+                       vec![between.degrees_of_freedom, residual.degrees_of_freedom],
+                       between.p_value.unwrap()))
+}
+
+#[allow(unused)]
+pub fn get_bartlett_test(groups: &[Array1<f64>]) -> Result<TestResult, String>
+{
+    if groups.len() < 2
+    {
+        return Err(String::from("Need at least two groups!"));
+    }
+    let num_groups = groups.len() as f64;
+    let mut total_degrees_of_freedom = 0.0;
+    let mut pooled_sum = 0.0;
+    let mut sum_of_log_variances = 0.0;
+    let mut sum_of_reciprocals = 0.0;
+    for group in groups
+    {
+        check_vector_for_nans(group)?;
+        if group.len() < 2
+        {
+            return Err(String::from("Every group needs at least two observations!"));
+        }
+        let degrees_of_freedom = (group.len() - 1) as f64;
+        let variance = get_variance(group)?;
+        if variance <= 0.0
+        {
+            return Err(String::from("Every group needs a positive variance!"));
+        }
+        total_degrees_of_freedom += degrees_of_freedom;
+        pooled_sum += degrees_of_freedom * variance;
+        sum_of_log_variances += degrees_of_freedom * variance.ln();
+        sum_of_reciprocals += 1.0 / degrees_of_freedom;
+    }
+    let pooled_variance = pooled_sum / total_degrees_of_freedom;
+    let correction = 1.0 + (sum_of_reciprocals - 1.0 / total_degrees_of_freedom) / (3.0 * (num_groups - 1.0));
+    let statistic = (total_degrees_of_freedom * pooled_variance.ln() - sum_of_log_variances) / correction;
+
+    Ok(TestResult::new("Bartlett", statistic, vec![num_groups - 1.0], get_chi_squared_sf(statistic, num_groups - 1.0)?))
 }
\ No newline at end of file
@@ -4,4 +4,31 @@ pub mod counting;
 pub mod sampling;
 pub mod file_reading;
 pub mod multiple_linear_regression;
-pub mod hypothesis_testing;
\ No newline at end of file
+pub mod hypothesis_testing;
+pub mod distributions;
+pub mod linear_algebra;
+pub mod generalized_linear_models;
+pub mod basis_expansion;
+pub mod robust_regression;
+pub mod errors_in_variables_regression;
+pub mod analysis_of_variance;
+pub mod contingency_table;
+pub mod exact_tests;
+pub mod rank_tests;
+pub mod normality_tests;
+pub mod multiple_testing;
+pub mod power_analysis;
+pub mod effect_sizes;
+pub mod confidence_intervals;
+pub mod big_integer;
+pub mod time_series;
+pub mod optimization;
+pub mod smoothing;
+pub mod stationarity_tests;
+pub mod arima;
+pub mod decomposition;
+pub mod principal_components;
+pub mod factor_analysis;
+pub mod clustering;
+pub mod hierarchical_clustering;
+pub mod density_clustering;
\ No newline at end of file
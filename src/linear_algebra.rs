@@ -0,0 +1,375 @@
+use ndarray::{Array1, Array2};
+
+const SINGULARITY_TOLERANCE: f64 = 1e-12;
+const MAX_JACOBI_SWEEPS: usize = 100;
+
+// A = U diag(singular_values) V^T
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct SingularValueDecomposition
+{
+    pub u: Array2<f64>,
+    pub singular_values: Array1<f64>,
+    pub v: Array2<f64>,
+}
+
+// A = V diag(eigenvalues) V^T
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct SymmetricEigendecomposition
+{
+    pub eigenvalues: Array1<f64>,
+    pub eigenvectors: Array2<f64>,
+}
+
+#[allow(unused)]
+pub fn solve_linear_system(a: &Array2<f64>, b: &Array1<f64>) -> Result<Array1<f64>, String>
+{
+    let n = check_matrix_is_square(a)?;
+    if b.len() != n
+    {
+        return Err(String::from("Right hand side must have as many elements as the matrix has rows!"));
+    }
+
+    let mut lu = a.to_owned();
+    let mut x = b.to_owned();
+    let scale = lu.iter().fold(0.0f64, |acc, v| acc.max(v.abs())).max(1.0);
+
+    // Gaussian elimination with partial pivoting
+    for k in 0..n
+    {
+        let pivot_row = (k..n)
+            .max_by(|&i, &j| lu[[i, k]].abs().partial_cmp(&lu[[j, k]].abs()).unwrap())
+            .unwrap();
+        if lu[[pivot_row, k]].abs() <= SINGULARITY_TOLERANCE * scale
+        {
+            return Err(String::from("Matrix is singular!"));
+        }
+        if pivot_row != k
+        {
+            for j in 0..n
+            {
+                lu.swap([k, j], [pivot_row, j]);
+            }
+            x.swap(k, pivot_row);
+        }
+        for i in k + 1..n
+        {
+            let factor = lu[[i, k]] / lu[[k, k]];
+            for j in k..n
+            {
+                lu[[i, j]] -= factor * lu[[k, j]];
+            }
+            x[i] -= factor * x[k];
+        }
+    }
+
+    // Back substitution
+    for i in (0..n).rev()
+    {
+        let mut sum = x[i];
+        for j in i + 1..n
+        {
+            sum -= lu[[i, j]] * x[j];
+        }
+        x[i] = sum / lu[[i, i]];
+    }
+    Ok(x)
+}
+
+#[allow(unused)]
+pub fn get_inverse(a: &Array2<f64>) -> Result<Array2<f64>, String>
+{
+    let n = check_matrix_is_square(a)?;
+    let mut inverse = Array2::<f64>::zeros((n, n));
+    for j in 0..n
+    {
+        let mut unit_vector = Array1::<f64>::zeros(n);
+        unit_vector[j] = 1.0;
+        let column = solve_linear_system(a, &unit_vector)?;
+        inverse.column_mut(j).assign(&column);
+    }
+    Ok(inverse)
+}
+
+// Solves (X^T W X) b = X^T W y
+#[allow(unused)]
+pub fn get_weighted_least_squares(features: &Array2<f64>, observations: &Array1<f64>, weights: &Array1<f64>)
+-> Result<Array1<f64>, String>
+{
+    if features.nrows() != observations.len() || features.nrows() != weights.len()
+    {
+        return Err(String::from("Features, observations and weights must have the same number of rows!"));
+    }
+    let weighted_features = features * &weights.view().insert_axis(ndarray::Axis(1));
+    let normal_matrix = get_matrix_product(&features.t().to_owned(), &weighted_features)?;
+    let right_hand_side = get_matrix_vector_product(&weighted_features.t().to_owned(), observations)?;
+    solve_linear_system(&normal_matrix, &right_hand_side)
+}
+
+// ndarray's dot() dispatches to BLAS, which is not linked without a LAPACK backend
+#[allow(unused)]
+pub fn get_matrix_product(a: &Array2<f64>, b: &Array2<f64>) -> Result<Array2<f64>, String>
+{
+    if a.ncols() != b.nrows()
+    {
+        return Err(String::from("Matrix dimensions do not match!"));
+    }
+    let mut product = Array2::<f64>::zeros((a.nrows(), b.ncols()));
+    for i in 0..a.nrows()
+    {
+        for k in 0..a.ncols()
+        {
+            let a_ik = a[[i, k]];
+            for j in 0..b.ncols()
+            {
+                product[[i, j]] += a_ik * b[[k, j]];
+            }
+        }
+    }
+    Ok(product)
+}
+
+#[allow(unused)]
+pub fn get_matrix_vector_product(a: &Array2<f64>, x: &Array1<f64>) -> Result<Array1<f64>, String>
+{
+    if a.ncols() != x.len()
+    {
+        return Err(String::from("Matrix and vector dimensions do not match!"));
+    }
+    Ok(a.rows()
+        .into_iter()
+        .map(|row| row.iter().zip(x).map(|(a_ij, x_j)| a_ij * x_j).sum())
+        .collect())
+}
+
+// Householder QR, avoids squaring the condition number like the normal equations do
+#[allow(unused)]
+pub fn get_least_squares(features: &Array2<f64>, observations: &Array1<f64>) -> Result<Array1<f64>, String>
+{
+    let (num_rows, num_columns) = features.dim();
+    if num_rows != observations.len()
+    {
+        return Err(String::from("Features and observations must have the same number of rows!"));
+    }
+    if num_rows < num_columns
+    {
+        return Err(String::from("Number of rows must not be less than the number of columns!"));
+    }
+
+    let mut r = features.to_owned();
+    let mut qt_y = observations.to_owned();
+    let scale = r.iter().fold(0.0f64, |acc, v| acc.max(v.abs())).max(1.0);
+
+    for k in 0..num_columns
+    {
+        let norm = (k..num_rows).map(|i| r[[i, k]].powi(2)).sum::<f64>().sqrt();
+        if norm <= SINGULARITY_TOLERANCE * scale
+        {
+            return Err(String::from("Features are linearly dependent!"));
+        }
+        let alpha = if r[[k, k]] > 0.0 { -norm } else { norm };
+        let mut v: Vec<f64> = (k..num_rows).map(|i| r[[i, k]]).collect();
+        v[0] -= alpha;
+        let v_norm_squared: f64 = v.iter().map(|v_i| v_i * v_i).sum();
+
+        for j in k..num_columns
+        {
+            let projection: f64 = (k..num_rows).map(|i| v[i - k] * r[[i, j]]).sum::<f64>() * 2.0 / v_norm_squared;
+            for i in k..num_rows
+            {
+                r[[i, j]] -= projection * v[i - k];
+            }
+        }
+        let projection: f64 = (k..num_rows).map(|i| v[i - k] * qt_y[i]).sum::<f64>() * 2.0 / v_norm_squared;
+        for i in k..num_rows
+        {
+            qt_y[i] -= projection * v[i - k];
+        }
+    }
+
+    let mut coefficients = Array1::<f64>::zeros(num_columns);
+    for i in (0..num_columns).rev()
+    {
+        let mut sum = qt_y[i];
+        for j in i + 1..num_columns
+        {
+            sum -= r[[i, j]] * coefficients[j];
+        }
+        coefficients[i] = sum / r[[i, i]];
+    }
+    Ok(coefficients)
+}
+
+// One-sided Jacobi rotations of the columns, singular values are in decreasing order and U has as many
+// columns as A, columns of U belonging to zero singular values are zero
+#[allow(unused)]
+pub fn get_singular_value_decomposition(a: &Array2<f64>) -> Result<SingularValueDecomposition, String>
+{
+    let (num_rows, num_columns) = a.dim();
+    if num_rows == 0 || num_columns == 0
+    {
+        return Err(String::from("Matrix cannot be empty!"));
+    }
+    let mut u = a.to_owned();
+    let mut v = Array2::<f64>::eye(num_columns);
+    let mut converged = false;
+    for _ in 0..MAX_JACOBI_SWEEPS
+    {
+        let mut rotated = false;
+        for i in 0..num_columns
+        {
+            for j in i + 1..num_columns
+            {
+                let alpha: f64 = u.column(i).iter().map(|x| x * x).sum();
+                let beta: f64 = u.column(j).iter().map(|x| x * x).sum();
+                let gamma: f64 = u.column(i).iter().zip(u.column(j)).map(|(x, y)| x * y).sum();
+                if gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt()
+                {
+                    continue;
+                }
+                rotated = true;
+                let zeta = (beta - alpha) / (2.0 * gamma);
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = c * t;
+                for matrix in [&mut u, &mut v]
+                {
+                    for row in 0..matrix.nrows()
+                    {
+                        let (x, y) = (matrix[[row, i]], matrix[[row, j]]);
+                        matrix[[row, i]] = c * x - s * y;
+                        matrix[[row, j]] = s * x + c * y;
+                    }
+                }
+            }
+        }
+        if !rotated
+        {
+            converged = true;
+            break;
+        }
+    }
+    if !converged
+    {
+        return Err(String::from("Singular value decomposition did not converge!"));
+    }
+
+    let norms: Vec<f64> = u.columns().into_iter().map(|column| column.iter().map(|x| x * x).sum::<f64>().sqrt()).collect();
+    let mut order: Vec<usize> = (0..num_columns).collect();
+    order.sort_by(|&i, &j| norms[j].partial_cmp(&norms[i]).unwrap());
+    let largest = norms[order[0]];
+    let mut left = Array2::<f64>::zeros((num_rows, num_columns));
+    let mut right = Array2::<f64>::zeros((num_columns, num_columns));
+    let mut singular_values = Array1::<f64>::zeros(num_columns);
+    for (k, &j) in order.iter().enumerate()
+    {
+        singular_values[k] = norms[j];
+        right.column_mut(k).assign(&v.column(j));
+        if norms[j] > SINGULARITY_TOLERANCE * largest
+        {
+            left.column_mut(k).assign(&u.column(j).mapv(|x| x / norms[j]));
+        }
+    }
+    Ok(SingularValueDecomposition { u: left, singular_values, v: right })
+}
+
+// Cyclic Jacobi rotations, eigenvalues are in decreasing order with the eigenvectors as columns
+#[allow(unused)]
+pub fn get_symmetric_eigendecomposition(a: &Array2<f64>) -> Result<SymmetricEigendecomposition, String>
+{
+    let n = check_matrix_is_square(a)?;
+    if n == 0
+    {
+        return Err(String::from("Matrix cannot be empty!"));
+    }
+    let scale = a.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if (0..n).any(|i| (0..i).any(|j| (a[[i, j]] - a[[j, i]]).abs() > SINGULARITY_TOLERANCE * scale.max(1.0)))
+    {
+        return Err(String::from("Matrix must be symmetric!"));
+    }
+    let mut d = a.to_owned();
+    let mut v = Array2::<f64>::eye(n);
+    let mut converged = false;
+    for _ in 0..MAX_JACOBI_SWEEPS
+    {
+        let off_diagonal: f64 = (0..n).map(|i| (0..i).map(|j| d[[i, j]].powi(2)).sum::<f64>()).sum();
+        if off_diagonal.sqrt() <= f64::EPSILON * scale
+        {
+            converged = true;
+            break;
+        }
+        for p in 0..n
+        {
+            for q in p + 1..n
+            {
+                // Entries negligible against both diagonal entries are set to zero
+                if d[[p, q]].abs() <= 0.5 * f64::EPSILON * d[[p, p]].abs().min(d[[q, q]].abs())
+                {
+                    d[[p, q]] = 0.0;
+                    d[[q, p]] = 0.0;
+                }
+                if d[[p, q]] == 0.0
+                {
+                    continue;
+                }
+                let theta = (d[[q, q]] - d[[p, p]]) / (2.0 * d[[p, q]]);
+                let t = if theta >= 0.0 { 1.0 } else { -1.0 } / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for k in 0..n
+                {
+                    let (x, y) = (d[[k, p]], d[[k, q]]);
+                    d[[k, p]] = c * x - s * y;
+                    d[[k, q]] = s * x + c * y;
+                }
+                for k in 0..n
+                {
+                    let (x, y) = (d[[p, k]], d[[q, k]]);
+                    d[[p, k]] = c * x - s * y;
+                    d[[q, k]] = s * x + c * y;
+                }
+                for k in 0..n
+                {
+                    let (x, y) = (v[[k, p]], v[[k, q]]);
+                    v[[k, p]] = c * x - s * y;
+                    v[[k, q]] = s * x + c * y;
+                }
+            }
+        }
+    }
+    if !converged
+    {
+        return Err(String::from("Eigendecomposition did not converge!"));
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| d[[j, j]].partial_cmp(&d[[i, i]]).unwrap());
+    let eigenvalues = order.iter().map(|&i| d[[i, i]]).collect();
+    let mut eigenvectors = Array2::<f64>::zeros((n, n));
+    for (k, &i) in order.iter().enumerate()
+    {
+        eigenvectors.column_mut(k).assign(&v.column(i));
+    }
+    Ok(SymmetricEigendecomposition { eigenvalues, eigenvectors })
+}
+
+#[allow(unused)]
+pub fn add_intercept_column(features: &Array2<f64>) -> Array2<f64>
+{
+    let mut design_matrix = Array2::<f64>::ones((features.nrows(), features.ncols() + 1));
+    design_matrix.slice_mut(ndarray::s![.., 1..]).assign(features);
+    design_matrix
+}
+
+fn check_matrix_is_square(a: &Array2<f64>) -> Result<usize, String>
+{
+    if a.nrows() != a.ncols()
+    {
+        Err(String::from("Matrix must be square!"))
+    }
+    else
+    {
+        Ok(a.nrows())
+    }
+}
@@ -0,0 +1,122 @@
+use super::hypothesis_testing::TestResult;
+use super::utilities::check_vector_for_nans;
+use ndarray::Array1;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PAdjustMethod
+{
+    // Family-wise error rate
+    Bonferroni,
+    Holm,
+    Hochberg,
+    Hommel,
+    // False discovery rate
+    BenjaminiHochberg,
+    BenjaminiYekutieli,
+}
+
+// Adjusted p-values in the order of the input, as in R's p.adjust
+#[allow(unused)]
+pub fn get_adjusted_p_values(p_values: &Array1<f64>, method: PAdjustMethod) -> Result<Array1<f64>, String>
+{
+    let p_values = check_vector_for_nans(p_values)?;
+    if p_values.iter().any(|&p| !(0.0..=1.0).contains(&p))
+    {
+        return Err(String::from("P-values must be between 0 and 1!"));
+    }
+    let n = p_values.len();
+    if n == 0
+    {
+        return Ok(Array1::zeros(0));
+    }
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| p_values[a].partial_cmp(&p_values[b]).unwrap());
+    let sorted: Vec<f64> = order.iter().map(|&i| p_values[i]).collect();
+    let num_tests = n as f64;
+
+    let adjusted_sorted: Vec<f64> = match method
+    {
+        PAdjustMethod::Bonferroni => sorted.iter().map(|p| p * num_tests).collect(),
+        PAdjustMethod::Holm =>
+        {
+            // Step-down, running maximum from the smallest p-value
+            let mut running_maximum: f64 = 0.0;
+            sorted.iter().enumerate().map(|(i, p)|
+            {
+                running_maximum = running_maximum.max((n - i) as f64 * p);
+                running_maximum
+            }).collect()
+        },
+        PAdjustMethod::Hochberg => get_step_up(&sorted, |i| (n - i) as f64),
+        PAdjustMethod::Hommel => get_hommel(&sorted),
+        PAdjustMethod::BenjaminiHochberg => get_step_up(&sorted, |i| num_tests / (i + 1) as f64),
+        PAdjustMethod::BenjaminiYekutieli =>
+        {
+            let harmonic_sum: f64 = (1..=n).map(|i| 1.0 / i as f64).sum();
+            get_step_up(&sorted, |i| harmonic_sum * num_tests / (i + 1) as f64)
+        },
+    };
+
+    let mut adjusted = Array1::<f64>::zeros(n);
+    for (rank, &index) in order.iter().enumerate()
+    {
+        adjusted[index] = adjusted_sorted[rank].min(1.0);
+    }
+    Ok(adjusted)
+}
+
+// Adjusts the p-values of several test results at once
+#[allow(unused)]
+pub fn adjust_test_results(results: &mut [TestResult], method: PAdjustMethod) -> Result<(), String>
+{
+    let p_values: Array1<f64> = results.iter().map(|result| result.p_value).collect();
+    let adjusted = get_adjusted_p_values(&p_values, method)?;
+    for (result, &p_value) in results.iter_mut().zip(adjusted.iter())
+    {
+        result.p_value = p_value;
+    }
+    Ok(())
+}
+
+// Step-up, running minimum from the largest p-value
+fn get_step_up<F>(sorted: &[f64], multiplier: F) -> Vec<f64>
+where F: Fn(usize) -> f64
+{
+    let mut adjusted = vec![0.0; sorted.len()];
+    let mut running_minimum = f64::INFINITY;
+    for i in (0..sorted.len()).rev()
+    {
+        running_minimum = running_minimum.min(multiplier(i) * sorted[i]);
+        adjusted[i] = running_minimum;
+    }
+    adjusted
+}
+
+// Closed testing with Simes' tests over all subsets of size m
+fn get_hommel(sorted: &[f64]) -> Vec<f64>
+{
+    let n = sorted.len();
+    let initial = sorted.iter().enumerate().map(|(i, p)| n as f64 * p / (i + 1) as f64).fold(f64::INFINITY, f64::min);
+    let mut q = vec![initial; n];
+    let mut adjusted = q.clone();
+    for m in (2..n).rev()
+    {
+        let simes_minimum = (n - m + 1..n).zip(2..)
+            .map(|(i, j)| m as f64 * sorted[i] / j as f64)
+            .fold(f64::INFINITY, f64::min);
+        for i in 0..=n - m
+        {
+            q[i] = (m as f64 * sorted[i]).min(simes_minimum);
+        }
+        for i in n - m + 1..n
+        {
+            q[i] = q[n - m];
+        }
+        for (a, &q_i) in adjusted.iter_mut().zip(&q)
+        {
+            *a = a.max(q_i);
+        }
+    }
+    adjusted.iter().zip(sorted).map(|(a, p)| a.max(*p)).collect()
+}
@@ -0,0 +1,564 @@
+use super::distributions::{get_chi_squared_sf, get_normal_cdf, get_normal_ppf, get_normal_sf};
+use super::hypothesis_testing::{HypothesisTest, TestResult};
+use super::utilities::{check_vector_for_nans, get_mean, get_standard_deviation};
+use ndarray::Array1;
+
+const MAX_SHAPIRO_WILK_SAMPLE_SIZE: usize = 5000;
+// Exact Kolmogorov-Smirnov p-values below these sizes, as in R's ks.test
+const MAX_EXACT_ONE_SAMPLE_SIZE: usize = 100;
+const MAX_EXACT_TWO_SAMPLE_PRODUCT: usize = 10_000;
+
+// Royston's (1995) approximation, algorithm AS R94
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct ShapiroWilk;
+
+// Composite test with estimated mean and standard deviation,
+// p-values from D'Agostino and Stephens (1986)
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct AndersonDarling;
+
+// Compares the data with a fully specified continuous cdf
+#[allow(unused)]
+pub struct KolmogorovSmirnov<F>
+where F: Fn(f64) -> f64
+{
+    cdf: F,
+}
+
+// Data holds exactly two samples
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct TwoSampleKolmogorovSmirnov;
+
+// Kolmogorov-Smirnov against a normal with estimated parameters,
+// p-values from Dallal and Wilkinson (1986)
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct Lilliefors;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct JarqueBera;
+
+// Combines the skewness and kurtosis z-scores into K^2
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct DAgostinoPearson;
+
+impl HypothesisTest for ShapiroWilk
+{
+    type Data = Array1<f64>;
+
+    fn run(&self, data: &Array1<f64>) -> Result<TestResult, String>
+    {
+        let sorted = get_sorted(data)?;
+        let n = sorted.len();
+        if !(3..=MAX_SHAPIRO_WILK_SAMPLE_SIZE).contains(&n)
+        {
+            return Err(String::from("Shapiro-Wilk needs between 3 and 5000 observations!"));
+        }
+        if sorted[n - 1] - sorted[0] <= 0.0
+        {
+            return Err(String::from("All observations are identical!"));
+        }
+
+        let coefficients = get_shapiro_wilk_coefficients(n)?;
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let numerator = coefficients.iter().zip(&sorted).map(|(a, x)| a * x).sum::<f64>().powi(2);
+        let denominator: f64 = sorted.iter().map(|x| (x - mean).powi(2)).sum();
+        let w = (numerator / denominator).min(1.0);
+
+        let p_value = if n == 3
+        {
+            6.0 / std::f64::consts::PI * (w.sqrt().asin() - std::f64::consts::FRAC_PI_3)
+        }
+        else
+        {
+            let n = n as f64;
+            let mut y = (1.0 - w).ln();
+            let (mean, standard_deviation) = if n <= 11.0
+            {
+                let gamma = -2.273 + 0.459 * n;
+                if y >= gamma
+                {
+                    return Ok(TestResult::new("Shapiro-Wilk", w, vec![], 0.0));
+                }
+                y = -(gamma - y).ln();
+                (get_polynomial(&[0.544, -0.39978, 0.025054, -6.714e-4], n),
+                 get_polynomial(&[1.3822, -0.77857, 0.062767, -0.0020322], n).exp())
+            }
+            else
+            {
+                let ln_n = n.ln();
+                (get_polynomial(&[-1.5861, -0.31082, -0.083751, 0.0038915], ln_n),
+                 get_polynomial(&[-0.4803, -0.082676, 0.0030302], ln_n).exp())
+            };
+            get_normal_sf((y - mean) / standard_deviation)
+        };
+        Ok(TestResult::new("Shapiro-Wilk", w, vec![], p_value))
+    }
+}
+
+impl HypothesisTest for AndersonDarling
+{
+    type Data = Array1<f64>;
+
+    fn run(&self, data: &Array1<f64>) -> Result<TestResult, String>
+    {
+        let sorted = get_sorted(data)?;
+        let n = sorted.len();
+        if n < 8
+        {
+            return Err(String::from("Anderson-Darling needs at least 8 observations!"));
+        }
+        let standardized = get_standardized(&sorted)?;
+        let sum: f64 = (0..n).map(|i|
+        {
+            (2 * i + 1) as f64 * (get_normal_cdf(standardized[i]).ln() + get_normal_sf(standardized[n - 1 - i]).ln())
+        }).sum();
+        let n = n as f64;
+        let a_squared = -n - sum / n;
+
+        // Small sample adjustment for estimated parameters
+        let adjusted = a_squared * (1.0 + 0.75 / n + 2.25 / n.powi(2));
+        let p_value = if adjusted < 0.2
+        {
+            1.0 - (-13.436 + 101.14 * adjusted - 223.73 * adjusted.powi(2)).exp()
+        }
+        else if adjusted < 0.34
+        {
+            1.0 - (-8.318 + 42.796 * adjusted - 59.938 * adjusted.powi(2)).exp()
+        }
+        else if adjusted < 0.6
+        {
+            (0.9177 - 4.279 * adjusted - 1.38 * adjusted.powi(2)).exp()
+        }
+        else if adjusted < 10.0
+        {
+            (1.2937 - 5.709 * adjusted + 0.0186 * adjusted.powi(2)).exp()
+        }
+        else
+        {
+            3.7e-24
+        };
+        Ok(TestResult::new("Anderson-Darling", a_squared, vec![], p_value))
+    }
+}
+
+#[allow(unused)]
+impl<F> KolmogorovSmirnov<F>
+where F: Fn(f64) -> f64
+{
+    pub fn new(cdf: F) -> Self
+    {
+        KolmogorovSmirnov { cdf }
+    }
+}
+
+impl<F> HypothesisTest for KolmogorovSmirnov<F>
+where F: Fn(f64) -> f64
+{
+    type Data = Array1<f64>;
+
+    fn run(&self, data: &Array1<f64>) -> Result<TestResult, String>
+    {
+        let sorted = get_sorted(data)?;
+        if sorted.is_empty()
+        {
+            return Err(String::from("Vector cannot be empty"));
+        }
+        let n = sorted.len();
+        let statistic = get_one_sample_statistic(&sorted, &self.cdf);
+        let p_value = if n < MAX_EXACT_ONE_SAMPLE_SIZE && !has_ties(&sorted)
+        {
+            1.0 - get_kolmogorov_cdf(n, statistic)
+        }
+        else
+        {
+            get_kolmogorov_limit_sf((n as f64).sqrt() * statistic)
+        };
+        Ok(TestResult::new("Kolmogorov-Smirnov", statistic, vec![], p_value))
+    }
+}
+
+impl HypothesisTest for TwoSampleKolmogorovSmirnov
+{
+    type Data = [Array1<f64>];
+
+    fn run(&self, data: &[Array1<f64>]) -> Result<TestResult, String>
+    {
+        if data.len() != 2
+        {
+            return Err(String::from("Two-sample Kolmogorov-Smirnov needs exactly two samples!"));
+        }
+        let sample_1 = get_sorted(&data[0])?;
+        let sample_2 = get_sorted(&data[1])?;
+        if sample_1.is_empty() || sample_2.is_empty()
+        {
+            return Err(String::from("Vector cannot be empty"));
+        }
+        let (m, n) = (sample_1.len(), sample_2.len());
+
+        // Largest distance between the empirical cdfs, evaluated at every jump
+        let mut statistic: f64 = 0.0;
+        let (mut i, mut j) = (0, 0);
+        while i < m && j < n
+        {
+            let x = sample_1[i].min(sample_2[j]);
+            while i < m && sample_1[i] <= x
+            {
+                i += 1;
+            }
+            while j < n && sample_2[j] <= x
+            {
+                j += 1;
+            }
+            statistic = statistic.max((i as f64 / m as f64 - j as f64 / n as f64).abs());
+        }
+
+        let mut combined = sample_1.clone();
+        combined.extend(&sample_2);
+        combined.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let p_value = if m * n < MAX_EXACT_TWO_SAMPLE_PRODUCT && !has_ties(&combined)
+        {
+            1.0 - get_smirnov_cdf(m, n, statistic)
+        }
+        else
+        {
+            get_kolmogorov_limit_sf(((m * n) as f64 / (m + n) as f64).sqrt() * statistic)
+        };
+        Ok(TestResult::new("Two-sample Kolmogorov-Smirnov", statistic, vec![], p_value))
+    }
+}
+
+impl HypothesisTest for Lilliefors
+{
+    type Data = Array1<f64>;
+
+    fn run(&self, data: &Array1<f64>) -> Result<TestResult, String>
+    {
+        let sorted = get_sorted(data)?;
+        let n = sorted.len();
+        if n < 5
+        {
+            return Err(String::from("Lilliefors test needs at least 5 observations!"));
+        }
+        let standardized = get_standardized(&sorted)?;
+        let statistic = get_one_sample_statistic(&standardized, get_normal_cdf);
+
+        let n = n as f64;
+        let (k, effective_n) = if n <= 100.0 { (statistic, n) } else { (statistic * (n / 100.0).powf(0.49), 100.0) };
+        let mut p_value = (-7.01256 * k.powi(2) * (effective_n + 2.78019) + 2.99587 * k * (effective_n + 2.78019).sqrt()
+                           - 0.122119 + 0.974598 / effective_n.sqrt() + 1.67997 / effective_n).exp();
+        // The Dallal-Wilkinson formula is only accurate for small p-values
+        if p_value > 0.1
+        {
+            let kk = (n.sqrt() - 0.01 + 0.85 / n.sqrt()) * statistic;
+            p_value = if kk <= 0.302
+            {
+                1.0
+            }
+            else if kk <= 0.5
+            {
+                get_polynomial(&[2.76773, -19.828315, 80.709644, -138.55152, 81.218052], kk)
+            }
+            else if kk <= 0.9
+            {
+                get_polynomial(&[-4.901232, 40.662806, -97.490286, 94.029866, -32.355711], kk)
+            }
+            else if kk <= 1.31
+            {
+                get_polynomial(&[6.198765, -19.558097, 23.186922, -12.234627, 2.423045], kk)
+            }
+            else
+            {
+                0.0
+            };
+        }
+        Ok(TestResult::new("Lilliefors", statistic, vec![], p_value))
+    }
+}
+
+impl HypothesisTest for JarqueBera
+{
+    type Data = Array1<f64>;
+
+    fn run(&self, data: &Array1<f64>) -> Result<TestResult, String>
+    {
+        let (skewness, kurtosis) = get_skewness_and_kurtosis(data)?;
+        let n = data.len() as f64;
+        let statistic = n / 6.0 * (skewness.powi(2) + (kurtosis - 3.0).powi(2) / 4.0);
+        Ok(TestResult::new("Jarque-Bera", statistic, vec![2.0], get_chi_squared_sf(statistic, 2.0)?))
+    }
+}
+
+impl HypothesisTest for DAgostinoPearson
+{
+    type Data = Array1<f64>;
+
+    fn run(&self, data: &Array1<f64>) -> Result<TestResult, String>
+    {
+        if data.len() < 8
+        {
+            return Err(String::from("D'Agostino's K-squared test needs at least 8 observations!"));
+        }
+        let (skewness, kurtosis) = get_skewness_and_kurtosis(data)?;
+        let n = data.len() as f64;
+
+        // D'Agostino's transformation of the sample skewness
+        let y = skewness * ((n + 1.0) * (n + 3.0) / (6.0 * (n - 2.0))).sqrt();
+        let beta_2 = 3.0 * (n.powi(2) + 27.0 * n - 70.0) * (n + 1.0) * (n + 3.0) / ((n - 2.0) * (n + 5.0) * (n + 7.0) * (n + 9.0));
+        let w_squared = -1.0 + (2.0 * (beta_2 - 1.0)).sqrt();
+        let delta = 1.0 / w_squared.sqrt().ln().sqrt();
+        let alpha = (2.0 / (w_squared - 1.0)).sqrt();
+        let z_skewness = delta * (y / alpha).asinh();
+
+        // Anscombe and Glynn's transformation of the sample kurtosis
+        let expected = 3.0 * (n - 1.0) / (n + 1.0);
+        let variance = 24.0 * n * (n - 2.0) * (n - 3.0) / ((n + 1.0).powi(2) * (n + 3.0) * (n + 5.0));
+        let x = (kurtosis - expected) / variance.sqrt();
+        let root_beta_1 = 6.0 * (n.powi(2) - 5.0 * n + 2.0) / ((n + 7.0) * (n + 9.0))
+                          * (6.0 * (n + 3.0) * (n + 5.0) / (n * (n - 2.0) * (n - 3.0))).sqrt();
+        let a = 6.0 + 8.0 / root_beta_1 * (2.0 / root_beta_1 + (1.0 + 4.0 / root_beta_1.powi(2)).sqrt());
+        let denominator = 1.0 + x * (2.0 / (a - 4.0)).sqrt();
+        let cube_root = ((1.0 - 2.0 / a) / denominator.abs()).cbrt() * denominator.signum();
+        let z_kurtosis = (1.0 - 2.0 / (9.0 * a) - cube_root) / (2.0 / (9.0 * a)).sqrt();
+
+        let statistic = z_skewness.powi(2) + z_kurtosis.powi(2);
+        Ok(TestResult::new("D'Agostino-Pearson K-squared", statistic, vec![2.0], get_chi_squared_sf(statistic, 2.0)?))
+    }
+}
+
+// Antisymmetric weights for the order statistics
+fn get_shapiro_wilk_coefficients(n: usize) -> Result<Vec<f64>, String>
+{
+    let mut coefficients = vec![0.0; n];
+    let half = n / 2;
+    if n == 3
+    {
+        coefficients[0] = -std::f64::consts::FRAC_1_SQRT_2;
+        coefficients[2] = std::f64::consts::FRAC_1_SQRT_2;
+        return Ok(coefficients);
+    }
+    let mut m = Vec::with_capacity(n);
+    for i in 1..=n
+    {
+        m.push(get_normal_ppf((i as f64 - 0.375) / (n as f64 + 0.25))?);
+    }
+    let sum_of_squares: f64 = m.iter().map(|m_i| m_i.powi(2)).sum();
+    let u = 1.0 / (n as f64).sqrt();
+
+    let a_n = get_polynomial(&[0.0, 0.221157, -0.147981, -2.071190, 4.434685, -2.706056], u) + m[n - 1] / sum_of_squares.sqrt();
+    coefficients[n - 1] = a_n;
+    let (num_fixed, phi) = if n > 5
+    {
+        let a_n_1 = get_polynomial(&[0.0, 0.042981, -0.293762, -1.752461, 5.682633, -3.582633], u) + m[n - 2] / sum_of_squares.sqrt();
+        coefficients[n - 2] = a_n_1;
+        (2, (sum_of_squares - 2.0 * m[n - 1].powi(2) - 2.0 * m[n - 2].powi(2)) / (1.0 - 2.0 * a_n.powi(2) - 2.0 * a_n_1.powi(2)))
+    }
+    else
+    {
+        (1, (sum_of_squares - 2.0 * m[n - 1].powi(2)) / (1.0 - 2.0 * a_n.powi(2)))
+    };
+    for i in half..n - num_fixed
+    {
+        coefficients[i] = m[i] / phi.sqrt();
+    }
+    for i in 0..half
+    {
+        coefficients[i] = -coefficients[n - 1 - i];
+    }
+    if n % 2 == 1
+    {
+        coefficients[half] = 0.0;
+    }
+    Ok(coefficients)
+}
+
+fn get_one_sample_statistic<F>(sorted: &[f64], cdf: F) -> f64
+where F: Fn(f64) -> f64
+{
+    let n = sorted.len() as f64;
+    sorted.iter().enumerate().map(|(i, &x)|
+    {
+        let probability = cdf(x);
+        (probability - i as f64 / n).max((i + 1) as f64 / n - probability)
+    }).fold(0.0, f64::max)
+}
+
+// Marsaglia, Tsang and Wang (2003), P(D_n < d) as a power of a banded matrix
+fn get_kolmogorov_cdf(n: usize, d: f64) -> f64
+{
+    let nd = n as f64 * d;
+    let k = nd.floor() as usize + 1;
+    let m = 2 * k - 1;
+    let h = k as f64 - nd;
+
+    let mut matrix: Vec<Vec<f64>> = (0..m).map(|i| (0..m).map(|j| if i + 1 >= j { 1.0 } else { 0.0 }).collect()).collect();
+    for (i, row) in matrix.iter_mut().enumerate()
+    {
+        row[0] -= h.powi(i as i32 + 1);
+    }
+    for (i, value) in matrix[m - 1].iter_mut().enumerate()
+    {
+        *value -= h.powi((m - i) as i32);
+    }
+    if 2.0 * h - 1.0 > 0.0
+    {
+        matrix[m - 1][0] += (2.0 * h - 1.0).powi(m as i32);
+    }
+    for (i, row) in matrix.iter_mut().enumerate()
+    {
+        for (j, value) in row.iter_mut().enumerate()
+        {
+            if i + 1 > j
+            {
+                for g in 1..=i + 1 - j
+                {
+                    *value /= g as f64;
+                }
+            }
+        }
+    }
+
+    // Exponentiation by squaring, keeping track of a power of ten to avoid overflow
+    let (mut power, mut power_exponent) = (get_identity(m), 0);
+    let (mut base, mut base_exponent) = (matrix, 0);
+    let mut remaining = n;
+    while remaining > 0
+    {
+        if remaining % 2 == 1
+        {
+            power = get_square_product(&power, &base);
+            power_exponent += base_exponent;
+            rescale(&mut power, &mut power_exponent, k - 1);
+        }
+        remaining /= 2;
+        if remaining > 0
+        {
+            base = get_square_product(&base, &base);
+            base_exponent *= 2;
+            rescale(&mut base, &mut base_exponent, k - 1);
+        }
+    }
+
+    let mut probability = power[k - 1][k - 1];
+    for i in 1..=n
+    {
+        probability *= i as f64 / n as f64;
+        if probability < 1e-140
+        {
+            probability *= 1e140;
+            power_exponent -= 140;
+        }
+    }
+    (probability * 10f64.powi(power_exponent)).clamp(0.0, 1.0)
+}
+
+fn get_identity(m: usize) -> Vec<Vec<f64>>
+{
+    (0..m).map(|i| (0..m).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect()
+}
+
+fn get_square_product(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<Vec<f64>>
+{
+    let m = a.len();
+    (0..m).map(|i| (0..m).map(|j| (0..m).map(|l| a[i][l] * b[l][j]).sum()).collect()).collect()
+}
+
+fn rescale(matrix: &mut [Vec<f64>], exponent: &mut i32, center: usize)
+{
+    if matrix[center][center] > 1e140
+    {
+        for value in matrix.iter_mut().flatten()
+        {
+            *value *= 1e-140;
+        }
+        *exponent += 140;
+    }
+}
+
+// P(D_mn < d) by counting lattice paths that stay within the band, as in R's psmirnov
+fn get_smirnov_cdf(m: usize, n: usize, d: f64) -> f64
+{
+    let (m, n) = if m > n { (n, m) } else { (m, n) };
+    let (md, nd) = (m as f64, n as f64);
+    let q = (0.5 + (d * md * nd - 1e-7).floor()) / (md * nd);
+    let mut u: Vec<f64> = (0..=n).map(|j| if j as f64 / nd > q { 0.0 } else { 1.0 }).collect();
+    for i in 1..=m
+    {
+        let w = i as f64 / (i + n) as f64;
+        u[0] = if i as f64 / md > q { 0.0 } else { w * u[0] };
+        for j in 1..=n
+        {
+            u[j] = if (i as f64 / md - j as f64 / nd).abs() > q { 0.0 } else { w * u[j] + u[j - 1] };
+        }
+    }
+    u[n]
+}
+
+// Survival function of the Kolmogorov distribution
+fn get_kolmogorov_limit_sf(x: f64) -> f64
+{
+    if x <= 0.0
+    {
+        return 1.0;
+    }
+    let mut sum = 0.0;
+    for j in 1..=100
+    {
+        let term = (-2.0 * (j as f64 * x).powi(2)).exp();
+        sum += if j % 2 == 1 { term } else { -term };
+        if term < 1e-16
+        {
+            break;
+        }
+    }
+    (2.0 * sum).clamp(0.0, 1.0)
+}
+
+// Population moments, as used by both the Jarque-Bera and the D'Agostino tests
+fn get_skewness_and_kurtosis(data: &Array1<f64>) -> Result<(f64, f64), String>
+{
+    let mean = get_mean(data)?;
+    let n = data.len() as f64;
+    let moment = |k: i32| data.iter().map(|x| (x - mean).powi(k)).sum::<f64>() / n;
+    let m_2 = moment(2);
+    if m_2 <= 0.0
+    {
+        return Err(String::from("All observations are identical!"));
+    }
+    Ok((moment(3) / m_2.powf(1.5), moment(4) / m_2.powi(2)))
+}
+
+fn get_sorted(data: &Array1<f64>) -> Result<Vec<f64>, String>
+{
+    let mut sorted = check_vector_for_nans(data)?.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Ok(sorted)
+}
+
+fn get_standardized(sorted: &[f64]) -> Result<Vec<f64>, String>
+{
+    let data = Array1::from(sorted.to_vec());
+    let mean = get_mean(&data)?;
+    let standard_deviation = get_standard_deviation(&data)?;
+    if standard_deviation <= 0.0
+    {
+        return Err(String::from("All observations are identical!"));
+    }
+    Ok(sorted.iter().map(|x| (x - mean) / standard_deviation).collect())
+}
+
+fn has_ties(sorted: &[f64]) -> bool
+{
+    sorted.windows(2).any(|pair| pair[0] == pair[1])
+}
+
+// Coefficients in increasing order of powers
+fn get_polynomial(coefficients: &[f64], x: f64) -> f64
+{
+    coefficients.iter().rev().fold(0.0, |accumulator, c| accumulator * x + c)
+}
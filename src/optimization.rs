@@ -0,0 +1,101 @@
+use ndarray::Array1;
+
+const REFLECTION: f64 = 1.0;
+const EXPANSION: f64 = 2.0;
+const CONTRACTION: f64 = 0.5;
+const SHRINKAGE: f64 = 0.5;
+
+// Derivative-free Nelder-Mead simplex search, the initial simplex steps initial_step along every axis.
+// Returns the best point and its function value
+#[allow(unused)]
+pub fn get_nelder_mead_minimum<F>(f: F, initial_point: &Array1<f64>, initial_step: f64, tolerance: f64, max_iterations: usize)
+-> Result<(Array1<f64>, f64), String>
+where F: Fn(&Array1<f64>) -> f64
+{
+    let dimension = initial_point.len();
+    if dimension == 0
+    {
+        return Err(String::from("Vector cannot be empty"));
+    }
+    let mut simplex: Vec<Array1<f64>> = vec![initial_point.clone()];
+    for i in 0..dimension
+    {
+        let mut vertex = initial_point.clone();
+        vertex[i] += initial_step;
+        simplex.push(vertex);
+    }
+    let mut values: Vec<f64> = simplex.iter().map(|vertex| get_finite_value(&f, vertex)).collect();
+
+    for _ in 0..max_iterations
+    {
+        let mut order: Vec<usize> = (0..=dimension).collect();
+        order.sort_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap());
+        simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+        values = order.iter().map(|&i| values[i]).collect();
+        if (values[dimension] - values[0]).abs() <= tolerance * (values[0].abs() + tolerance)
+        {
+            break;
+        }
+
+        let centroid = simplex[..dimension].iter().fold(Array1::<f64>::zeros(dimension), |sum, vertex| sum + vertex) / dimension as f64;
+        let worst = simplex[dimension].clone();
+        let reflected = &centroid + &((&centroid - &worst) * REFLECTION);
+        let reflected_value = get_finite_value(&f, &reflected);
+
+        if reflected_value < values[0]
+        {
+            let expanded = &centroid + &((&reflected - &centroid) * EXPANSION);
+            let expanded_value = get_finite_value(&f, &expanded);
+            if expanded_value < reflected_value
+            {
+                simplex[dimension] = expanded;
+                values[dimension] = expanded_value;
+            }
+            else
+            {
+                simplex[dimension] = reflected;
+                values[dimension] = reflected_value;
+            }
+        }
+        else if reflected_value < values[dimension - 1]
+        {
+            simplex[dimension] = reflected;
+            values[dimension] = reflected_value;
+        }
+        else
+        {
+            // Contract towards the better of the worst and the reflected point
+            let (target, target_value) = if reflected_value < values[dimension] { (reflected, reflected_value) } else { (worst, values[dimension]) };
+            let contracted = &centroid + &((&target - &centroid) * CONTRACTION);
+            let contracted_value = get_finite_value(&f, &contracted);
+            if contracted_value < target_value
+            {
+                simplex[dimension] = contracted;
+                values[dimension] = contracted_value;
+            }
+            else
+            {
+                for i in 1..=dimension
+                {
+                    simplex[i] = &simplex[0] + &((&simplex[i] - &simplex[0]) * SHRINKAGE);
+                    values[i] = get_finite_value(&f, &simplex[i]);
+                }
+            }
+        }
+    }
+
+    let best = (0..=dimension).min_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap()).unwrap();
+    if !values[best].is_finite()
+    {
+        return Err(String::from("Optimization did not find a finite function value!"));
+    }
+    Ok((simplex[best].clone(), values[best]))
+}
+
+// Maps NaN to infinity so that invalid regions are never preferred
+fn get_finite_value<F>(f: &F, point: &Array1<f64>) -> f64
+where F: Fn(&Array1<f64>) -> f64
+{
+    let value = f(point);
+    if value.is_nan() { f64::INFINITY } else { value }
+}
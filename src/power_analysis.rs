@@ -0,0 +1,255 @@
+use super::distributions::{get_chi_squared_ppf, get_f_ppf, get_noncentral_chi_squared_sf, get_noncentral_f_sf,
+                           get_noncentral_t_cdf, get_noncentral_t_sf, get_normal_cdf, get_normal_ppf, get_t_ppf};
+use super::hypothesis_testing::TestKind;
+
+const MAX_SAMPLE_SIZE: f64 = 1e9;
+const SMALLEST_VALUE: f64 = 1e-10;
+
+// Effect sizes follow Cohen (1988), with OneTailedLower tests expecting a negative effect
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerTest
+{
+    // d = (mean - hypothesized mean) / population standard deviation
+    ZTest(TestKind),
+    // Cohen's d, also for paired differences
+    OneSampleT(TestKind),
+    // Cohen's d, sample size per group
+    TwoSampleT(TestKind),
+    // Cohen's h, sample size per group
+    TwoProportions(TestKind),
+    // Cohen's w, total sample size
+    ChiSquared { degrees_of_freedom: f64 },
+    // Cohen's f, sample size per group
+    OneWayAnova { num_groups: usize },
+    // Correlation coefficient, total sample size
+    Correlation(TestKind),
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct PowerAnalysis
+{
+    pub effect_size: f64,
+    pub sample_size: f64,
+    pub alpha_level: f64,
+    pub power: f64,
+}
+
+#[allow(unused)]
+pub fn get_power(test: PowerTest, effect_size: f64, sample_size: f64, alpha_level: f64) -> Result<f64, String>
+{
+    check_probability(alpha_level, "Alpha level")?;
+    if sample_size < get_minimum_sample_size(test)
+    {
+        return Err(format!("Sample size must be at least {}!", get_minimum_sample_size(test)));
+    }
+
+    let power = match test
+    {
+        PowerTest::ZTest(test_kind) => get_normal_power(effect_size * sample_size.sqrt(), alpha_level, test_kind)?,
+        PowerTest::TwoProportions(test_kind) => get_normal_power(effect_size * (sample_size / 2.0).sqrt(), alpha_level, test_kind)?,
+        PowerTest::OneSampleT(test_kind) =>
+        {
+            get_t_power(effect_size * sample_size.sqrt(), sample_size - 1.0, alpha_level, test_kind)?
+        },
+        PowerTest::TwoSampleT(test_kind) =>
+        {
+            get_t_power(effect_size * (sample_size / 2.0).sqrt(), 2.0 * sample_size - 2.0, alpha_level, test_kind)?
+        },
+        PowerTest::ChiSquared { degrees_of_freedom } =>
+        {
+            let critical_value = get_chi_squared_ppf(1.0 - alpha_level, degrees_of_freedom)?;
+            get_noncentral_chi_squared_sf(critical_value, degrees_of_freedom, sample_size * effect_size.powi(2))?
+        },
+        PowerTest::OneWayAnova { num_groups } =>
+        {
+            let k = num_groups as f64;
+            let (numerator_degrees_of_freedom, denominator_degrees_of_freedom) = (k - 1.0, k * (sample_size - 1.0));
+            let critical_value = get_f_ppf(1.0 - alpha_level, numerator_degrees_of_freedom, denominator_degrees_of_freedom)?;
+            get_noncentral_f_sf(critical_value, numerator_degrees_of_freedom, denominator_degrees_of_freedom, k * sample_size * effect_size.powi(2))?
+        },
+        PowerTest::Correlation(test_kind) => get_correlation_power(effect_size, sample_size, alpha_level, test_kind)?,
+    };
+    Ok(power.clamp(0.0, 1.0))
+}
+
+// Exactly one of the four quantities must be None, it is solved for by bisection
+// since power increases with the effect size, the sample size and the alpha level
+#[allow(unused)]
+pub fn get_power_analysis(test: PowerTest,
+                          effect_size: Option<f64>,
+                          sample_size: Option<f64>,
+                          alpha_level: Option<f64>,
+                          power: Option<f64>) -> Result<PowerAnalysis, String>
+{
+    let num_missing = [effect_size, sample_size, alpha_level, power].iter().filter(|value| value.is_none()).count();
+    if num_missing != 1
+    {
+        return Err(String::from("Exactly one of effect size, sample size, alpha level and power must be missing!"));
+    }
+    if let Some(power) = power
+    {
+        check_probability(power, "Power")?;
+    }
+
+    match (effect_size, sample_size, alpha_level, power)
+    {
+        (Some(effect_size), Some(sample_size), Some(alpha_level), None) =>
+        {
+            Ok(PowerAnalysis { effect_size, sample_size, alpha_level, power: get_power(test, effect_size, sample_size, alpha_level)? })
+        },
+        (Some(effect_size), None, Some(alpha_level), Some(power)) =>
+        {
+            let minimum = get_minimum_sample_size(test);
+            let sample_size = solve_increasing(|n| get_power(test, effect_size, n, alpha_level), power, minimum, minimum * 2.0, MAX_SAMPLE_SIZE)?;
+            Ok(PowerAnalysis { effect_size, sample_size, alpha_level, power })
+        },
+        (None, Some(sample_size), Some(alpha_level), Some(power)) =>
+        {
+            let sign = if get_test_kind(test) == Some(TestKind::OneTailedLower) { -1.0 } else { 1.0 };
+            let maximum = match test
+            {
+                PowerTest::Correlation(_) => 1.0 - SMALLEST_VALUE,
+                PowerTest::TwoProportions(_) => std::f64::consts::PI,
+                _ => f64::INFINITY,
+            };
+            let magnitude = solve_increasing(|e| get_power(test, sign * e, sample_size, alpha_level), power, SMALLEST_VALUE, maximum.min(1.0), maximum)?;
+            Ok(PowerAnalysis { effect_size: sign * magnitude, sample_size, alpha_level, power })
+        },
+        (Some(effect_size), Some(sample_size), None, Some(power)) =>
+        {
+            let alpha_level = solve_increasing(|a| get_power(test, effect_size, sample_size, a), power, SMALLEST_VALUE, 0.5, 1.0 - SMALLEST_VALUE)?;
+            Ok(PowerAnalysis { effect_size, sample_size, alpha_level, power })
+        },
+        _ => unreachable!(),
+    }
+}
+
+// Effect size for the difference of two proportions
+#[allow(unused)]
+pub fn get_cohens_h(proportion_1: f64, proportion_2: f64) -> Result<f64, String>
+{
+    check_probability(proportion_1, "Proportion")?;
+    check_probability(proportion_2, "Proportion")?;
+    Ok(2.0 * proportion_1.sqrt().asin() - 2.0 * proportion_2.sqrt().asin())
+}
+
+fn get_normal_power(shift: f64, alpha_level: f64, test_kind: TestKind) -> Result<f64, String>
+{
+    Ok(match test_kind
+    {
+        TestKind::TwoTailed =>
+        {
+            let critical_value = get_normal_ppf(1.0 - alpha_level / 2.0)?;
+            get_normal_cdf(shift - critical_value) + get_normal_cdf(-shift - critical_value)
+        },
+        TestKind::OneTailedUpper => get_normal_cdf(shift - get_normal_ppf(1.0 - alpha_level)?),
+        TestKind::OneTailedLower => get_normal_cdf(-shift - get_normal_ppf(1.0 - alpha_level)?),
+    })
+}
+
+fn get_t_power(noncentrality: f64, degrees_of_freedom: f64, alpha_level: f64, test_kind: TestKind) -> Result<f64, String>
+{
+    Ok(match test_kind
+    {
+        TestKind::TwoTailed =>
+        {
+            let critical_value = get_t_ppf(1.0 - alpha_level / 2.0, degrees_of_freedom)?;
+            get_noncentral_t_sf(critical_value, degrees_of_freedom, noncentrality)?
+                + get_noncentral_t_cdf(-critical_value, degrees_of_freedom, noncentrality)?
+        },
+        TestKind::OneTailedUpper => get_noncentral_t_sf(get_t_ppf(1.0 - alpha_level, degrees_of_freedom)?, degrees_of_freedom, noncentrality)?,
+        TestKind::OneTailedLower => get_noncentral_t_cdf(-get_t_ppf(1.0 - alpha_level, degrees_of_freedom)?, degrees_of_freedom, noncentrality)?,
+    })
+}
+
+// Fisher z approximation with the bias correction r / (2 (n - 1)), as in R's pwr.r.test
+fn get_correlation_power(correlation: f64, sample_size: f64, alpha_level: f64, test_kind: TestKind) -> Result<f64, String>
+{
+    if correlation.abs() >= 1.0
+    {
+        return Err(String::from("Correlation must be between -1 and 1!"));
+    }
+    let degrees_of_freedom = sample_size - 2.0;
+    let tail_alpha = if test_kind == TestKind::TwoTailed { alpha_level / 2.0 } else { alpha_level };
+    let t_critical = get_t_ppf(1.0 - tail_alpha, degrees_of_freedom)?;
+    let critical_correlation = (t_critical.powi(2) / (t_critical.powi(2) + degrees_of_freedom)).sqrt();
+    let z_critical = critical_correlation.atanh();
+    let correlation = if test_kind == TestKind::OneTailedLower { -correlation } else { correlation };
+    let z = correlation.atanh() + correlation / (2.0 * (sample_size - 1.0));
+    let scale = (sample_size - 3.0).sqrt();
+
+    Ok(match test_kind
+    {
+        TestKind::TwoTailed => get_normal_cdf((z - z_critical) * scale) + get_normal_cdf((-z - z_critical) * scale),
+        _ => get_normal_cdf((z - z_critical) * scale),
+    })
+}
+
+fn get_minimum_sample_size(test: PowerTest) -> f64
+{
+    match test
+    {
+        PowerTest::ZTest(_) | PowerTest::TwoProportions(_) | PowerTest::ChiSquared { .. } => 1.0,
+        PowerTest::OneSampleT(_) | PowerTest::TwoSampleT(_) | PowerTest::OneWayAnova { .. } => 2.0,
+        PowerTest::Correlation(_) => 4.0,
+    }
+}
+
+fn get_test_kind(test: PowerTest) -> Option<TestKind>
+{
+    match test
+    {
+        PowerTest::ZTest(test_kind) | PowerTest::OneSampleT(test_kind) | PowerTest::TwoSampleT(test_kind)
+        | PowerTest::TwoProportions(test_kind) | PowerTest::Correlation(test_kind) => Some(test_kind),
+        _ => None,
+    }
+}
+
+fn check_probability(value: f64, name: &str) -> Result<(), String>
+{
+    if value <= 0.0 || value >= 1.0 || value.is_nan()
+    {
+        return Err(format!("{} must be between 0 and 1!", name));
+    }
+    Ok(())
+}
+
+// Finds x in [lower, maximum] with f(x) = target for an increasing f,
+// the upper end of the bracket grows from initial_upper by doubling
+fn solve_increasing<F>(f: F, target: f64, lower: f64, initial_upper: f64, maximum: f64) -> Result<f64, String>
+where F: Fn(f64) -> Result<f64, String>
+{
+    if f(lower)? >= target
+    {
+        return Err(String::from("Target power is already reached at the lower end of the search range!"));
+    }
+    let (mut lower, mut upper) = (lower, initial_upper.min(maximum));
+    while f(upper)? < target
+    {
+        if upper >= maximum
+        {
+            return Err(String::from("Target power cannot be reached within the search range!"));
+        }
+        lower = upper;
+        upper = (upper * 2.0).min(maximum);
+    }
+    for _ in 0..200
+    {
+        let middle = 0.5 * (lower + upper);
+        if f(middle)? < target
+        {
+            lower = middle;
+        }
+        else
+        {
+            upper = middle;
+        }
+        if upper - lower <= 1e-10 * upper.abs().max(1.0)
+        {
+            break;
+        }
+    }
+    Ok(0.5 * (lower + upper))
+}
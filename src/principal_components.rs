@@ -0,0 +1,154 @@
+use super::linear_algebra::{get_matrix_product, get_singular_value_decomposition};
+use ndarray::{Array1, Array2, Axis};
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComponentCriterion
+{
+    // Components whose variance exceeds the average, i.e. eigenvalues above one for scaled data
+    Kaiser,
+    // Horn (1965): components whose variance exceeds the mean variance of the same component
+    // in simulated uncorrelated normal data with the same size and column variances
+    ParallelAnalysis { num_simulations: usize, seed: u64 },
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct PrincipalComponentAnalysis
+{
+    center: bool,
+    scale: bool,
+}
+
+// Loadings hold one component per column with unit length, scores one component per column.
+// Explained variances are the eigenvalues of the covariance matrix (correlation matrix when scaled)
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct PrincipalComponents
+{
+    pub loadings: Array2<f64>,
+    pub scores: Array2<f64>,
+    pub explained_variance: Array1<f64>,
+    pub explained_variance_ratio: Array1<f64>,
+    pub cumulative_variance_ratio: Array1<f64>,
+    means: Array1<f64>,
+    scales: Array1<f64>,
+    standard_deviations: Array1<f64>,
+    model: PrincipalComponentAnalysis,
+}
+
+#[allow(unused)]
+impl PrincipalComponentAnalysis
+{
+    // R's prcomp centres but does not scale, scaling the columns to unit variance analyses the correlation matrix
+    pub fn new(center: bool, scale: bool) -> Self
+    {
+        PrincipalComponentAnalysis { center, scale }
+    }
+
+    // Rows are observations, the signs are chosen so that the largest loading of each component is positive
+    pub fn fit(&self, data: &Array2<f64>) -> Result<PrincipalComponents, String>
+    {
+        let (num_rows, num_columns) = data.dim();
+        if num_rows < 2 || num_columns == 0
+        {
+            return Err(String::from("Need at least two observations and one variable!"));
+        }
+        if data.iter().any(|x| x.is_nan())
+        {
+            return Err(String::from("There is at least one NaN in the data!"));
+        }
+
+        let means = if self.center { data.mean_axis(Axis(0)).unwrap() } else { Array1::zeros(num_columns) };
+        let scales = if self.scale
+        {
+            let scales = data.std_axis(Axis(0), 1.0);
+            if scales.iter().any(|&scale| scale == 0.0)
+            {
+                return Err(String::from("Cannot scale a constant column!"));
+            }
+            scales
+        }
+        else
+        {
+            Array1::ones(num_columns)
+        };
+
+        let standardized = (data - &means) / &scales;
+        let standard_deviations = standardized.std_axis(Axis(0), 1.0);
+        let decomposition = get_singular_value_decomposition(&standardized)?;
+        let mut loadings = decomposition.v;
+        for mut component in loadings.columns_mut()
+        {
+            let largest = component.iter().cloned().fold(0.0, |a: f64, b| if b.abs() > a.abs() { b } else { a });
+            if largest < 0.0
+            {
+                component.mapv_inplace(|x| -x);
+            }
+        }
+        let scores = get_matrix_product(&standardized, &loadings)?;
+        let explained_variance = decomposition.singular_values.mapv(|s| s * s / (num_rows as f64 - 1.0));
+        let total_variance = explained_variance.sum();
+        let explained_variance_ratio = explained_variance.mapv(|variance| variance / total_variance);
+        let mut cumulative = 0.0;
+        let cumulative_variance_ratio = explained_variance_ratio.mapv(|ratio| { cumulative += ratio; cumulative });
+        Ok(PrincipalComponents { loadings, scores, explained_variance, explained_variance_ratio, cumulative_variance_ratio, means, scales, standard_deviations, model: *self })
+    }
+}
+
+#[allow(unused)]
+impl PrincipalComponents
+{
+    // Scores of new observations with the centring and scaling of the fitted data
+    pub fn transform(&self, data: &Array2<f64>) -> Result<Array2<f64>, String>
+    {
+        if data.ncols() != self.means.len()
+        {
+            return Err(String::from("Data must have as many columns as the fitted data!"));
+        }
+        get_matrix_product(&((data - &self.means) / &self.scales), &self.loadings)
+    }
+
+    // Scores of the leading k components give the best rank k approximation of the data
+    pub fn inverse_transform(&self, scores: &Array2<f64>) -> Result<Array2<f64>, String>
+    {
+        let num_components = scores.ncols();
+        if num_components == 0 || num_components > self.loadings.ncols()
+        {
+            return Err(String::from("Number of score columns must be between 1 and the number of components!"));
+        }
+        let loadings = self.loadings.slice(ndarray::s![.., ..num_components]).t().to_owned();
+        Ok(get_matrix_product(scores, &loadings)? * &self.scales + &self.means)
+    }
+
+    pub fn get_num_components(&self, criterion: ComponentCriterion) -> Result<usize, String>
+    {
+        let thresholds = match criterion
+        {
+            ComponentCriterion::Kaiser => Array1::from_elem(self.explained_variance.len(), self.explained_variance.mean().unwrap()),
+            ComponentCriterion::ParallelAnalysis { num_simulations, seed } =>
+            {
+                if num_simulations == 0
+                {
+                    return Err(String::from("Need at least one simulation!"));
+                }
+                let rng = fastrand::Rng::with_seed(seed);
+                let (num_rows, num_columns) = self.scores.dim();
+                let mut mean_variances = Array1::<f64>::zeros(num_columns);
+                for _ in 0..num_simulations
+                {
+                    let simulated = Array2::from_shape_fn((num_rows, num_columns), |(_, j)| self.standard_deviations[j] * get_standard_normal(&rng));
+                    mean_variances += &self.model.fit(&simulated)?.explained_variance;
+                }
+                mean_variances / num_simulations as f64
+            },
+        };
+        Ok(self.explained_variance.iter().zip(thresholds.iter()).take_while(|(variance, threshold)| variance > threshold).count())
+    }
+}
+
+// Box-Muller transform
+fn get_standard_normal(rng: &fastrand::Rng) -> f64
+{
+    (-2.0 * (1.0 - rng.f64()).ln()).sqrt() * (2.0 * std::f64::consts::PI * rng.f64()).cos()
+}
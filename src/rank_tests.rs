@@ -0,0 +1,454 @@
+use super::distributions::{get_chi_squared_sf, get_ln_gamma, get_normal_cdf, get_normal_sf};
+use super::hypothesis_testing::{TestKind, TestResult};
+use super::multiple_testing::{get_adjusted_p_values, PAdjustMethod};
+use super::utilities::{check_vector_for_nans, get_ranks};
+use ndarray::{Array1, Array2};
+
+// Auto switches to the normal approximation beyond these sizes
+const MAX_EXACT_SAMPLE_SIZE: usize = 50;
+const MAX_EXACT_ARRANGEMENTS: f64 = 100_000.0;
+const STATISTIC_TOLERANCE: f64 = 1e-9;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RankTestMethod
+{
+    // Exact for small samples without ties, asymptotic otherwise
+    Auto,
+    Exact,
+    Asymptotic,
+}
+
+#[allow(unused)]
+#[derive(Debug)]
+pub struct DunnComparison
+{
+    pub group_1: usize,
+    pub group_2: usize,
+    pub z_statistic: f64,
+    pub p_value: f64,
+    pub adjusted_p_value: f64,
+    pub reject: bool,
+}
+
+// OneTailedUpper tests whether sample_1 tends to be larger than sample_2,
+// the effect size is the rank-biserial correlation
+#[allow(unused)]
+pub fn get_mann_whitney_u_test(sample_1: &Array1<f64>, sample_2: &Array1<f64>, test_kind: TestKind, method: RankTestMethod)
+-> Result<TestResult, String>
+{
+    let sample_1 = check_vector_for_nans(sample_1)?;
+    let sample_2 = check_vector_for_nans(sample_2)?;
+    if sample_1.is_empty() || sample_2.is_empty()
+    {
+        return Err(String::from("Vector cannot be empty"));
+    }
+    let (n_1, n_2) = (sample_1.len(), sample_2.len());
+    let combined: Array1<f64> = sample_1.iter().chain(sample_2.iter()).copied().collect();
+    let ranks = get_ranks(&combined)?;
+    let u_1 = ranks.iter().take(n_1).sum::<f64>() - (n_1 * (n_1 + 1)) as f64 / 2.0;
+    let tie_sum = get_tie_sum(&combined);
+
+    let p_value = if use_exact(method, tie_sum == 0.0, n_1 + n_2 <= MAX_EXACT_SAMPLE_SIZE)?
+    {
+        get_exact_p_value(&get_mann_whitney_distribution(n_1, n_2), u_1.round() as usize, test_kind)
+    }
+    else
+    {
+        let n = (n_1 + n_2) as f64;
+        let variance = (n_1 * n_2) as f64 / 12.0 * ((n + 1.0) - tie_sum / (n * (n - 1.0)));
+        get_normal_p_value(u_1 - (n_1 * n_2) as f64 / 2.0, variance, test_kind)?
+    };
+    let rank_biserial = 2.0 * u_1 / (n_1 * n_2) as f64 - 1.0;
+    Ok(TestResult::new("Mann-Whitney U", u_1, vec![], p_value).with_effect_size(rank_biserial))
+}
+
+// Zero differences are dropped, the effect size is the matched-pairs rank-biserial correlation
+#[allow(unused)]
+pub fn get_wilcoxon_signed_rank_test(sample: &Array1<f64>, hypothesized_median: f64, test_kind: TestKind, method: RankTestMethod)
+-> Result<TestResult, String>
+{
+    let sample = check_vector_for_nans(sample)?;
+    let differences: Array1<f64> = sample.iter().map(|x| x - hypothesized_median).filter(|&d| d != 0.0).collect();
+    if differences.is_empty()
+    {
+        return Err(String::from("All differences are zero!"));
+    }
+    let n = differences.len();
+    let absolute_differences = differences.mapv(f64::abs);
+    let ranks = get_ranks(&absolute_differences)?;
+    let w_plus: f64 = ranks.iter().zip(differences.iter()).filter(|(_, &d)| d > 0.0).map(|(r, _)| r).sum();
+    let rank_total = (n * (n + 1)) as f64 / 2.0;
+    let tie_sum = get_tie_sum(&absolute_differences);
+    let has_zeros = differences.len() < sample.len();
+
+    let p_value = if use_exact(method, tie_sum == 0.0 && !has_zeros, n <= MAX_EXACT_SAMPLE_SIZE)?
+    {
+        get_exact_p_value(&get_signed_rank_distribution(n), w_plus.round() as usize, test_kind)
+    }
+    else
+    {
+        let n = n as f64;
+        let variance = n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - tie_sum / 48.0;
+        get_normal_p_value(w_plus - rank_total / 2.0, variance, test_kind)?
+    };
+    let rank_biserial = (2.0 * w_plus - rank_total) / rank_total;
+    Ok(TestResult::new("Wilcoxon signed-rank", w_plus, vec![], p_value).with_effect_size(rank_biserial))
+}
+
+// Signed-rank test on the differences sample_1 - sample_2
+#[allow(unused)]
+pub fn get_paired_wilcoxon_signed_rank_test(sample_1: &Array1<f64>, sample_2: &Array1<f64>, test_kind: TestKind, method: RankTestMethod)
+-> Result<TestResult, String>
+{
+    if sample_1.len() != sample_2.len()
+    {
+        return Err(String::from("Vector lengths do not match!"));
+    }
+    get_wilcoxon_signed_rank_test(&(sample_1 - sample_2), 0.0, test_kind, method)
+}
+
+// The exact p-value enumerates all assignments of the (mid)ranks to the groups,
+// the effect size is epsilon squared
+#[allow(unused)]
+pub fn get_kruskal_wallis_test(groups: &[Array1<f64>], method: RankTestMethod) -> Result<TestResult, String>
+{
+    let (combined, sizes) = get_combined_groups(groups)?;
+    let ranks = get_ranks(&combined)?;
+    let n = combined.len() as f64;
+    let tie_correction = 1.0 - get_tie_sum(&combined) / (n.powi(3) - n);
+    if tie_correction <= 0.0
+    {
+        return Err(String::from("All observations are tied!"));
+    }
+
+    let rank_sums = get_group_rank_sums(&ranks, &sizes);
+    let observed_sum = get_scaled_squared_sum(&rank_sums, &sizes);
+    let statistic = (12.0 / (n * (n + 1.0)) * observed_sum - 3.0 * (n + 1.0)) / tie_correction;
+    let degrees_of_freedom = (groups.len() - 1) as f64;
+
+    let ln_arrangements = get_ln_gamma(n + 1.0) - sizes.iter().map(|&size| get_ln_gamma(size as f64 + 1.0)).sum::<f64>();
+    let p_value = if use_exact(method, true, ln_arrangements <= MAX_EXACT_ARRANGEMENTS.ln())?
+    {
+        if ln_arrangements > MAX_EXACT_ARRANGEMENTS.ln()
+        {
+            return Err(String::from("Too many arrangements for an exact p-value, use the asymptotic method!"));
+        }
+        let mut capacities = sizes.clone();
+        let mut rank_sums = vec![0.0; sizes.len()];
+        let (mut num_as_extreme, mut num_arrangements) = (0.0, 0.0);
+        assign_ranks(ranks.as_slice().unwrap(), 0, &mut capacities, &mut rank_sums, &mut |rank_sums|
+        {
+            num_arrangements += 1.0;
+            if is_as_extreme(get_scaled_squared_sum(rank_sums, &sizes), observed_sum)
+            {
+                num_as_extreme += 1.0;
+            }
+        });
+        num_as_extreme / num_arrangements
+    }
+    else
+    {
+        get_chi_squared_sf(statistic, degrees_of_freedom)?
+    };
+    let epsilon_squared = statistic / (n - 1.0);
+    Ok(TestResult::new("Kruskal-Wallis H", statistic, vec![degrees_of_freedom], p_value).with_effect_size(epsilon_squared))
+}
+
+// Pairwise comparisons of mean ranks after a Kruskal-Wallis test
+#[allow(unused)]
+pub fn get_dunn_test(groups: &[Array1<f64>], adjust_method: PAdjustMethod, alpha_level: f64) -> Result<Vec<DunnComparison>, String>
+{
+    let (combined, sizes) = get_combined_groups(groups)?;
+    let ranks = get_ranks(&combined)?;
+    let n = combined.len() as f64;
+    let variance = n * (n + 1.0) / 12.0 - get_tie_sum(&combined) / (12.0 * (n - 1.0));
+    let mean_ranks: Vec<f64> = get_group_rank_sums(&ranks, &sizes).iter().zip(&sizes).map(|(sum, &size)| sum / size as f64).collect();
+
+    let mut comparisons = Vec::new();
+    for i in 0..groups.len()
+    {
+        for j in i + 1..groups.len()
+        {
+            let standard_error = (variance * (1.0 / sizes[i] as f64 + 1.0 / sizes[j] as f64)).sqrt();
+            let z_statistic = (mean_ranks[i] - mean_ranks[j]) / standard_error;
+            let p_value = 2.0 * get_normal_sf(z_statistic.abs());
+            comparisons.push(DunnComparison { group_1: i, group_2: j, z_statistic, p_value, adjusted_p_value: p_value, reject: false });
+        }
+    }
+
+    let p_values: Array1<f64> = comparisons.iter().map(|comparison| comparison.p_value).collect();
+    let adjusted_p_values = get_adjusted_p_values(&p_values, adjust_method)?;
+    for (comparison, &adjusted_p_value) in comparisons.iter_mut().zip(adjusted_p_values.iter())
+    {
+        comparison.adjusted_p_value = adjusted_p_value;
+        comparison.reject = adjusted_p_value < alpha_level;
+    }
+    Ok(comparisons)
+}
+
+// Rows are blocks and columns are treatments, the effect size is Kendall's W
+#[allow(unused)]
+pub fn get_friedman_test(data: &Array2<f64>, method: RankTestMethod) -> Result<TestResult, String>
+{
+    let (num_blocks, num_treatments) = data.dim();
+    if num_blocks < 2 || num_treatments < 2
+    {
+        return Err(String::from("Friedman test needs at least two blocks and two treatments!"));
+    }
+    if data.iter().any(|x| x.is_nan())
+    {
+        return Err(String::from("Vector contains NaNs"));
+    }
+    let mut block_ranks = Vec::with_capacity(num_blocks);
+    let mut tie_sum = 0.0;
+    for block in data.rows()
+    {
+        let block = block.to_owned();
+        tie_sum += get_tie_sum(&block);
+        block_ranks.push(get_ranks(&block)?.to_vec());
+    }
+    let (n, k) = (num_blocks as f64, num_treatments as f64);
+    let tie_correction = 1.0 - tie_sum / (n * k * (k.powi(2) - 1.0));
+    if tie_correction <= 0.0
+    {
+        return Err(String::from("All observations within every block are tied!"));
+    }
+
+    let rank_sums: Vec<f64> = (0..num_treatments).map(|j| block_ranks.iter().map(|ranks| ranks[j]).sum()).collect();
+    let observed_sum: f64 = rank_sums.iter().map(|r| r.powi(2)).sum();
+    let statistic = (12.0 / (n * k * (k + 1.0)) * observed_sum - 3.0 * n * (k + 1.0)) / tie_correction;
+    let degrees_of_freedom = k - 1.0;
+
+    // Under the null hypothesis every ordering within a block is equally likely
+    let ln_arrangements = n * get_ln_gamma(k + 1.0);
+    let p_value = if use_exact(method, true, ln_arrangements <= MAX_EXACT_ARRANGEMENTS.ln())?
+    {
+        if ln_arrangements > MAX_EXACT_ARRANGEMENTS.ln()
+        {
+            return Err(String::from("Too many arrangements for an exact p-value, use the asymptotic method!"));
+        }
+        let orderings = get_orderings(num_treatments);
+        let mut rank_sums = vec![0.0; num_treatments];
+        let (mut num_as_extreme, mut num_arrangements) = (0.0, 0.0);
+        permute_blocks(&block_ranks, &orderings, 0, &mut rank_sums, &mut |rank_sums|
+        {
+            num_arrangements += 1.0;
+            if is_as_extreme(rank_sums.iter().map(|r| r.powi(2)).sum(), observed_sum)
+            {
+                num_as_extreme += 1.0;
+            }
+        });
+        num_as_extreme / num_arrangements
+    }
+    else
+    {
+        get_chi_squared_sf(statistic, degrees_of_freedom)?
+    };
+    let kendalls_w = statistic / (n * (k - 1.0));
+    Ok(TestResult::new("Friedman", statistic, vec![degrees_of_freedom], p_value).with_effect_size(kendalls_w))
+}
+
+fn use_exact(method: RankTestMethod, no_ties: bool, small_sample: bool) -> Result<bool, String>
+{
+    match method
+    {
+        RankTestMethod::Auto => Ok(no_ties && small_sample),
+        RankTestMethod::Asymptotic => Ok(false),
+        RankTestMethod::Exact if !no_ties => Err(String::from("Exact p-values need data without ties or zeros!")),
+        RankTestMethod::Exact => Ok(true),
+    }
+}
+
+// Sum of t^3 - t over groups of t tied values
+fn get_tie_sum(values: &Array1<f64>) -> f64
+{
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mut tie_sum = 0.0;
+    let mut start = 0;
+    while start < sorted.len()
+    {
+        let end = start + sorted[start..].iter().take_while(|&&x| x == sorted[start]).count();
+        let t = (end - start) as f64;
+        tie_sum += t.powi(3) - t;
+        start = end;
+    }
+    tie_sum
+}
+
+fn get_exact_p_value(distribution: &[f64], observed: usize, test_kind: TestKind) -> f64
+{
+    let upper: f64 = distribution.iter().skip(observed).sum();
+    let lower: f64 = distribution.iter().take(observed + 1).sum();
+    match test_kind
+    {
+        TestKind::OneTailedUpper => upper,
+        TestKind::OneTailedLower => lower,
+        TestKind::TwoTailed => (2.0 * upper.min(lower)).min(1.0),
+    }
+}
+
+// Normal approximation with a continuity correction of one half
+fn get_normal_p_value(deviation: f64, variance: f64, test_kind: TestKind) -> Result<f64, String>
+{
+    if variance <= 0.0
+    {
+        return Err(String::from("All observations are tied!"));
+    }
+    let standard_deviation = variance.sqrt();
+    Ok(match test_kind
+    {
+        TestKind::OneTailedUpper => get_normal_sf((deviation - 0.5) / standard_deviation),
+        TestKind::OneTailedLower => get_normal_cdf((deviation + 0.5) / standard_deviation),
+        TestKind::TwoTailed => (2.0 * get_normal_sf((deviation.abs() - 0.5).max(0.0) / standard_deviation)).min(1.0),
+    })
+}
+
+// Null distribution of U for sample sizes m and n from the recursion
+// p(u; m, n) = m / (m + n) * p(u - n; m - 1, n) + n / (m + n) * p(u; m, n - 1)
+fn get_mann_whitney_distribution(m: usize, n: usize) -> Vec<f64>
+{
+    let mut previous_rows: Vec<Vec<f64>> = (0..=n).map(|_| vec![1.0]).collect();
+    for i in 1..=m
+    {
+        let mut rows: Vec<Vec<f64>> = vec![vec![1.0]];
+        for j in 1..=n
+        {
+            let mut distribution = vec![0.0; i * j + 1];
+            let weight_i = i as f64 / (i + j) as f64;
+            for (u, probability) in previous_rows[j].iter().enumerate()
+            {
+                distribution[u + j] += weight_i * probability;
+            }
+            for (u, probability) in rows[j - 1].iter().enumerate()
+            {
+                distribution[u] += (1.0 - weight_i) * probability;
+            }
+            rows.push(distribution);
+        }
+        previous_rows = rows;
+    }
+    previous_rows.pop().unwrap()
+}
+
+// Each rank 1..n is positive with probability one half
+fn get_signed_rank_distribution(n: usize) -> Vec<f64>
+{
+    let mut distribution = vec![1.0];
+    for rank in 1..=n
+    {
+        let mut next = vec![0.0; distribution.len() + rank];
+        for (w, probability) in distribution.iter().enumerate()
+        {
+            next[w] += probability / 2.0;
+            next[w + rank] += probability / 2.0;
+        }
+        distribution = next;
+    }
+    distribution
+}
+
+fn get_combined_groups(groups: &[Array1<f64>]) -> Result<(Array1<f64>, Vec<usize>), String>
+{
+    if groups.len() < 2
+    {
+        return Err(String::from("Need at least two groups!"));
+    }
+    for group in groups
+    {
+        check_vector_for_nans(group)?;
+        if group.is_empty()
+        {
+            return Err(String::from("Vector cannot be empty"));
+        }
+    }
+    let combined = groups.iter().flat_map(|group| group.iter().copied()).collect();
+    Ok((combined, groups.iter().map(|group| group.len()).collect()))
+}
+
+fn get_group_rank_sums(ranks: &Array1<f64>, sizes: &[usize]) -> Vec<f64>
+{
+    let mut start = 0;
+    sizes.iter().map(|&size|
+    {
+        let sum = ranks.iter().skip(start).take(size).sum();
+        start += size;
+        sum
+    }).collect()
+}
+
+fn get_scaled_squared_sum(rank_sums: &[f64], sizes: &[usize]) -> f64
+{
+    rank_sums.iter().zip(sizes).map(|(r, &size)| r.powi(2) / size as f64).sum()
+}
+
+fn is_as_extreme(statistic: f64, observed: f64) -> bool
+{
+    statistic >= observed - STATISTIC_TOLERANCE * observed.abs().max(1.0)
+}
+
+// Puts every rank into each group that still has room, visiting each assignment once
+fn assign_ranks<F>(ranks: &[f64], index: usize, capacities: &mut [usize], rank_sums: &mut [f64], visit: &mut F)
+where F: FnMut(&[f64])
+{
+    if index == ranks.len()
+    {
+        visit(rank_sums);
+        return;
+    }
+    for group in 0..capacities.len()
+    {
+        if capacities[group] == 0
+        {
+            continue;
+        }
+        capacities[group] -= 1;
+        rank_sums[group] += ranks[index];
+        assign_ranks(ranks, index + 1, capacities, rank_sums, visit);
+        rank_sums[group] -= ranks[index];
+        capacities[group] += 1;
+    }
+}
+
+fn permute_blocks<F>(block_ranks: &[Vec<f64>], orderings: &[Vec<usize>], block: usize, rank_sums: &mut [f64], visit: &mut F)
+where F: FnMut(&[f64])
+{
+    if block == block_ranks.len()
+    {
+        visit(rank_sums);
+        return;
+    }
+    for ordering in orderings
+    {
+        for (treatment, &position) in ordering.iter().enumerate()
+        {
+            rank_sums[treatment] += block_ranks[block][position];
+        }
+        permute_blocks(block_ranks, orderings, block + 1, rank_sums, visit);
+        for (treatment, &position) in ordering.iter().enumerate()
+        {
+            rank_sums[treatment] -= block_ranks[block][position];
+        }
+    }
+}
+
+// All orderings of 0..k
+fn get_orderings(k: usize) -> Vec<Vec<usize>>
+{
+    if k == 0
+    {
+        return vec![vec![]];
+    }
+    let mut orderings = Vec::new();
+    for ordering in get_orderings(k - 1)
+    {
+        for position in 0..k
+        {
+            let mut extended = ordering.clone();
+            extended.insert(position, k - 1);
+            orderings.push(extended);
+        }
+    }
+    orderings
+}
@@ -0,0 +1,285 @@
+use super::linear_algebra::get_weighted_least_squares;
+use super::simple_linear_regression::{get_best_fitting_intercept, get_best_fitting_slope};
+use super::utilities::{check_vector_for_nans, get_median, get_median_absolute_deviation};
+use ndarray::{Array1, Array2};
+
+// Points whose standardized residual exceeds this are flagged by the median based estimators
+const OUTLIER_CUTOFF: f64 = 2.5;
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub enum MEstimator
+{
+    Huber(f64),
+    TukeyBisquare(f64),
+}
+
+#[allow(unused)]
+impl MEstimator
+{
+    // Tuning constants giving 95% efficiency for normal errors
+    pub fn huber() -> Self
+    {
+        MEstimator::Huber(1.345)
+    }
+
+    pub fn tukey_bisquare() -> Self
+    {
+        MEstimator::TukeyBisquare(4.685)
+    }
+
+    pub fn get_weight(&self, standardized_residual: f64) -> f64
+    {
+        let u = standardized_residual.abs();
+        match *self
+        {
+            MEstimator::Huber(k) => if u <= k { 1.0 } else { k / u },
+            MEstimator::TukeyBisquare(c) => if u <= c { (1.0 - (u / c).powi(2)).powi(2) } else { 0.0 },
+        }
+    }
+}
+
+#[allow(unused)]
+#[derive(Debug)]
+pub struct RobustFit
+{
+    pub slope: f64,
+    pub intercept: f64,
+    pub weights: Array1<f64>,
+    pub iterations: usize,
+}
+
+#[allow(unused)]
+#[derive(Debug)]
+pub struct RansacFit
+{
+    pub slope: f64,
+    pub intercept: f64,
+    pub inlier_mask: Array1<bool>,
+    pub num_trials: usize,
+}
+
+#[allow(unused)]
+pub fn get_theil_sen(independent_variable: &Array1<f64>, dependent_variable: &Array1<f64>) -> Result<RobustFit, String>
+{
+    check_inputs(independent_variable, dependent_variable)?;
+    let n = independent_variable.len();
+    let mut slopes = Vec::with_capacity(n * (n - 1) / 2);
+    for i in 0..n
+    {
+        for j in i + 1..n
+        {
+            if let Some(slope) = get_pairwise_slope(independent_variable, dependent_variable, i, j)
+            {
+                slopes.push(slope);
+            }
+        }
+    }
+    let slope = get_median(&Array1::from(slopes)).map_err(|_| String::from("All x values are identical!"))?;
+    get_median_based_fit(independent_variable, dependent_variable, slope)
+}
+
+#[allow(unused)]
+pub fn get_siegel_repeated_medians(independent_variable: &Array1<f64>, dependent_variable: &Array1<f64>) -> Result<RobustFit, String>
+{
+    check_inputs(independent_variable, dependent_variable)?;
+    let n = independent_variable.len();
+    let mut median_slopes = Vec::with_capacity(n);
+    for i in 0..n
+    {
+        let slopes: Array1<f64> = (0..n)
+            .filter(|&j| j != i)
+            .filter_map(|j| get_pairwise_slope(independent_variable, dependent_variable, i, j))
+            .collect();
+        if let Ok(median_slope) = get_median(&slopes)
+        {
+            median_slopes.push(median_slope);
+        }
+    }
+    let slope = get_median(&Array1::from(median_slopes)).map_err(|_| String::from("All x values are identical!"))?;
+    get_median_based_fit(independent_variable, dependent_variable, slope)
+}
+
+// Iteratively reweighted least squares with the residual scale re-estimated by the MAD in every step
+#[allow(unused)]
+pub fn get_m_estimate(independent_variable: &Array1<f64>,
+                      dependent_variable: &Array1<f64>,
+                      estimator: MEstimator,
+                      max_iterations: usize) -> Result<RobustFit, String>
+{
+    check_inputs(independent_variable, dependent_variable)?;
+    // Redescending estimators need a robust start to avoid local minima
+    let (mut intercept, mut slope) = match estimator
+    {
+        MEstimator::Huber(_) =>
+        {
+            (get_best_fitting_intercept(independent_variable, dependent_variable)?,
+             get_best_fitting_slope(independent_variable, dependent_variable)?)
+        },
+        MEstimator::TukeyBisquare(_) =>
+        {
+            let start = get_least_absolute_deviations(independent_variable, dependent_variable, max_iterations)?;
+            (start.intercept, start.slope)
+        },
+    };
+
+    let design_matrix = get_design_matrix(independent_variable);
+    let mut weights = Array1::<f64>::ones(independent_variable.len());
+    for iteration in 1..=max_iterations
+    {
+        let residuals = get_residuals(independent_variable, dependent_variable, slope, intercept);
+        let scale = get_median_absolute_deviation(&residuals)?;
+        if scale == 0.0
+        {
+            return Ok(RobustFit { slope, intercept, weights, iterations: iteration });
+        }
+        weights = residuals.mapv(|r| estimator.get_weight(r / scale));
+
+        let coefficients = get_weighted_least_squares(&design_matrix, dependent_variable, &weights)?;
+        let change = (coefficients[0] - intercept).abs().max((coefficients[1] - slope).abs());
+        intercept = coefficients[0];
+        slope = coefficients[1];
+        if change < 1e-10 * (1.0 + slope.abs().max(intercept.abs()))
+        {
+            return Ok(RobustFit { slope, intercept, weights, iterations: iteration });
+        }
+    }
+    Ok(RobustFit { slope, intercept, weights, iterations: max_iterations })
+}
+
+// L1 regression by IRLS with weights 1 / |r|
+#[allow(unused)]
+pub fn get_least_absolute_deviations(independent_variable: &Array1<f64>, dependent_variable: &Array1<f64>, max_iterations: usize)
+-> Result<RobustFit, String>
+{
+    check_inputs(independent_variable, dependent_variable)?;
+    let design_matrix = get_design_matrix(independent_variable);
+    let mut intercept = get_best_fitting_intercept(independent_variable, dependent_variable)?;
+    let mut slope = get_best_fitting_slope(independent_variable, dependent_variable)?;
+    let mut weights = Array1::<f64>::ones(independent_variable.len());
+
+    for iteration in 1..=max_iterations
+    {
+        let residuals = get_residuals(independent_variable, dependent_variable, slope, intercept);
+        weights = residuals.mapv(|r| 1.0 / r.abs().max(1e-8));
+
+        let coefficients = get_weighted_least_squares(&design_matrix, dependent_variable, &weights)?;
+        let change = (coefficients[0] - intercept).abs().max((coefficients[1] - slope).abs());
+        intercept = coefficients[0];
+        slope = coefficients[1];
+        if change < 1e-10 * (1.0 + slope.abs().max(intercept.abs()))
+        {
+            return Ok(RobustFit { slope, intercept, weights: normalize_weights(&weights), iterations: iteration });
+        }
+    }
+    Ok(RobustFit { slope, intercept, weights: normalize_weights(&weights), iterations: max_iterations })
+}
+
+// The residual threshold defaults to the MAD of the dependent variable
+#[allow(unused)]
+pub fn get_ransac(independent_variable: &Array1<f64>,
+                  dependent_variable: &Array1<f64>,
+                  residual_threshold: Option<f64>,
+                  max_trials: usize,
+                  seed: u64) -> Result<RansacFit, String>
+{
+    check_inputs(independent_variable, dependent_variable)?;
+    let residual_threshold = match residual_threshold
+    {
+        Some(threshold) => threshold,
+        None => get_median_absolute_deviation(dependent_variable)? / 1.4826,
+    };
+    let n = independent_variable.len();
+    let rng = fastrand::Rng::with_seed(seed);
+
+    let mut best_mask: Option<Array1<bool>> = None;
+    let mut best_count = 0;
+    let mut best_sse = f64::INFINITY;
+    for _ in 0..max_trials
+    {
+        let i = rng.usize(0..n);
+        let j = (i + 1 + rng.usize(0..n - 1)) % n;
+        let slope = match get_pairwise_slope(independent_variable, dependent_variable, i, j)
+        {
+            Some(slope) => slope,
+            None => continue,
+        };
+        let intercept = dependent_variable[i] - slope * independent_variable[i];
+        let residuals = get_residuals(independent_variable, dependent_variable, slope, intercept);
+        let mask = residuals.mapv(|r| r.abs() <= residual_threshold);
+        let count = mask.iter().filter(|&&inlier| inlier).count();
+        let sse: f64 = residuals.iter().zip(&mask).filter(|(_, &inlier)| inlier).map(|(r, _)| r * r).sum();
+        if count > best_count || (count == best_count && sse < best_sse)
+        {
+            best_count = count;
+            best_sse = sse;
+            best_mask = Some(mask);
+        }
+    }
+
+    let inlier_mask = best_mask.ok_or_else(|| String::from("RANSAC could not find a valid model!"))?;
+    let inlier_x: Array1<f64> = independent_variable.iter().zip(&inlier_mask).filter(|(_, &inlier)| inlier).map(|(&x, _)| x).collect();
+    let inlier_y: Array1<f64> = dependent_variable.iter().zip(&inlier_mask).filter(|(_, &inlier)| inlier).map(|(&y, _)| y).collect();
+    Ok
+    (
+        RansacFit
+        {
+            slope: get_best_fitting_slope(&inlier_x, &inlier_y)?,
+            intercept: get_best_fitting_intercept(&inlier_x, &inlier_y)?,
+            inlier_mask,
+            num_trials: max_trials,
+        }
+    )
+}
+
+fn check_inputs(independent_variable: &Array1<f64>, dependent_variable: &Array1<f64>) -> Result<(), String>
+{
+    check_vector_for_nans(independent_variable)?;
+    check_vector_for_nans(dependent_variable)?;
+    if independent_variable.len() != dependent_variable.len()
+    {
+        return Err(String::from("Vector lengths do not match!"));
+    }
+    if independent_variable.len() < 3
+    {
+        return Err(String::from("Need at least three observations!"));
+    }
+    Ok(())
+}
+
+fn get_pairwise_slope(x: &Array1<f64>, y: &Array1<f64>, i: usize, j: usize) -> Option<f64>
+{
+    if x[i] == x[j]
+    {
+        None
+    }
+    else
+    {
+        Some((y[j] - y[i]) / (x[j] - x[i]))
+    }
+}
+
+fn get_residuals(x: &Array1<f64>, y: &Array1<f64>, slope: f64, intercept: f64) -> Array1<f64>
+{
+    y - &x.mapv(|x_i| slope * x_i + intercept)
+}
+
+fn get_design_matrix(x: &Array1<f64>) -> Array2<f64>
+{
+    super::linear_algebra::add_intercept_column(&x.view().insert_axis(ndarray::Axis(1)).to_owned())
+}
+
+fn get_median_based_fit(x: &Array1<f64>, y: &Array1<f64>, slope: f64) -> Result<RobustFit, String>
+{
+    let intercept = get_median(&x.iter().zip(y).map(|(x_i, y_i)| y_i - slope * x_i).collect())?;
+    let residuals = get_residuals(x, y, slope, intercept);
+    let scale = get_median_absolute_deviation(&residuals)?;
+    let weights = residuals.mapv(|r| if scale == 0.0 || (r / scale).abs() <= OUTLIER_CUTOFF { 1.0 } else { 0.0 });
+    Ok(RobustFit { slope, intercept, weights, iterations: 1 })
+}
+
+fn normalize_weights(weights: &Array1<f64>) -> Array1<f64>
+{
+    let max = weights.iter().cloned().fold(0.0, f64::max);
+    weights / max
+}
@@ -71,4 +71,51 @@ pub fn get_best_fitting_slope(independent_variable: &ndarray::Array1<f64>, depen
         denominator_sum += x_dev_denominator;
     }
     Ok (numerator_sum / denominator_sum)
+}
+
+#[allow(unused)]
+pub fn get_weighted_best_fitting_slope(independent_variable: &ndarray::Array1<f64>, dependent_variable: &ndarray::Array1<f64>, weights: &ndarray::Array1<f64>) -> Result<f64, String>
+{
+    check_weights(independent_variable, dependent_variable, weights)?;
+    let weighted_mean_independent_variable = get_weighted_mean(independent_variable, weights)?;
+    let weighted_mean_dependent_variable = get_weighted_mean(dependent_variable, weights)?;
+    let mut numerator_sum = 0.0;
+    let mut denominator_sum = 0.0;
+
+    for ((x_i, y_i), w_i) in independent_variable.iter().zip(dependent_variable).zip(weights)
+    {
+        let x_diff_to_mean = x_i - weighted_mean_independent_variable;
+        numerator_sum += w_i * x_diff_to_mean * (y_i - weighted_mean_dependent_variable);
+        denominator_sum += w_i * x_diff_to_mean.powi(2);
+    }
+    Ok (numerator_sum / denominator_sum)
+}
+
+#[allow(unused)]
+pub fn get_weighted_best_fitting_intercept(independent_variable: &ndarray::Array1<f64>, dependent_variable: &ndarray::Array1<f64>, weights: &ndarray::Array1<f64>) -> Result<f64, String>
+{
+    check_weights(independent_variable, dependent_variable, weights)?;
+    Ok (get_weighted_mean(dependent_variable, weights)?
+        - get_weighted_best_fitting_slope(independent_variable, dependent_variable, weights)?
+        * get_weighted_mean(independent_variable, weights)?)
+}
+
+fn check_weights(independent_variable: &ndarray::Array1<f64>, dependent_variable: &ndarray::Array1<f64>, weights: &ndarray::Array1<f64>) -> Result<(), String>
+{
+    if independent_variable.len() != dependent_variable.len() || independent_variable.len() != weights.len()
+    {
+        return Err(String::from("Variables and weights must have the same length!"));
+    }
+    let weights = check_vector_for_nans(weights)?;
+    if weights.iter().any(|&w| w < 0.0) || weights.sum() <= 0.0
+    {
+        return Err(String::from("Weights must not be negative and must not all be zero!"));
+    }
+    Ok(())
+}
+
+fn get_weighted_mean(input_vector: &ndarray::Array1<f64>, weights: &ndarray::Array1<f64>) -> Result<f64, String>
+{
+    let input_vector = check_vector_for_nans(input_vector)?;
+    Ok(input_vector.iter().zip(weights).map(|(x, w)| x * w).sum::<f64>() / weights.sum())
 }
\ No newline at end of file
@@ -0,0 +1,374 @@
+use super::optimization::get_nelder_mead_minimum;
+use super::time_series::{Forecast, TimeSeries};
+use super::utilities::{get_mean, get_sse};
+use ndarray::{s, Array1};
+
+const PARAMETER_BOUNDS: (f64, f64) = (1e-4, 0.9999);
+const DAMPING_BOUNDS: (f64, f64) = (0.8, 0.98);
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrendKind
+{
+    None,
+    Additive,
+    DampedAdditive,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeasonalKind
+{
+    None,
+    Additive,
+    Multiplicative,
+}
+
+// alpha smooths the level, beta the trend, gamma the seasonal component and phi damps the trend
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothingParameters
+{
+    pub alpha: f64,
+    pub beta: Option<f64>,
+    pub gamma: Option<f64>,
+    pub phi: Option<f64>,
+}
+
+// Simple exponential smoothing, Holt's linear trend and Holt-Winters in one model
+#[allow(unused)]
+#[derive(Debug, Clone, Copy)]
+pub struct ExponentialSmoothing
+{
+    trend_kind: TrendKind,
+    seasonal_kind: SeasonalKind,
+    period: usize,
+}
+
+// States after the last observation, seasonal holds the factors for the next period steps ahead.
+// Fitted values are one-step-ahead predictions for the last fitted_values.len() observations
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct ExponentialSmoothingFit
+{
+    pub parameters: SmoothingParameters,
+    pub level: f64,
+    pub trend: f64,
+    pub seasonal: Array1<f64>,
+    pub fitted_values: Array1<f64>,
+    pub residuals: Array1<f64>,
+    pub sse: f64,
+    model: ExponentialSmoothing,
+}
+
+struct FilterOutput
+{
+    fitted_values: Vec<f64>,
+    level: f64,
+    trend: f64,
+    seasonal: Vec<f64>,
+}
+
+#[allow(unused)]
+impl ExponentialSmoothing
+{
+    // period is the season length, it is ignored without a seasonal component
+    pub fn new(trend_kind: TrendKind, seasonal_kind: SeasonalKind, period: usize) -> Result<Self, String>
+    {
+        if seasonal_kind != SeasonalKind::None && period < 2
+        {
+            return Err(String::from("Seasonal period must be at least 2!"));
+        }
+        let period = if seasonal_kind == SeasonalKind::None { 1 } else { period };
+        Ok(ExponentialSmoothing { trend_kind, seasonal_kind, period })
+    }
+
+    // Chooses the smoothing parameters that minimize the SSE of the one-step-ahead predictions
+    pub fn fit(&self, series: &TimeSeries) -> Result<ExponentialSmoothingFit, String>
+    {
+        self.check_series(series)?;
+        let bounds = self.get_parameter_bounds();
+        let initial_values = [0.3, 0.1, 0.1, 0.9];
+        let mut initial_point = Array1::<f64>::zeros(bounds.len());
+        for (i, &(lower, upper)) in bounds.iter().enumerate()
+        {
+            let start = if bounds.len() == i + 1 && self.trend_kind == TrendKind::DampedAdditive { initial_values[3] } else { initial_values[i] };
+            let fraction = (start - lower) / (upper - lower);
+            initial_point[i] = (fraction / (1.0 - fraction)).ln();
+        }
+
+        let objective = |point: &Array1<f64>|
+        {
+            let parameters = self.get_parameters(point, &bounds);
+            match self.filter(series.get_values(), &parameters)
+            {
+                Ok(output) => get_sum_of_squared_errors(series.get_values(), &output.fitted_values),
+                Err(_) => f64::INFINITY,
+            }
+        };
+        let (point, _) = get_nelder_mead_minimum(objective, &initial_point, 1.0, 1e-10, 2000)?;
+        // Restarting around the first solution guards against a collapsed simplex
+        let (point, _) = get_nelder_mead_minimum(objective, &point, 0.5, 1e-12, 2000)?;
+        self.fit_with_parameters(series, self.get_parameters(&point, &bounds))
+    }
+
+    pub fn fit_with_parameters(&self, series: &TimeSeries, parameters: SmoothingParameters) -> Result<ExponentialSmoothingFit, String>
+    {
+        self.check_series(series)?;
+        let output = self.filter(series.get_values(), &parameters)?;
+        let n = series.len();
+        let observed = series.get_values().slice(s![n - output.fitted_values.len()..]).to_owned();
+        let seasonal = (1..=self.period).map(|h| output.seasonal[(n - 1 + h) % self.period]).collect();
+        let fitted_values = Array1::from(output.fitted_values);
+        let sse = get_sse(&fitted_values, &observed)?;
+        Ok(ExponentialSmoothingFit
+        {
+            parameters,
+            level: output.level,
+            trend: output.trend,
+            seasonal,
+            residuals: &observed - &fitted_values,
+            fitted_values,
+            sse,
+            model: *self,
+        })
+    }
+
+    fn filter(&self, values: &Array1<f64>, parameters: &SmoothingParameters) -> Result<FilterOutput, String>
+    {
+        let alpha = parameters.alpha;
+        let beta = if self.trend_kind == TrendKind::None { 0.0 } else { parameters.beta.ok_or("Trend models need beta!")? };
+        let gamma = if self.seasonal_kind == SeasonalKind::None { 0.0 } else { parameters.gamma.ok_or("Seasonal models need gamma!")? };
+        let phi = if self.trend_kind == TrendKind::DampedAdditive { parameters.phi.ok_or("Damped trend models need phi!")? } else { 1.0 };
+        for &value in [alpha, beta, gamma].iter()
+        {
+            if !(0.0..=1.0).contains(&value)
+            {
+                return Err(String::from("Smoothing parameters must be between 0 and 1!"));
+            }
+        }
+
+        let (mut level, mut trend, mut seasonal, start) = self.get_initial_states(values)?;
+        let multiplicative = self.seasonal_kind == SeasonalKind::Multiplicative;
+        let mut fitted_values = Vec::with_capacity(values.len() - start);
+        for (t, &value) in values.iter().enumerate().skip(start)
+        {
+            let phase = t % self.period;
+            let base = level + phi * trend;
+            let season = seasonal[phase];
+            let new_level = if multiplicative
+            {
+                fitted_values.push(base * season);
+                alpha * value / season + (1.0 - alpha) * base
+            }
+            else
+            {
+                fitted_values.push(base + season);
+                alpha * (value - season) + (1.0 - alpha) * base
+            };
+            trend = beta * (new_level - level) + (1.0 - beta) * phi * trend;
+            level = new_level;
+            seasonal[phase] = if multiplicative
+            {
+                gamma * value / level + (1.0 - gamma) * season
+            }
+            else
+            {
+                gamma * (value - level) + (1.0 - gamma) * season
+            };
+        }
+        Ok(FilterOutput { fitted_values, level, trend, seasonal })
+    }
+
+    // Level and trend after the first observation(s) and, for seasonal models, the first season
+    // with its linear trend removed, returns the index of the first predicted observation
+    fn get_initial_states(&self, values: &Array1<f64>) -> Result<(f64, f64, Vec<f64>, usize), String>
+    {
+        let has_trend = self.trend_kind != TrendKind::None;
+        if self.seasonal_kind == SeasonalKind::None
+        {
+            return Ok(if has_trend { (values[1], values[1] - values[0], vec![0.0], 2) } else { (values[0], 0.0, vec![0.0], 1) });
+        }
+        let m = self.period;
+        let first_mean = get_mean(&values.slice(s![..m]).to_owned())?;
+        let second_mean = get_mean(&values.slice(s![m..2 * m]).to_owned())?;
+        let trend = if has_trend { (second_mean - first_mean) / m as f64 } else { 0.0 };
+        let center = (m as f64 - 1.0) / 2.0;
+        let seasonal = (0..m).map(|i|
+        {
+            let trend_value = first_mean + (i as f64 - center) * trend;
+            if self.seasonal_kind == SeasonalKind::Multiplicative { values[i] / trend_value } else { values[i] - trend_value }
+        }).collect();
+        Ok((first_mean + center * trend, trend, seasonal, m))
+    }
+
+    fn get_parameter_bounds(&self) -> Vec<(f64, f64)>
+    {
+        let mut bounds = vec![PARAMETER_BOUNDS];
+        if self.trend_kind != TrendKind::None
+        {
+            bounds.push(PARAMETER_BOUNDS);
+        }
+        if self.seasonal_kind != SeasonalKind::None
+        {
+            bounds.push(PARAMETER_BOUNDS);
+        }
+        if self.trend_kind == TrendKind::DampedAdditive
+        {
+            bounds.push(DAMPING_BOUNDS);
+        }
+        bounds
+    }
+
+    // The optimizer works on unbounded values that are mapped into the bounds by a logistic function
+    fn get_parameters(&self, point: &Array1<f64>, bounds: &[(f64, f64)]) -> SmoothingParameters
+    {
+        let mut values = point.iter().zip(bounds).map(|(&x, &(lower, upper))| lower + (upper - lower) / (1.0 + (-x).exp()));
+        let alpha = values.next().unwrap();
+        let beta = if self.trend_kind != TrendKind::None { values.next() } else { None };
+        let gamma = if self.seasonal_kind != SeasonalKind::None { values.next() } else { None };
+        let phi = if self.trend_kind == TrendKind::DampedAdditive { values.next() } else { None };
+        SmoothingParameters { alpha, beta, gamma, phi }
+    }
+
+    fn check_series(&self, series: &TimeSeries) -> Result<(), String>
+    {
+        let minimum = if self.seasonal_kind == SeasonalKind::None { 3 } else { 2 * self.period + 1 };
+        if series.len() < minimum
+        {
+            return Err(format!("Need at least {} observations!", minimum));
+        }
+        if self.seasonal_kind == SeasonalKind::Multiplicative && series.get_values().iter().any(|&value| value <= 0.0)
+        {
+            return Err(String::from("Multiplicative seasonality needs positive values!"));
+        }
+        Ok(())
+    }
+}
+
+#[allow(unused)]
+impl ExponentialSmoothingFit
+{
+    // Forecast variance sigma^2 (1 + c_1^2 + ... + c_{h-1}^2) of the additive error models (Hyndman et al., 2008),
+    // used as an approximation under multiplicative seasonality
+    pub fn get_forecast(&self, horizon: usize, confidence_level: f64) -> Result<Forecast, String>
+    {
+        if horizon == 0
+        {
+            return Err(String::from("Horizon must be at least 1!"));
+        }
+        let num_parameters = 1 + self.parameters.beta.is_some() as usize + self.parameters.gamma.is_some() as usize + self.parameters.phi.is_some() as usize;
+        if self.residuals.len() <= num_parameters
+        {
+            return Err(String::from("Too few fitted observations for prediction intervals!"));
+        }
+        let variance = self.sse / (self.residuals.len() - num_parameters) as f64;
+        let alpha = self.parameters.alpha;
+        let beta = self.parameters.beta.unwrap_or(0.0);
+        let gamma = self.parameters.gamma.unwrap_or(0.0);
+        let phi = self.parameters.phi.unwrap_or(1.0);
+        let period = self.model.period;
+
+        let mut mean = Array1::<f64>::zeros(horizon);
+        let mut standard_errors = Array1::<f64>::zeros(horizon);
+        let mut damped_sum = 0.0;
+        let mut sum_of_squared_coefficients = 0.0;
+        for h in 1..=horizon
+        {
+            // Coefficient c_{h-1} uses phi + ... + phi^(h-1), before the sum moves on to h
+            if h > 1
+            {
+                let seasonal_term = if self.model.seasonal_kind != SeasonalKind::None && (h - 1) % period == 0 { gamma * (1.0 - alpha) } else { 0.0 };
+                sum_of_squared_coefficients += (alpha * (1.0 + beta * damped_sum) + seasonal_term).powi(2);
+            }
+            damped_sum += phi.powi(h as i32);
+            let base = self.level + damped_sum * self.trend;
+            let season = self.seasonal[(h - 1) % period];
+            mean[h - 1] = match self.model.seasonal_kind
+            {
+                SeasonalKind::Multiplicative => base * season,
+                _ => base + season,
+            };
+            standard_errors[h - 1] = (variance * (1.0 + sum_of_squared_coefficients)).sqrt();
+        }
+        Forecast::new(mean, standard_errors, confidence_level)
+    }
+}
+
+// Trailing mean of the last window observations, labelled with the index of the newest one
+#[allow(unused)]
+pub fn get_simple_moving_average(series: &TimeSeries, window: usize) -> Result<TimeSeries, String>
+{
+    get_weighted_moving_average(series, &Array1::from_elem(window, 1.0))
+}
+
+// Trailing weighted mean, the last weight belongs to the newest observation, weights are normalized
+#[allow(unused)]
+pub fn get_weighted_moving_average(series: &TimeSeries, weights: &Array1<f64>) -> Result<TimeSeries, String>
+{
+    let window = weights.len();
+    check_window(series, window)?;
+    let total_weight = weights.sum();
+    if total_weight <= 0.0 || weights.iter().any(|&weight| weight < 0.0)
+    {
+        return Err(String::from("Weights must be non-negative with a positive sum!"));
+    }
+    let values = series.get_values();
+    let averages: Array1<f64> = (window - 1..series.len())
+        .map(|t| (0..window).map(|j| weights[j] * values[t + 1 - window + j]).sum::<f64>() / total_weight)
+        .collect();
+    TimeSeries::new(averages, series.get_index().slice(s![window - 1..]).to_owned())
+}
+
+// Symmetric mean labelled with the middle observation, an even window uses the 2 x window average
+// that gives half weight to both ends, as in classical decomposition
+#[allow(unused)]
+pub fn get_centered_moving_average(series: &TimeSeries, window: usize) -> Result<TimeSeries, String>
+{
+    let weights = if window % 2 == 1
+    {
+        Array1::from_elem(window, 1.0)
+    }
+    else
+    {
+        let mut weights = Array1::from_elem(window + 1, 1.0);
+        weights[0] = 0.5;
+        weights[window] = 0.5;
+        weights
+    };
+    let averages = get_weighted_moving_average(series, &weights)?;
+    let half_width = weights.len() / 2;
+    TimeSeries::new(averages.get_values().clone(), series.get_index().slice(s![half_width..series.len() - half_width]).to_owned())
+}
+
+// s_0 = x_0 and s_t = alpha x_t + (1 - alpha) s_{t-1}
+#[allow(unused)]
+pub fn get_ewma(series: &TimeSeries, alpha: f64) -> Result<TimeSeries, String>
+{
+    if alpha <= 0.0 || alpha > 1.0
+    {
+        return Err(String::from("Alpha must be in (0, 1]!"));
+    }
+    let mut smoothed = series.get_values().clone();
+    for t in 1..smoothed.len()
+    {
+        smoothed[t] = alpha * smoothed[t] + (1.0 - alpha) * smoothed[t - 1];
+    }
+    TimeSeries::new(smoothed, series.get_index().clone())
+}
+
+fn check_window(series: &TimeSeries, window: usize) -> Result<(), String>
+{
+    if window == 0 || window > series.len()
+    {
+        return Err(String::from("Window must be between 1 and the series length!"));
+    }
+    Ok(())
+}
+
+fn get_sum_of_squared_errors(observed: &Array1<f64>, fitted_values: &[f64]) -> f64
+{
+    let start = observed.len() - fitted_values.len();
+    let sse: f64 = fitted_values.iter().zip(observed.iter().skip(start)).map(|(fitted, value)| (value - fitted).powi(2)).sum();
+    if sse.is_finite() { sse } else { f64::INFINITY }
+}
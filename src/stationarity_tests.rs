@@ -0,0 +1,323 @@
+use super::distributions::get_normal_cdf;
+use super::hypothesis_testing::TestResult;
+use super::linear_algebra::{get_inverse, get_least_squares, get_matrix_product, get_matrix_vector_product};
+use super::time_series::{InformationCriterion, TimeSeries};
+use ndarray::{Array1, Array2};
+
+const SIGNIFICANCE_LEVELS: [f64; 3] = [0.01, 0.05, 0.1];
+
+// MacKinnon (2010) response surfaces b_inf + b_1 / T + b_2 / T^2 + b_3 / T^3 for the ADF statistic at 1, 5 and 10 %
+const ADF_CONSTANT_COEFFICIENTS: [[f64; 4]; 3] = [[-3.43035, -6.5393, -16.786, -79.433],
+                                                   [-2.86154, -2.8903, -4.234, -40.040],
+                                                   [-2.56677, -1.5384, -2.809, 0.0]];
+const ADF_TREND_COEFFICIENTS: [[f64; 4]; 3] = [[-3.95877, -9.0531, -28.428, -134.155],
+                                                [-3.41049, -4.3904, -9.036, -45.374],
+                                                [-3.12705, -2.5856, -3.925, -22.380]];
+
+// MacKinnon (1994) normal approximations Phi(c_0 + c_1 tau + ...) of the Dickey-Fuller distribution,
+// small p-value coefficients apply up to tau_star, statistics outside [tau_min, tau_max] get 0 or 1
+const MACKINNON_CONSTANT_SMALL_P: [f64; 3] = [2.1659, 1.4412, 0.038269];
+const MACKINNON_CONSTANT_LARGE_P: [f64; 4] = [1.7339, 0.93202, -0.12745, -0.010368];
+const MACKINNON_CONSTANT_BOUNDS: [f64; 3] = [-18.83, -1.61, 2.74];
+const MACKINNON_TREND_SMALL_P: [f64; 3] = [3.2512, 1.6047, 0.049588];
+const MACKINNON_TREND_LARGE_P: [f64; 4] = [2.5261, 0.61654, -0.37956, -0.060285];
+const MACKINNON_TREND_BOUNDS: [f64; 3] = [-16.18, -2.89, 0.7];
+
+// Kwiatkowski et al. (1992), Table 1
+const KPSS_LEVEL_CRITICAL_VALUES: [f64; 3] = [0.739, 0.463, 0.347];
+const KPSS_TREND_CRITICAL_VALUES: [f64; 3] = [0.216, 0.146, 0.119];
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeterministicTerms
+{
+    Constant,
+    ConstantAndTrend,
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StationarityTest
+{
+    // Null hypothesis: unit root
+    AugmentedDickeyFuller,
+    // Null hypothesis: stationarity
+    Kpss,
+    // Null hypothesis: unit root
+    PhillipsPerron,
+}
+
+// Critical values are pairs of significance level and critical value. ADF and Phillips-Perron p-values
+// follow MacKinnon (1994), KPSS p-values are interpolated between the tabulated levels and clamped to [0.01, 0.1]
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct StationarityTestResult
+{
+    pub test_result: TestResult,
+    pub lags: usize,
+    pub critical_values: Vec<(f64, f64)>,
+}
+
+struct OlsFit
+{
+    coefficients: Array1<f64>,
+    standard_errors: Array1<f64>,
+    residuals: Array1<f64>,
+}
+
+// Regresses the differences on the lagged level, the deterministic terms and lags lagged differences,
+// the default of trunc((n - 1)^(1/3)) lags follows R's tseries
+#[allow(unused)]
+pub fn get_adf_test(series: &TimeSeries, lags: Option<usize>, terms: DeterministicTerms) -> Result<StationarityTestResult, String>
+{
+    let values = series.get_values();
+    let lags = lags.unwrap_or(((values.len() as f64 - 1.0).cbrt()) as usize);
+    let (features, observations) = get_adf_regression(values, lags, lags, terms)?;
+    let fit = get_ols_with_standard_errors(&features, &observations)?;
+    get_dickey_fuller_result("Augmented Dickey-Fuller", fit.coefficients[0] / fit.standard_errors[0], features.nrows(), lags, terms)
+}
+
+// Compares 0, ..., max_lags lagged differences on the common sample after max_lags observations and refits
+// the best model on all observations, the default of trunc(12 (n / 100)^(1/4)) lags follows Schwert (1989)
+// and is capped at the most lags that short series leave room for
+#[allow(unused)]
+pub fn get_adf_test_with_lag_selection(series: &TimeSeries, max_lags: Option<usize>, terms: DeterministicTerms, criterion: InformationCriterion) -> Result<StationarityTestResult, String>
+{
+    let values = series.get_values();
+    let max_lags = max_lags.unwrap_or_else(||
+    {
+        // get_adf_regression needs n >= 2 max_lags + 5 observations, one more with a trend
+        let max_fitting = values.len().saturating_sub(5 + (terms == DeterministicTerms::ConstantAndTrend) as usize) / 2;
+        ((12.0 * (values.len() as f64 / 100.0).powf(0.25)) as usize).min(max_fitting)
+    });
+    let mut best: Option<(usize, f64)> = None;
+    for lags in 0..=max_lags
+    {
+        let (features, observations) = get_adf_regression(values, lags, max_lags, terms)?;
+        let residuals = get_ols_with_standard_errors(&features, &observations)?.residuals;
+        let num_rows = features.nrows() as f64;
+        let penalty = match criterion
+        {
+            InformationCriterion::Aic => 2.0,
+            InformationCriterion::Bic => num_rows.ln(),
+        };
+        let value = num_rows * (residuals.mapv(|e| e.powi(2)).sum() / num_rows).ln() + penalty * features.ncols() as f64;
+        if best.is_none_or(|(_, best_value)| value < best_value)
+        {
+            best = Some((lags, value));
+        }
+    }
+    get_adf_test(series, best.map(|(lags, _)| lags), terms)
+}
+
+// Corrects the Dickey-Fuller t-statistic without lagged differences for serial correlation with a
+// Bartlett-weighted long-run variance, the default of trunc(4 (n / 100)^(1/4)) lags follows R's tseries
+#[allow(unused)]
+pub fn get_phillips_perron_test(series: &TimeSeries, lags: Option<usize>, terms: DeterministicTerms) -> Result<StationarityTestResult, String>
+{
+    let values = series.get_values();
+    let lags = lags.unwrap_or((4.0 * (values.len() as f64 / 100.0).powf(0.25)) as usize);
+    let (features, observations) = get_adf_regression(values, 0, 0, terms)?;
+    let num_rows = features.nrows();
+    if lags >= num_rows
+    {
+        return Err(String::from("Series is too short for this number of lags!"));
+    }
+    let OlsFit { coefficients, standard_errors, residuals } = get_ols_with_standard_errors(&features, &observations)?;
+    let t = num_rows as f64;
+    let short_run_variance = residuals.mapv(|e| e.powi(2)).sum() / t;
+    let mut long_run_variance = short_run_variance;
+    for lag in 1..=lags
+    {
+        let autocovariance = (lag..num_rows).map(|i| residuals[i] * residuals[i - lag]).sum::<f64>() / t;
+        long_run_variance += 2.0 * (1.0 - lag as f64 / (lags as f64 + 1.0)) * autocovariance;
+    }
+    if long_run_variance <= 0.0
+    {
+        return Err(String::from("Long-run variance is not positive!"));
+    }
+    let residual_variance = short_run_variance * t / (t - features.ncols() as f64);
+    let statistic = (short_run_variance / long_run_variance).sqrt() * coefficients[0] / standard_errors[0]
+        - (long_run_variance - short_run_variance) / (2.0 * long_run_variance.sqrt()) * t * standard_errors[0] / residual_variance.sqrt();
+    get_dickey_fuller_result("Phillips-Perron", statistic, num_rows, lags, terms)
+}
+
+// Bartlett-weighted long-run variance, the default of trunc(3 sqrt(n) / 13) lags follows R's tseries
+#[allow(unused)]
+pub fn get_kpss_test(series: &TimeSeries, lags: Option<usize>, terms: DeterministicTerms) -> Result<StationarityTestResult, String>
+{
+    let values = series.get_values();
+    let n = values.len();
+    let lags = lags.unwrap_or((3.0 * (n as f64).sqrt() / 13.0) as usize);
+    if n < 3 || lags >= n
+    {
+        return Err(String::from("Series is too short for this number of lags!"));
+    }
+
+    let residuals = match terms
+    {
+        DeterministicTerms::Constant =>
+        {
+            let mean = values.mean().unwrap();
+            values.mapv(|value| value - mean)
+        },
+        DeterministicTerms::ConstantAndTrend =>
+        {
+            let features = Array2::from_shape_fn((n, 2), |(t, j)| if j == 0 { 1.0 } else { (t + 1) as f64 });
+            let coefficients = get_least_squares(&features, values)?;
+            values - &get_matrix_vector_product(&features, &coefficients)?
+        },
+    };
+
+    let mut partial_sum = 0.0;
+    let mut sum_of_squared_partial_sums = 0.0;
+    for residual in residuals.iter()
+    {
+        partial_sum += residual;
+        sum_of_squared_partial_sums += partial_sum.powi(2);
+    }
+    let mut long_run_variance = residuals.mapv(|e| e.powi(2)).sum() / n as f64;
+    for lag in 1..=lags
+    {
+        let autocovariance = (lag..n).map(|t| residuals[t] * residuals[t - lag]).sum::<f64>() / n as f64;
+        long_run_variance += 2.0 * (1.0 - lag as f64 / (lags as f64 + 1.0)) * autocovariance;
+    }
+    if long_run_variance <= 0.0
+    {
+        return Err(String::from("Long-run variance is not positive!"));
+    }
+    let statistic = sum_of_squared_partial_sums / ((n as f64).powi(2) * long_run_variance);
+
+    let critical_values = if terms == DeterministicTerms::Constant { KPSS_LEVEL_CRITICAL_VALUES } else { KPSS_TREND_CRITICAL_VALUES };
+    // Large statistics reject stationarity
+    let p_value = get_interpolated_p_value(statistic, &critical_values, false);
+    Ok(StationarityTestResult
+    {
+        test_result: TestResult::new("KPSS", statistic, vec![], p_value),
+        lags,
+        critical_values: SIGNIFICANCE_LEVELS.iter().cloned().zip(critical_values.iter().cloned()).collect(),
+    })
+}
+
+// Number of first differences needed for stationarity, as in R's forecast::ndiffs:
+// difference while KPSS rejects stationarity or ADF and Phillips-Perron fail to reject a unit root
+#[allow(unused)]
+pub fn get_differencing_order(series: &TimeSeries, max_order: usize, test: StationarityTest, alpha_level: f64) -> Result<usize, String>
+{
+    let mut differenced = series.clone();
+    for order in 0..max_order
+    {
+        if differenced.get_values().iter().all(|&value| value == differenced.get_values()[0])
+        {
+            return Ok(order);
+        }
+        let needs_difference = match test
+        {
+            StationarityTest::AugmentedDickeyFuller => get_adf_test(&differenced, None, DeterministicTerms::Constant)?.test_result.p_value >= alpha_level,
+            StationarityTest::Kpss => get_kpss_test(&differenced, None, DeterministicTerms::Constant)?.test_result.p_value < alpha_level,
+            StationarityTest::PhillipsPerron => get_phillips_perron_test(&differenced, None, DeterministicTerms::Constant)?.test_result.p_value >= alpha_level,
+        };
+        if !needs_difference
+        {
+            return Ok(order);
+        }
+        differenced = differenced.get_difference(1, 1)?;
+    }
+    Ok(max_order)
+}
+
+// Rows start after skipped_lags + 1 observations so that regressions with fewer lags can share a sample
+fn get_adf_regression(values: &Array1<f64>, lags: usize, skipped_lags: usize, terms: DeterministicTerms) -> Result<(Array2<f64>, Array1<f64>), String>
+{
+    let n = values.len();
+    let num_columns = 2 + lags + (terms == DeterministicTerms::ConstantAndTrend) as usize;
+    if n < skipped_lags + num_columns + 3
+    {
+        return Err(String::from("Series is too short for this number of lags!"));
+    }
+
+    let differences: Array1<f64> = (1..n).map(|t| values[t] - values[t - 1]).collect();
+    let num_rows = differences.len() - skipped_lags;
+    let mut features = Array2::<f64>::zeros((num_rows, num_columns));
+    let mut observations = Array1::<f64>::zeros(num_rows);
+    for row in 0..num_rows
+    {
+        let t = row + skipped_lags;
+        observations[row] = differences[t];
+        features[[row, 0]] = values[t];
+        features[[row, 1]] = 1.0;
+        for i in 1..=lags
+        {
+            features[[row, 1 + i]] = differences[t - i];
+        }
+        if terms == DeterministicTerms::ConstantAndTrend
+        {
+            features[[row, num_columns - 1]] = (t + 1) as f64;
+        }
+    }
+    Ok((features, observations))
+}
+
+// Critical values from the MacKinnon (2010) response surfaces for num_rows observations
+fn get_dickey_fuller_result(test_name: &str, statistic: f64, num_rows: usize, lags: usize, terms: DeterministicTerms) -> Result<StationarityTestResult, String>
+{
+    let t = num_rows as f64;
+    let coefficient_table = if terms == DeterministicTerms::Constant { ADF_CONSTANT_COEFFICIENTS } else { ADF_TREND_COEFFICIENTS };
+    let critical_values = coefficient_table.iter().map(|b| b[0] + b[1] / t + b[2] / t.powi(2) + b[3] / t.powi(3));
+    Ok(StationarityTestResult
+    {
+        test_result: TestResult::new(test_name, statistic, vec![], get_mackinnon_p_value(statistic, terms)),
+        lags,
+        critical_values: SIGNIFICANCE_LEVELS.iter().cloned().zip(critical_values).collect(),
+    })
+}
+
+// Small statistics reject the unit root
+fn get_mackinnon_p_value(statistic: f64, terms: DeterministicTerms) -> f64
+{
+    let (small_p, large_p, bounds) = match terms
+    {
+        DeterministicTerms::Constant => (&MACKINNON_CONSTANT_SMALL_P[..], &MACKINNON_CONSTANT_LARGE_P[..], MACKINNON_CONSTANT_BOUNDS),
+        DeterministicTerms::ConstantAndTrend => (&MACKINNON_TREND_SMALL_P[..], &MACKINNON_TREND_LARGE_P[..], MACKINNON_TREND_BOUNDS),
+    };
+    if statistic < bounds[0]
+    {
+        return 0.0;
+    }
+    if statistic > bounds[2]
+    {
+        return 1.0;
+    }
+    let coefficients = if statistic <= bounds[1] { small_p } else { large_p };
+    get_normal_cdf(coefficients.iter().rev().fold(0.0, |value, c| value * statistic + c))
+}
+
+// Critical values belong to the 1, 5 and 10 % levels
+fn get_interpolated_p_value(statistic: f64, critical_values: &[f64], lower_tail: bool) -> f64
+{
+    let distance = |critical_value: f64| if lower_tail { critical_value - statistic } else { statistic - critical_value };
+    if distance(critical_values[0]) >= 0.0
+    {
+        return SIGNIFICANCE_LEVELS[0];
+    }
+    for i in 1..SIGNIFICANCE_LEVELS.len()
+    {
+        if distance(critical_values[i]) >= 0.0
+        {
+            let fraction = distance(critical_values[i]) / (distance(critical_values[i]) - distance(critical_values[i - 1]));
+            return SIGNIFICANCE_LEVELS[i] - fraction * (SIGNIFICANCE_LEVELS[i] - SIGNIFICANCE_LEVELS[i - 1]);
+        }
+    }
+    SIGNIFICANCE_LEVELS[SIGNIFICANCE_LEVELS.len() - 1]
+}
+
+fn get_ols_with_standard_errors(features: &Array2<f64>, observations: &Array1<f64>) -> Result<OlsFit, String>
+{
+    let coefficients = get_least_squares(features, observations)?;
+    let residuals = observations - &get_matrix_vector_product(features, &coefficients)?;
+    let degrees_of_freedom = (features.nrows() - features.ncols()) as f64;
+    let variance = residuals.mapv(|e| e.powi(2)).sum() / degrees_of_freedom;
+    let covariance = get_inverse(&get_matrix_product(&features.t().to_owned(), features)?)?;
+    let standard_errors = covariance.diag().mapv(|c| (c * variance).sqrt());
+    Ok(OlsFit { coefficients, standard_errors, residuals })
+}
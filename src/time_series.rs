@@ -0,0 +1,304 @@
+use super::distributions::{get_chi_squared_sf, get_normal_ppf};
+use super::hypothesis_testing::TestResult;
+use super::utilities::{check_vector_for_nans, get_mean, get_median};
+use ndarray::{s, Array1, Array2};
+
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Aggregation
+{
+    Sum,
+    Mean,
+    Median,
+    Min,
+    Max,
+    First,
+    Last,
+    Count,
+}
+
+// Model selection criterion shared by AutoArima and the ADF lag selection
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InformationCriterion
+{
+    Aic,
+    Bic,
+}
+
+// Entry k - 1 belongs to lag k, confidence bands are half-widths around zero
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct Correlogram
+{
+    pub values: Array1<f64>,
+    pub confidence_bands: Array1<f64>,
+}
+
+// Point forecasts for steps 1, 2, ... ahead with prediction intervals
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct Forecast
+{
+    pub mean: Array1<f64>,
+    pub standard_errors: Array1<f64>,
+    pub lower: Array1<f64>,
+    pub upper: Array1<f64>,
+}
+
+#[allow(unused)]
+impl Forecast
+{
+    // Normal prediction intervals around the point forecasts
+    pub fn new(mean: Array1<f64>, standard_errors: Array1<f64>, confidence_level: f64) -> Result<Self, String>
+    {
+        let z = get_critical_value(confidence_level)?;
+        let lower = &mean - &(&standard_errors * z);
+        let upper = &mean + &(&standard_errors * z);
+        Ok(Forecast { mean, standard_errors, lower, upper })
+    }
+}
+
+#[allow(unused)]
+#[derive(Debug, Clone)]
+pub struct TimeSeries
+{
+    values: Array1<f64>,
+    index: Array1<f64>,
+}
+
+#[allow(unused)]
+impl TimeSeries
+{
+    // Observations are sorted by their index, observations with equal index keep their order
+    pub fn new(values: Array1<f64>, index: Array1<f64>) -> Result<Self, String>
+    {
+        if values.len() != index.len()
+        {
+            return Err(String::from("Values and index must have the same length!"));
+        }
+        if values.is_empty()
+        {
+            return Err(String::from("Vector cannot be empty"));
+        }
+        check_vector_for_nans(&values)?;
+        check_vector_for_nans(&index)?;
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by(|&a, &b| index[a].partial_cmp(&index[b]).unwrap());
+        Ok(TimeSeries
+        {
+            values: order.iter().map(|&i| values[i]).collect(),
+            index: order.iter().map(|&i| index[i]).collect(),
+        })
+    }
+
+    // Equally spaced observations indexed 0, 1, 2, ...
+    pub fn from_values(values: Array1<f64>) -> Result<Self, String>
+    {
+        let index = Array1::range(0.0, values.len() as f64, 1.0);
+        TimeSeries::new(values, index)
+    }
+
+    // E.g. the value and year columns of a file read with file_reading::read_csv_to_array
+    pub fn from_columns(data: &Array2<f64>, value_column: usize, index_column: usize) -> Result<Self, String>
+    {
+        if value_column >= data.ncols() || index_column >= data.ncols()
+        {
+            return Err(String::from("Column index out of bounds!"));
+        }
+        TimeSeries::new(data.column(value_column).to_owned(), data.column(index_column).to_owned())
+    }
+
+    pub fn get_values(&self) -> &Array1<f64>
+    {
+        &self.values
+    }
+
+    pub fn get_index(&self) -> &Array1<f64>
+    {
+        &self.index
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.values.is_empty()
+    }
+
+    // x_{t - lag} at time t, the first lag time points have no lagged value and are dropped
+    pub fn get_lag(&self, lag: usize) -> Result<TimeSeries, String>
+    {
+        self.check_lag(lag)?;
+        let n = self.len();
+        Ok(TimeSeries { values: self.values.slice(s![..n - lag]).to_owned(), index: self.index.slice(s![lag..]).to_owned() })
+    }
+
+    // x_t - x_{t - lag}, applied order times, e.g. lag 12 removes a monthly seasonality
+    pub fn get_difference(&self, lag: usize, order: usize) -> Result<TimeSeries, String>
+    {
+        let mut differenced = self.clone();
+        for _ in 0..order
+        {
+            differenced.check_lag(lag)?;
+            let n = differenced.len();
+            let values = &differenced.values.slice(s![lag..]) - &differenced.values.slice(s![..n - lag]);
+            differenced = TimeSeries { values, index: differenced.index.slice(s![lag..]).to_owned() };
+        }
+        Ok(differenced)
+    }
+
+    // Sample autocorrelations r_1, ..., r_max_lag with Bartlett's bands,
+    // under an MA(k - 1) process r_k has variance (1 + 2 (r_1^2 + ... + r_{k-1}^2)) / n
+    pub fn get_acf(&self, max_lag: usize, confidence_level: f64) -> Result<Correlogram, String>
+    {
+        let autocorrelations = self.get_autocorrelations(max_lag)?;
+        let z = get_critical_value(confidence_level)?;
+        let n = self.len() as f64;
+        let mut sum_of_squares = 0.0;
+        let mut confidence_bands = Array1::<f64>::zeros(max_lag);
+        for k in 0..max_lag
+        {
+            confidence_bands[k] = z * ((1.0 + 2.0 * sum_of_squares) / n).sqrt();
+            sum_of_squares += autocorrelations[k].powi(2);
+        }
+        Ok(Correlogram { values: autocorrelations, confidence_bands })
+    }
+
+    // Durbin-Levinson recursion, the bands assume white noise
+    pub fn get_pacf(&self, max_lag: usize, confidence_level: f64) -> Result<Correlogram, String>
+    {
+        let autocorrelations = self.get_autocorrelations(max_lag)?;
+        let z = get_critical_value(confidence_level)?;
+        let partial_autocorrelations = get_partial_autocorrelations(&autocorrelations);
+        let band = z / (self.len() as f64).sqrt();
+        Ok(Correlogram { values: partial_autocorrelations, confidence_bands: Array1::from_elem(max_lag, band) })
+    }
+
+    // fitted_parameters is subtracted from the degrees of freedom when testing model residuals
+    pub fn get_ljung_box_test(&self, lags: usize, fitted_parameters: usize) -> Result<TestResult, String>
+    {
+        let autocorrelations = self.get_autocorrelations(lags)?;
+        let n = self.len() as f64;
+        let statistic = n * (n + 2.0) * autocorrelations.iter().enumerate().map(|(k, r)| r.powi(2) / (n - k as f64 - 1.0)).sum::<f64>();
+        get_portmanteau_result("Ljung-Box", statistic, lags, fitted_parameters)
+    }
+
+    pub fn get_box_pierce_test(&self, lags: usize, fitted_parameters: usize) -> Result<TestResult, String>
+    {
+        let autocorrelations = self.get_autocorrelations(lags)?;
+        let statistic = self.len() as f64 * autocorrelations.mapv(|r| r.powi(2)).sum();
+        get_portmanteau_result("Box-Pierce", statistic, lags, fitted_parameters)
+    }
+
+    // Groups observations into periods [k * period, (k + 1) * period) of the index,
+    // each period is labelled with its start, periods without observations are left out
+    pub fn get_resampled(&self, period: f64, aggregation: Aggregation) -> Result<TimeSeries, String>
+    {
+        if period <= 0.0 || !period.is_finite()
+        {
+            return Err(String::from("Period must be positive!"));
+        }
+        let mut values = Vec::new();
+        let mut index = Vec::new();
+        let mut start = 0;
+        while start < self.len()
+        {
+            let label = (self.index[start] / period).floor() * period;
+            let mut end = start;
+            while end < self.len() && (self.index[end] / period).floor() * period == label
+            {
+                end += 1;
+            }
+            values.push(get_aggregate(&self.values.slice(s![start..end]).to_owned(), aggregation)?);
+            index.push(label);
+            start = end;
+        }
+        Ok(TimeSeries { values: Array1::from(values), index: Array1::from(index) })
+    }
+
+    fn get_autocorrelations(&self, max_lag: usize) -> Result<Array1<f64>, String>
+    {
+        if max_lag == 0 || max_lag >= self.len()
+        {
+            return Err(String::from("Maximum lag must be between 1 and the series length minus one!"));
+        }
+        let mean = get_mean(&self.values)?;
+        let centered = self.values.mapv(|x| x - mean);
+        let variance = centered.mapv(|x| x.powi(2)).sum();
+        if variance == 0.0
+        {
+            return Err(String::from("Series is constant!"));
+        }
+        let n = self.len();
+        Ok((1..=max_lag).map(|k| (0..n - k).map(|t| centered[t] * centered[t + k]).sum::<f64>() / variance).collect())
+    }
+
+    fn check_lag(&self, lag: usize) -> Result<(), String>
+    {
+        if lag == 0 || lag >= self.len()
+        {
+            return Err(String::from("Lag must be between 1 and the series length minus one!"));
+        }
+        Ok(())
+    }
+}
+
+// phi_kk from the autocorrelations r_1, ..., r_m, also used to fit autoregressions
+pub(crate) fn get_partial_autocorrelations(autocorrelations: &Array1<f64>) -> Array1<f64>
+{
+    let max_lag = autocorrelations.len();
+    let mut partial_autocorrelations = Array1::<f64>::zeros(max_lag);
+    let mut coefficients: Vec<f64> = Vec::with_capacity(max_lag);
+    for k in 0..max_lag
+    {
+        let numerator = autocorrelations[k] - (0..k).map(|j| coefficients[j] * autocorrelations[k - 1 - j]).sum::<f64>();
+        let denominator = 1.0 - (0..k).map(|j| coefficients[j] * autocorrelations[j]).sum::<f64>();
+        let phi = numerator / denominator;
+        let previous = coefficients.clone();
+        for j in 0..k
+        {
+            coefficients[j] = previous[j] - phi * previous[k - 1 - j];
+        }
+        coefficients.push(phi);
+        partial_autocorrelations[k] = phi;
+    }
+    partial_autocorrelations
+}
+
+fn get_portmanteau_result(test_name: &str, statistic: f64, lags: usize, fitted_parameters: usize) -> Result<TestResult, String>
+{
+    if fitted_parameters >= lags
+    {
+        return Err(String::from("Number of lags must exceed the number of fitted parameters!"));
+    }
+    let degrees_of_freedom = (lags - fitted_parameters) as f64;
+    Ok(TestResult::new(test_name, statistic, vec![degrees_of_freedom], get_chi_squared_sf(statistic, degrees_of_freedom)?))
+}
+
+fn get_aggregate(values: &Array1<f64>, aggregation: Aggregation) -> Result<f64, String>
+{
+    Ok(match aggregation
+    {
+        Aggregation::Sum => values.sum(),
+        Aggregation::Mean => get_mean(values)?,
+        Aggregation::Median => get_median(values)?,
+        Aggregation::Min => values.fold(f64::INFINITY, |a, &b| a.min(b)),
+        Aggregation::Max => values.fold(f64::NEG_INFINITY, |a, &b| a.max(b)),
+        Aggregation::First => values[0],
+        Aggregation::Last => values[values.len() - 1],
+        Aggregation::Count => values.len() as f64,
+    })
+}
+
+fn get_critical_value(confidence_level: f64) -> Result<f64, String>
+{
+    if confidence_level <= 0.0 || confidence_level >= 1.0
+    {
+        return Err(String::from("Confidence level must be between 0 and 1!"));
+    }
+    get_normal_ppf(0.5 + confidence_level / 2.0)
+}
@@ -1,4 +1,5 @@
 use num_traits;
+use super::linear_algebra::get_matrix_product;
 use ndarray::{Array1, Array2};
 
 #[allow(unused)]
@@ -100,6 +101,19 @@ pub fn get_covariance_matrix(mat: &Array2<f64>) -> Result<ndarray::Array2<f64>,
     Ok(cov_mat)
 }
 
+// Sample covariance of all columns, the rows being the observations. get_covariance_matrix cannot stand in for it,
+// as it leaves out the last column and divides the variances by n - 1 but the covariances by n
+#[allow(unused)]
+pub fn get_sample_covariance_matrix(data: &Array2<f64>) -> Result<Array2<f64>, String>
+{
+    if data.nrows() < 2
+    {
+        return Err(String::from("Need at least two observations to estimate a covariance matrix!"));
+    }
+    let centered = data - &data.mean_axis(ndarray::Axis(0)).unwrap();
+    Ok(get_matrix_product(&centered.t().to_owned(), &centered)? / (data.nrows() as f64 - 1.0))
+}
+
 #[allow(unused)]
 pub fn get_sample_covariance(x: &Array1<f64>, y: &Array1<f64>) -> Result<f64, String>
 {
@@ -246,6 +260,83 @@ pub fn get_mean(input_vector: &Array1<f64>) -> Result<f64, String>
     }
 }
 
+#[allow(unused)]
+pub fn get_median(input_vector: &Array1<f64>) -> Result<f64, String>
+{
+    let input_vector = check_vector_for_nans(input_vector)?;
+    if input_vector.is_empty()
+    {
+        return Err(String::from("Vector cannot be empty"));
+    }
+    let mut sorted = input_vector.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let middle = sorted.len() / 2;
+    if sorted.len() % 2 == 0
+    {
+        Ok((sorted[middle - 1] + sorted[middle]) / 2.0)
+    }
+    else
+    {
+        Ok(sorted[middle])
+    }
+}
+
+// Ranks starting at 1, tied values share the average of their ranks
+#[allow(unused)]
+pub fn get_ranks(input_vector: &Array1<f64>) -> Result<Array1<f64>, String>
+{
+    let input_vector = check_vector_for_nans(input_vector)?;
+    let mut order: Vec<usize> = (0..input_vector.len()).collect();
+    order.sort_by(|&a, &b| input_vector[a].partial_cmp(&input_vector[b]).unwrap());
+
+    let mut ranks = Array1::<f64>::zeros(input_vector.len());
+    let mut start = 0;
+    while start < order.len()
+    {
+        let mut end = start;
+        while end + 1 < order.len() && input_vector[order[end + 1]] == input_vector[order[start]]
+        {
+            end += 1;
+        }
+        let average_rank = (start + end) as f64 / 2.0 + 1.0;
+        for &index in &order[start..=end]
+        {
+            ranks[index] = average_rank;
+        }
+        start = end + 1;
+    }
+    Ok(ranks)
+}
+
+// Linear interpolation between order statistics (Hyndman & Fan type 7)
+#[allow(unused)]
+pub fn get_quantile(input_vector: &Array1<f64>, probability: f64) -> Result<f64, String>
+{
+    let input_vector = check_vector_for_nans(input_vector)?;
+    if input_vector.is_empty()
+    {
+        return Err(String::from("Vector cannot be empty"));
+    }
+    if !(0.0..=1.0).contains(&probability)
+    {
+        return Err(String::from("Probability must be between 0 and 1!"));
+    }
+    let mut sorted = input_vector.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let position = probability * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    Ok(sorted[lower] + (position - lower as f64) * (sorted[upper] - sorted[lower]))
+}
+
+// Scaled by 1.4826 so that it estimates the standard deviation for normal data
+#[allow(unused)]
+pub fn get_median_absolute_deviation(input_vector: &Array1<f64>) -> Result<f64, String>
+{
+    let median = get_median(input_vector)?;
+    Ok(1.4826 * get_median(&input_vector.mapv(|x| (x - median).abs()))?)
+}
+
 pub fn get_factorial(n: u64) -> u128 
 {
     let mut result: u128 = 1;
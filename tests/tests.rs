@@ -1,12 +1,39 @@
 #[cfg(test)]
 mod tests
 {
-    use ndarray::{Array1, arr1};
+    use ndarray::{s, Array1, Array2, arr1};
     use math::round::*;
     use simple_statistics::counting;
     use simple_statistics::simple_linear_regression;
     use simple_statistics::utilities;
-    use simple_statistics::hypothesis_testing::{ZTestKind, ZTest, get_f_statistic};  
+    use simple_statistics::hypothesis_testing::{self, ZTestKind, ZTest, TestKind, get_f_statistic};  
+    use simple_statistics::distributions;
+    use simple_statistics::generalized_linear_models::*;
+    use simple_statistics::robust_regression::{self, MEstimator};
+    use simple_statistics::errors_in_variables_regression::{self, IntervalMethod};
+    use simple_statistics::analysis_of_variance::{self, SumOfSquaresType, PostHocMethod};
+    use simple_statistics::contingency_table::{self, ContingencyTable};
+    use simple_statistics::exact_tests::{self, FisherMethod};
+    use simple_statistics::rank_tests::{self, RankTestMethod};
+    use simple_statistics::normality_tests::*;
+    use simple_statistics::hypothesis_testing::{HypothesisTest, Ttest, TTestKind};
+    use simple_statistics::multiple_testing::{self, PAdjustMethod};
+    use simple_statistics::power_analysis::{self, PowerTest};
+    use simple_statistics::effect_sizes;
+    use simple_statistics::confidence_intervals::{self, ProportionIntervalMethod};
+    use simple_statistics::time_series::{TimeSeries, Aggregation, InformationCriterion};
+    use simple_statistics::file_reading;
+    use simple_statistics::smoothing::{self, ExponentialSmoothing, TrendKind, SeasonalKind, SmoothingParameters};
+    use simple_statistics::stationarity_tests::{self, DeterministicTerms, StationarityTest};
+    use simple_statistics::arima::{Arima, ArimaMethod, AutoArima};
+    use simple_statistics::decomposition::{self, DecompositionKind};
+    use simple_statistics::principal_components::{PrincipalComponentAnalysis, ComponentCriterion};
+    use simple_statistics::linear_algebra;
+    use simple_statistics::factor_analysis::{self, FactorAnalysis, FactorExtraction, Rotation};
+    use simple_statistics::clustering::{self, DistanceMetric, KMeans, KMeansAlgorithm, MiniBatchKMeans, KMedoids};
+    use simple_statistics::hierarchical_clustering::{AgglomerativeClustering, Linkage};
+    use simple_statistics::density_clustering::{Dbscan, Hdbscan};
+    use simple_statistics::basis_expansion::{Basis, BasisRegression, Transform, get_polynomial_regression, get_box_cox_lambda};
     use assert_approx_eq;
     static NUM_DECIMAL_DIGITS: i8 = 3;
 
@@ -38,7 +65,7 @@ mod tests
         let sample_2 = arr1(&[5.0, 20.0, 40.0, 80.0, 100.0]);
 
         let result = half_away_from_zero(get_f_statistic(&sample_1, &sample_2).unwrap(), num_decimal_digits);
-        let expected_result = 0.0047;
+        let expected_result = 214.0;
 
         assert_eq!(result, expected_result);
     }
@@ -66,6 +93,22 @@ mod tests
         assert_eq!(result, expected_result);
     }
 
+    #[test]
+    fn test_sample_covariance_matrix()
+    {
+        let vector_1 = arr1(&[12.0, 30.0, 15.0, 24.0, 14.0, 18.0, 28.0, 26.0, 19.0, 27.0]);
+        let vector_2 = arr1(&[20.0, 60.0, 27.0, 50.0, 21.0, 30.0, 61.0, 54.0, 32.0, 57.0]);
+        let data = ndarray::stack![ndarray::Axis(1), vector_1, vector_2];
+
+        // Keeps every column and divides by n - 1 throughout
+        let covariance = utilities::get_sample_covariance_matrix(&data).unwrap();
+        assert_eq!(covariance.dim(), (2, 2));
+        assert_eq!(half_away_from_zero(covariance[[0, 1]], NUM_DECIMAL_DIGITS), 106.933);
+        assert_eq!(covariance[[0, 1]], covariance[[1, 0]]);
+        assert_eq!(half_away_from_zero(covariance[[1, 1]], NUM_DECIMAL_DIGITS), half_away_from_zero(utilities::get_variance(&vector_2).unwrap(), NUM_DECIMAL_DIGITS));
+        assert!(utilities::get_sample_covariance_matrix(&ndarray::arr2(&[[1.0, 2.0]])).is_err());
+    }
+
     #[test]
     fn test_coefficient_of_variation()
     {
@@ -337,6 +380,1890 @@ mod tests
         assert_eq!(round(&x, 2), 3.000);
     }
 
+    #[test]
+    fn test_normal_cdf_and_ppf()
+    {
+        let result = half_away_from_zero(distributions::get_normal_cdf(1.96), 4);
+        assert_eq!(result, 0.975);
+
+        let result = half_away_from_zero(distributions::get_normal_ppf(0.975).unwrap(), 4);
+        assert_eq!(result, 1.96);
+    }
+
+    #[test]
+    fn test_gaussian_glm_matches_least_squares()
+    {
+        let x = arr1(&[34.0, 108.0, 64.0, 88.0, 99.0, 51.0]);
+        let y = arr1(&[5.0, 17.0, 11.0, 8.0, 14.0, 5.0]);
+        let features = x.clone().insert_axis(ndarray::Axis(1));
+
+        let glm = Glm::new(Box::new(Gaussian), Box::new(IdentityLink), true);
+        let result = glm.fit(&features, &y, None, None).unwrap();
+
+        assert_approx_eq::assert_approx_eq!(result.coefficients[0], simple_linear_regression::get_best_fitting_intercept(&x, &y).unwrap(), 1e-8);
+        assert_approx_eq::assert_approx_eq!(result.coefficients[1], simple_linear_regression::get_best_fitting_slope(&x, &y).unwrap(), 1e-8);
+        assert_approx_eq::assert_approx_eq!(result.deviance, 30.075, 1e-3);
+        assert_approx_eq::assert_approx_eq!(result.null_deviance, 120.0, 1e-8);
+    }
+
+    #[test]
+    fn test_poisson_glm_with_binary_covariate()
+    {
+        // With a single binary covariate the fitted means are the group means
+        let features = ndarray::arr2(&[[0.0], [0.0], [0.0], [1.0], [1.0], [1.0]]);
+        let y = arr1(&[2.0, 3.0, 7.0, 10.0, 12.0, 14.0]);
+
+        let glm = Glm::new(Box::new(Poisson), Box::new(LogLink), true);
+        let result = glm.fit(&features, &y, None, None).unwrap();
+
+        assert!(result.converged);
+        assert_approx_eq::assert_approx_eq!(result.coefficients[0].exp(), 4.0, 1e-6);
+        assert_approx_eq::assert_approx_eq!((result.coefficients[0] + result.coefficients[1]).exp(), 12.0, 1e-6);
+        assert_eq!(result.dispersion, 1.0);
+    }
+
+    #[test]
+    fn test_binomial_glm_with_offset_free_logit()
+    {
+        // Proportions with the number of trials as prior weights
+        let features = ndarray::arr2(&[[0.0], [0.0], [1.0], [1.0]]);
+        let y = arr1(&[0.2, 0.4, 0.6, 0.8]);
+        let trials = arr1(&[10.0, 10.0, 10.0, 10.0]);
+
+        let glm = Glm::new(Box::new(Binomial), Box::new(LogitLink), true);
+        let result = glm.fit(&features, &y, None, Some(&trials)).unwrap();
+
+        assert_approx_eq::assert_approx_eq!(result.coefficients[0], (0.3f64 / 0.7).ln(), 1e-6);
+        assert_approx_eq::assert_approx_eq!(result.coefficients[1], (0.7f64 / 0.3).ln() - (0.3f64 / 0.7).ln(), 1e-6);
+        // Standard error of a log odds ratio is sqrt(sum of 1 / cell counts)
+        assert_approx_eq::assert_approx_eq!(result.standard_errors[1], (2.0 / 6.0 + 2.0 / 14.0f64).sqrt(), 1e-6);
+    }
+
+    #[test]
+    fn test_poisson_glm_rejects_negative_counts()
+    {
+        let features = ndarray::arr2(&[[0.0], [1.0], [2.0]]);
+        let y = arr1(&[1.0, -2.0, 3.0]);
+
+        let glm = Glm::new(Box::new(Poisson), Box::new(LogLink), true);
+        assert!(glm.fit(&features, &y, None, None).is_err());
+    }
+
+    #[test]
+    fn test_glm_rejects_means_outside_link_domain()
+    {
+        // Counts above one have no probit or logit
+        let features = ndarray::arr2(&[[0.0], [1.0], [2.0], [3.0]]);
+        let y = arr1(&[1.0, 3.0, 4.0, 8.0]);
+
+        assert!(Glm::new(Box::new(Poisson), Box::new(ProbitLink), true).fit(&features, &y, None, None).is_err());
+        assert!(Glm::new(Box::new(Poisson), Box::new(LogitLink), true).fit(&features, &y, None, None).is_err());
+        assert!(ProbitLink.link(1.5).is_err());
+        assert!(LogLink.derivative(0.0).is_err());
+        assert_approx_eq::assert_approx_eq!(ProbitLink.link(0.975).unwrap(), 1.959964, 1e-6);
+    }
+
+    #[test]
+    fn test_glm_predict_checks_offset_length()
+    {
+        let features = ndarray::arr2(&[[0.0], [0.0], [0.0], [1.0], [1.0], [1.0]]);
+        let y = arr1(&[2.0, 3.0, 7.0, 10.0, 12.0, 14.0]);
+
+        let glm = Glm::new(Box::new(Poisson), Box::new(LogLink), true);
+        let result = glm.fit(&features, &y, None, None).unwrap();
+        let new_features = ndarray::arr2(&[[0.0], [1.0]]);
+        assert!(glm.predict(&new_features, &result.coefficients, Some(&arr1(&[0.0]))).is_err());
+        let predictions = glm.predict(&new_features, &result.coefficients, Some(&arr1(&[0.0, 2.0f64.ln()]))).unwrap();
+        assert_approx_eq::assert_approx_eq!(predictions[0], 4.0, 1e-6);
+        assert_approx_eq::assert_approx_eq!(predictions[1], 24.0, 1e-6);
+    }
+
+    #[test]
+    fn test_polynomial_regression_recovers_quadratic()
+    {
+        let x = arr1(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let y = x.mapv(|x| 1.0 + 2.0 * x + 3.0 * x * x);
+
+        let regression = get_polynomial_regression(&x, &y, 2).unwrap();
+        let predictions = regression.predict(&arr1(&[10.0, -1.0])).unwrap();
+
+        assert_approx_eq::assert_approx_eq!(predictions[0], 321.0, 1e-8);
+        assert_approx_eq::assert_approx_eq!(predictions[1], 2.0, 1e-8);
+        assert_approx_eq::assert_approx_eq!(regression.coefficient_of_determination, 1.0, 1e-10);
+    }
+
+    #[test]
+    fn test_linear_polynomial_matches_simple_linear_regression()
+    {
+        let x = arr1(&[34.0, 108.0, 64.0, 88.0, 99.0, 51.0]);
+        let y = arr1(&[5.0, 17.0, 11.0, 8.0, 14.0, 5.0]);
+
+        let regression = get_polynomial_regression(&x, &y, 1).unwrap();
+        let slope = simple_linear_regression::get_best_fitting_slope(&x, &y).unwrap();
+        let intercept = simple_linear_regression::get_best_fitting_intercept(&x, &y).unwrap();
+        let expected = utilities::get_predictions(&x, slope, intercept).unwrap();
+
+        for (result, expected) in regression.fitted_values.iter().zip(expected.iter())
+        {
+            assert_approx_eq::assert_approx_eq!(result, expected, 1e-8);
+        }
+        assert_approx_eq::assert_approx_eq!(regression.sse, 30.075, 1e-3);
+    }
+
+    #[test]
+    fn test_linear_b_spline_fits_broken_line()
+    {
+        let x = arr1(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        let y = x.mapv(|x: f64| if x < 4.0 { x } else { 4.0 + 3.0 * (x - 4.0) });
+        let basis = Basis::BSpline { interior_knots: vec![4.0], degree: 1 };
+
+        let regression = BasisRegression::fit(&x, &y, &basis, Transform::Identity, Transform::Identity).unwrap();
+        let predictions = regression.predict(&arr1(&[2.5, 6.5, 8.0])).unwrap();
+
+        assert_approx_eq::assert_approx_eq!(predictions[0], 2.5, 1e-8);
+        assert_approx_eq::assert_approx_eq!(predictions[1], 11.5, 1e-8);
+        assert_approx_eq::assert_approx_eq!(predictions[2], 16.0, 1e-8);
+        assert!(regression.predict(&arr1(&[9.0])).is_err());
+    }
+
+    #[test]
+    fn test_natural_cubic_spline_extrapolates_linearly()
+    {
+        let x = arr1(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        let y = x.mapv(|x| 3.0 - 0.5 * x);
+        let basis = Basis::NaturalCubicSpline { interior_knots: vec![2.0, 4.0, 6.0] };
+
+        let regression = BasisRegression::fit(&x, &y, &basis, Transform::Identity, Transform::Identity).unwrap();
+        let predictions = regression.predict(&arr1(&[12.0])).unwrap();
+
+        assert_approx_eq::assert_approx_eq!(predictions[0], -3.0, 1e-8);
+    }
+
+    #[test]
+    fn test_log_response_transform_is_applied_on_prediction()
+    {
+        let x = arr1(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let y = x.mapv(|x: f64| (1.0 + 0.5 * x).exp());
+
+        let regression = BasisRegression::fit(&x, &y, &Basis::Polynomial(1), Transform::Identity, Transform::Log).unwrap();
+        let predictions = regression.predict(&arr1(&[6.0])).unwrap();
+
+        assert_approx_eq::assert_approx_eq!(predictions[0], 4.0f64.exp(), 1e-8);
+        assert!(Transform::BoxCox(0.5).apply(&arr1(&[1.0, -1.0])).is_err());
+    }
+
+    #[test]
+    fn test_box_cox_lambda_for_squared_data()
+    {
+        // Squares of evenly spaced values are made symmetric by a square root
+        let x = arr1(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]).mapv(|x: f64| x * x);
+        let lambda = get_box_cox_lambda(&x).unwrap();
+
+        assert!(lambda > 0.3 && lambda < 0.7);
+    }
+
+    fn get_line_with_outlier() -> (Array1<f64>, Array1<f64>)
+    {
+        let x = arr1(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]);
+        let noise = arr1(&[0.1, -0.2, 0.15, -0.05, 0.0, 0.2, -0.1, 0.05, -0.15, 0.0]);
+        let mut y = x.mapv(|x| 2.0 * x + 1.0) + noise;
+        y[9] = 100.0;
+        (x, y)
+    }
+
+    #[test]
+    fn test_median()
+    {
+        assert_eq!(utilities::get_median(&arr1(&[3.0, 1.0, 2.0])).unwrap(), 2.0);
+        assert_eq!(utilities::get_median(&arr1(&[4.0, 1.0, 3.0, 2.0])).unwrap(), 2.5);
+    }
+
+    #[test]
+    fn test_theil_sen_ignores_outlier()
+    {
+        let (x, y) = get_line_with_outlier();
+        let fit = robust_regression::get_theil_sen(&x, &y).unwrap();
+
+        assert_approx_eq::assert_approx_eq!(fit.slope, 2.0, 0.1);
+        assert_approx_eq::assert_approx_eq!(fit.intercept, 1.0, 0.3);
+        assert_eq!(fit.weights[9], 0.0);
+        assert_eq!(fit.weights.sum(), 9.0);
+
+        let fit = robust_regression::get_siegel_repeated_medians(&x, &y).unwrap();
+        assert_approx_eq::assert_approx_eq!(fit.slope, 2.0, 0.1);
+        assert_eq!(fit.weights[9], 0.0);
+    }
+
+    #[test]
+    fn test_m_estimators_down_weight_outlier()
+    {
+        let (x, y) = get_line_with_outlier();
+        let least_squares_slope = simple_linear_regression::get_best_fitting_slope(&x, &y).unwrap();
+
+        let huber = robust_regression::get_m_estimate(&x, &y, MEstimator::huber(), 100).unwrap();
+        assert!((huber.slope - 2.0).abs() < (least_squares_slope - 2.0).abs());
+        assert!(huber.weights[9] < 0.1);
+
+        let tukey = robust_regression::get_m_estimate(&x, &y, MEstimator::tukey_bisquare(), 100).unwrap();
+        assert_approx_eq::assert_approx_eq!(tukey.slope, 2.0, 0.05);
+        assert_eq!(tukey.weights[9], 0.0);
+    }
+
+    #[test]
+    fn test_least_absolute_deviations_passes_through_points()
+    {
+        let x = arr1(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let y = arr1(&[3.0, 5.0, 7.0, 9.0, 50.0]);
+        let fit = robust_regression::get_least_absolute_deviations(&x, &y, 500).unwrap();
+
+        assert_approx_eq::assert_approx_eq!(fit.slope, 2.0, 1e-4);
+        assert_approx_eq::assert_approx_eq!(fit.intercept, 1.0, 1e-4);
+    }
+
+    #[test]
+    fn test_seeded_ransac_flags_outlier()
+    {
+        let (x, y) = get_line_with_outlier();
+        let fit = robust_regression::get_ransac(&x, &y, Some(1.0), 50, 42).unwrap();
+        let repeated = robust_regression::get_ransac(&x, &y, Some(1.0), 50, 42).unwrap();
+
+        assert!(!fit.inlier_mask[9]);
+        assert_eq!(fit.inlier_mask.iter().filter(|&&inlier| inlier).count(), 9);
+        assert_approx_eq::assert_approx_eq!(fit.slope, 2.0, 0.1);
+        assert_eq!(fit.slope, repeated.slope);
+    }
+
+    #[test]
+    fn test_t_distribution_quantiles()
+    {
+        let result = half_away_from_zero(distributions::get_t_ppf(0.975, 10.0).unwrap(), NUM_DECIMAL_DIGITS);
+        assert_eq!(result, 2.228);
+
+        let result = half_away_from_zero(distributions::get_t_cdf(-2.228139, 10.0).unwrap(), NUM_DECIMAL_DIGITS);
+        assert_eq!(result, 0.025);
+    }
+
+    #[test]
+    fn test_quantile()
+    {
+        let v = arr1(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(utilities::get_quantile(&v, 0.25).unwrap(), 2.0);
+        assert_eq!(utilities::get_quantile(&v, 0.1).unwrap(), 1.4);
+    }
+
+    #[test]
+    fn test_weighted_least_squares_ignores_zero_weight()
+    {
+        let x = arr1(&[34.0, 108.0, 64.0, 88.0, 99.0, 51.0, 70.0]);
+        let y = arr1(&[5.0, 17.0, 11.0, 8.0, 14.0, 5.0, 500.0]);
+        let weights = arr1(&[1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0]);
+
+        let result = half_away_from_zero(simple_linear_regression::get_weighted_best_fitting_slope(&x, &y, &weights).unwrap(), NUM_DECIMAL_DIGITS);
+        assert_eq!(result, 0.146);
+        let result = half_away_from_zero(simple_linear_regression::get_weighted_best_fitting_intercept(&x, &y, &weights).unwrap(), NUM_DECIMAL_DIGITS);
+        assert_eq!(result, -0.820);
+
+        // Invalid weights are errors rather than panics or NaN slopes
+        let invalid_weights = [arr1(&[1.0, 1.0]), arr1(&[1.0, 1.0, 1.0, f64::NAN, 1.0, 1.0, 1.0]), arr1(&[1.0, 1.0, 1.0, -1.0, 1.0, 1.0, 1.0]), Array1::zeros(7)];
+        for weights in invalid_weights.iter()
+        {
+            assert!(simple_linear_regression::get_weighted_best_fitting_slope(&x, &y, weights).is_err());
+            assert!(simple_linear_regression::get_weighted_best_fitting_intercept(&x, &y, weights).is_err());
+        }
+    }
+
+    #[test]
+    fn test_orthogonal_regression()
+    {
+        let x = arr1(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let y = arr1(&[2.0, 4.0, 5.0, 4.0, 5.0]);
+
+        let result = errors_in_variables_regression::get_orthogonal_regression(&x, &y, IntervalMethod::Jackknife, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(result.slope, NUM_DECIMAL_DIGITS), 0.721);
+        assert!(result.slope_interval.0 < result.slope && result.slope < result.slope_interval.1);
+
+        // With equal error variances the fit does not depend on which variable is called x
+        let swapped = errors_in_variables_regression::get_orthogonal_regression(&y, &x, IntervalMethod::Jackknife, 0.95).unwrap();
+        assert_approx_eq::assert_approx_eq!(swapped.slope, 1.0 / result.slope, 1e-10);
+    }
+
+    #[test]
+    fn test_deming_regression_bootstrap_is_reproducible()
+    {
+        let x = arr1(&[1.0, 2.1, 2.9, 4.2, 5.0, 6.1, 6.9, 8.0]);
+        let y = arr1(&[1.1, 2.0, 3.2, 3.9, 5.1, 5.8, 7.2, 7.9]);
+        let method = IntervalMethod::Bootstrap { num_resamples: 200, seed: 42 };
+
+        let result = errors_in_variables_regression::get_deming_regression(&x, &y, 2.0, method, 0.95).unwrap();
+        let repeated = errors_in_variables_regression::get_deming_regression(&x, &y, 2.0, method, 0.95).unwrap();
+
+        assert_approx_eq::assert_approx_eq!(result.slope, 1.0, 0.05);
+        assert_eq!(result.slope_interval, repeated.slope_interval);
+        assert!(result.slope_interval.0 < result.slope && result.slope < result.slope_interval.1);
+    }
+
+    #[test]
+    fn test_passing_bablok_regression()
+    {
+        let x = arr1(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+        let mut y = x.mapv(|x| 2.0 * x + 1.0);
+        y[6] = 40.0;
+
+        let result = errors_in_variables_regression::get_passing_bablok_regression(&x, &y, IntervalMethod::Jackknife, 0.95).unwrap();
+        assert_eq!(result.slope, 2.0);
+        assert_eq!(result.intercept, 1.0);
+    }
+
+    fn get_anova_groups() -> Vec<Array1<f64>>
+    {
+        vec![arr1(&[6.0, 8.0, 4.0, 5.0, 3.0, 4.0]),
+             arr1(&[8.0, 12.0, 9.0, 11.0, 6.0, 8.0]),
+             arr1(&[13.0, 9.0, 11.0, 8.0, 7.0, 12.0])]
+    }
+
+    #[test]
+    fn test_studentized_range_quantile()
+    {
+        let result = half_away_from_zero(distributions::get_studentized_range_ppf(0.95, 4, 20.0).unwrap(), 2);
+        assert_eq!(result, 3.96);
+    }
+
+    #[test]
+    fn test_one_way_anova()
+    {
+        let table = analysis_of_variance::get_one_way_anova(&get_anova_groups()).unwrap();
+        let between = table.get_row("Between groups").unwrap();
+
+        assert_eq!(between.sum_of_squares, 84.0);
+        assert_eq!(table.get_row("Residual").unwrap().sum_of_squares, 68.0);
+        assert_eq!(half_away_from_zero(between.f_statistic.unwrap(), NUM_DECIMAL_DIGITS), 9.265);
+        assert_eq!(half_away_from_zero(between.p_value.unwrap(), 4), 0.0024);
+        assert_eq!(half_away_from_zero(between.eta_squared.unwrap(), NUM_DECIMAL_DIGITS), 0.553);
+        assert_eq!(half_away_from_zero(between.omega_squared.unwrap(), NUM_DECIMAL_DIGITS), 0.479);
+    }
+
+    #[test]
+    fn test_tukey_hsd()
+    {
+        let comparisons = analysis_of_variance::get_post_hoc_comparisons(&get_anova_groups(), PostHocMethod::TukeyHsd, 0.05).unwrap();
+
+        // Groups 0 vs 2
+        assert_eq!(comparisons[1].mean_difference, -5.0);
+        assert_eq!(half_away_from_zero(comparisons[1].confidence_interval.0, 2), -8.19);
+        assert_eq!(half_away_from_zero(comparisons[1].confidence_interval.1, 2), -1.81);
+        assert!(comparisons[0].reject && comparisons[1].reject && !comparisons[2].reject);
+    }
+
+    #[test]
+    fn test_post_hoc_methods_agree_on_clear_differences()
+    {
+        let tukey = analysis_of_variance::get_post_hoc_comparisons(&get_anova_groups(), PostHocMethod::TukeyHsd, 0.05).unwrap();
+        for method in [PostHocMethod::Bonferroni, PostHocMethod::Scheffe, PostHocMethod::GamesHowell].iter()
+        {
+            let comparisons = analysis_of_variance::get_post_hoc_comparisons(&get_anova_groups(), *method, 0.05).unwrap();
+            assert!(comparisons[1].reject);
+            assert!(!comparisons[2].reject);
+            assert!(comparisons[2].p_value <= 1.0);
+        }
+        // Scheffe intervals are the most conservative
+        let scheffe = analysis_of_variance::get_post_hoc_comparisons(&get_anova_groups(), PostHocMethod::Scheffe, 0.05).unwrap();
+        assert!(scheffe[0].confidence_interval.1 - scheffe[0].confidence_interval.0 > tukey[0].confidence_interval.1 - tukey[0].confidence_interval.0);
+    }
+
+    #[test]
+    fn test_two_way_anova_balanced_types_agree()
+    {
+        let factor_a = arr1(&[0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0]);
+        let factor_b = arr1(&[0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0]);
+        let y = arr1(&[4.0, 5.0, 6.0, 8.0, 7.0, 9.0, 6.0, 5.0, 10.0, 12.0, 9.0, 11.0]);
+
+        let type_1 = analysis_of_variance::get_two_way_anova(&factor_a, &factor_b, &y, SumOfSquaresType::TypeI).unwrap();
+        let type_3 = analysis_of_variance::get_two_way_anova(&factor_a, &factor_b, &y, SumOfSquaresType::TypeIII).unwrap();
+        for (row_1, row_3) in type_1.rows.iter().zip(type_3.rows.iter())
+        {
+            assert_approx_eq::assert_approx_eq!(row_1.sum_of_squares, row_3.sum_of_squares, 1e-8);
+        }
+        assert_eq!(type_1.get_row("A:B").unwrap().degrees_of_freedom, 2.0);
+        assert_eq!(type_1.get_row("Residual").unwrap().degrees_of_freedom, 6.0);
+    }
+
+    #[test]
+    fn test_two_way_anova_unbalanced()
+    {
+        let factor_a = arr1(&[0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0]);
+        let factor_b = arr1(&[0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 1.0]);
+        let y = arr1(&[3.0, 4.0, 5.0, 8.0, 9.0, 6.0, 9.0, 11.0, 10.0]);
+
+        // Sequential sums of squares add up to the total
+        let type_1 = analysis_of_variance::get_two_way_anova(&factor_a, &factor_b, &y, SumOfSquaresType::TypeI).unwrap();
+        let sum: f64 = type_1.rows.iter().map(|row| row.sum_of_squares).sum();
+        assert_approx_eq::assert_approx_eq!(sum, type_1.total_sum_of_squares, 1e-8);
+
+        // Type II for A is Type I for A when A enters the model last
+        let type_2 = analysis_of_variance::get_two_way_anova(&factor_a, &factor_b, &y, SumOfSquaresType::TypeII).unwrap();
+        let swapped = analysis_of_variance::get_two_way_anova(&factor_b, &factor_a, &y, SumOfSquaresType::TypeI).unwrap();
+        assert_approx_eq::assert_approx_eq!(type_2.get_row("A").unwrap().sum_of_squares, swapped.get_row("B").unwrap().sum_of_squares, 1e-8);
+
+        let type_3 = analysis_of_variance::get_two_way_anova(&factor_a, &factor_b, &y, SumOfSquaresType::TypeIII).unwrap();
+        assert!((type_3.get_row("A").unwrap().sum_of_squares - type_1.get_row("A").unwrap().sum_of_squares).abs() > 1e-3);
+    }
+
+    #[test]
+    fn test_f_test_for_equal_variances()
+    {
+        let sample_1 = arr1(&[1.0, 2.0, 4.0, 5.0, 8.0]);
+        let sample_2 = arr1(&[5.0, 20.0, 40.0, 80.0, 100.0]);
+
+        let result = hypothesis_testing::get_f_test(&sample_1, &sample_2, TestKind::TwoTailed).unwrap();
+        assert_eq!(half_away_from_zero(result.statistic, 4), 0.0047);
+        assert_eq!(result.degrees_of_freedom, vec![4.0, 4.0]);
+        assert_eq!(half_away_from_zero(result.p_value * 1e4, 3), 1.294);
+
+        let upper = hypothesis_testing::get_f_test(&sample_1, &sample_2, TestKind::OneTailedUpper).unwrap();
+        let lower = hypothesis_testing::get_f_test(&sample_1, &sample_2, TestKind::OneTailedLower).unwrap();
+        assert_approx_eq::assert_approx_eq!(upper.p_value + lower.p_value, 1.0, 1e-12);
+        assert_approx_eq::assert_approx_eq!(lower.p_value * 2.0, result.p_value, 1e-12);
+    }
+
+    #[test]
+    fn test_levene_and_brown_forsythe()
+    {
+        let result = hypothesis_testing::get_levene_test(&get_anova_groups()).unwrap();
+        assert_eq!(half_away_from_zero(result.statistic, NUM_DECIMAL_DIGITS), 0.6);
+        assert_eq!(half_away_from_zero(result.p_value, NUM_DECIMAL_DIGITS), 0.561);
+        assert_eq!(result.degrees_of_freedom, vec![2.0, 15.0]);
+
+        let result = hypothesis_testing::get_brown_forsythe_test(&get_anova_groups()).unwrap();
+        assert!(result.p_value > 0.05);
+    }
+
+    #[test]
+    fn test_bartlett()
+    {
+        let result = hypothesis_testing::get_bartlett_test(&get_anova_groups()).unwrap();
+        assert_eq!(half_away_from_zero(result.statistic, NUM_DECIMAL_DIGITS), 0.367);
+        assert_eq!(half_away_from_zero(result.p_value, NUM_DECIMAL_DIGITS), 0.833);
+    }
+
+    #[test]
+    fn test_contingency_table_from_labels()
+    {
+        let treatment = ["a", "a", "b", "b", "b", "a", "b"];
+        let outcome = ["yes", "no", "yes", "yes", "no", "yes", "yes"];
+        let table = ContingencyTable::from_labels(&treatment, &outcome).unwrap();
+
+        assert_eq!(table.get_counts(), &ndarray::arr2(&[[1.0, 2.0], [1.0, 3.0]]));
+        assert_eq!(table.get_row_labels(), &[String::from("a"), String::from("b")]);
+        assert_eq!(table.get_column_labels(), &[String::from("no"), String::from("yes")]);
+    }
+
+    #[test]
+    fn test_contingency_table_from_labels_rejects_nan()
+    {
+        let dose = [1.0, 2.0, f64::NAN, 1.0];
+        let outcome = [0.0, 1.0, 1.0, 0.0];
+        assert!(ContingencyTable::from_labels(&dose, &outcome).is_err());
+
+        let table = ContingencyTable::from_labels(&[1.0, 2.0, 2.0, 1.0], &outcome).unwrap();
+        assert_eq!(table.get_counts(), &ndarray::arr2(&[[2.0, 0.0], [0.0, 2.0]]));
+    }
+
+    #[test]
+    fn test_chi_squared_independence()
+    {
+        let table = ContingencyTable::new(ndarray::arr2(&[[10.0, 20.0], [30.0, 40.0]])).unwrap();
+
+        let result = table.get_chi_squared_test(false).unwrap();
+        assert_eq!(result.expected_counts, ndarray::arr2(&[[12.0, 18.0], [28.0, 42.0]]));
+        assert_eq!(half_away_from_zero(result.test_result.statistic, 4), 0.7937);
+        assert_eq!(half_away_from_zero(result.test_result.p_value, 4), 0.373);
+        assert!(result.warnings.is_empty());
+
+        let corrected = table.get_chi_squared_test(true).unwrap();
+        assert_eq!(half_away_from_zero(corrected.test_result.statistic, 4), 0.4464);
+        assert_eq!(half_away_from_zero(corrected.test_result.p_value, 3), 0.504);
+
+        let g_test = table.get_g_test(false).unwrap();
+        assert_eq!(half_away_from_zero(g_test.test_result.statistic, 3), 0.804);
+
+        assert_eq!(half_away_from_zero(table.get_phi().unwrap(), 4), 0.0891);
+        assert_eq!(half_away_from_zero(table.get_cramers_v().unwrap(), 4), 0.0891);
+        assert_eq!(half_away_from_zero(table.get_contingency_coefficient().unwrap(), 4), 0.0887);
+    }
+
+    #[test]
+    fn test_small_expected_counts_are_flagged()
+    {
+        let table = ContingencyTable::new(ndarray::arr2(&[[1.0, 2.0], [1.0, 3.0]])).unwrap();
+        assert!(!table.get_chi_squared_test(true).unwrap().warnings.is_empty());
+    }
+
+    #[test]
+    fn test_chi_squared_goodness_of_fit()
+    {
+        let observed = arr1(&[16.0, 18.0, 16.0, 14.0, 12.0, 12.0]);
+        let expected = Array1::from_elem(6, 1.0 / 6.0);
+
+        let result = contingency_table::get_chi_squared_goodness_of_fit(&observed, &expected, 0).unwrap();
+        assert_eq!(half_away_from_zero(result.statistic, NUM_DECIMAL_DIGITS), 2.0);
+        assert_eq!(result.degrees_of_freedom, vec![5.0]);
+        assert_eq!(half_away_from_zero(result.p_value, NUM_DECIMAL_DIGITS), 0.849);
+
+        // Proportions that do not sum to one are a different hypothesis, not rescaled
+        assert!(contingency_table::get_chi_squared_goodness_of_fit(&observed, &arr1(&[1.0, 1.0, 1.0, 1.0, 1.0, 1.0]), 0).is_err());
+        assert!(contingency_table::get_chi_squared_goodness_of_fit(&observed, &arr1(&[0.2, 0.2, 0.2, 0.2, 0.1, 0.2]), 0).is_err());
+    }
+
+    #[test]
+    fn test_goodness_of_fit_to_fitted_distribution()
+    {
+        let data = arr1(&[-1.9, -1.2, -0.8, -0.5, -0.3, -0.1, 0.0, 0.2, 0.4, 0.6, 0.9, 1.3, 1.8, -0.6, 0.5, 0.1]);
+        let result = contingency_table::get_chi_squared_goodness_of_fit_to_distribution(&data, &[-1.0, 0.0, 1.0], distributions::get_normal_cdf, 0).unwrap();
+
+        assert_eq!(result.degrees_of_freedom, vec![3.0]);
+        assert!(result.p_value > 0.05);
+    }
+
+    #[test]
+    fn test_ln_combinations_do_not_overflow()
+    {
+        let result = counting::get_ln_combinations(10, 3).unwrap();
+        assert_approx_eq::assert_approx_eq!(result.exp(), 120.0, 1e-9);
+
+        let result = counting::get_ln_combinations(1000, 500).unwrap();
+        assert_eq!(half_away_from_zero(result, NUM_DECIMAL_DIGITS), 689.467);
+    }
+
+    #[test]
+    fn test_fisher_exact_two_by_two()
+    {
+        // Lady tasting tea
+        let table = ContingencyTable::new(ndarray::arr2(&[[3.0, 1.0], [1.0, 3.0]])).unwrap();
+
+        let result = exact_tests::get_fisher_exact_test(&table, TestKind::TwoTailed).unwrap();
+        assert_eq!(half_away_from_zero(result.test_result.p_value, 4), 0.4857);
+        // Conditional maximum likelihood estimate, not the sample odds ratio of 9
+        assert_eq!(half_away_from_zero(result.odds_ratio, 4), 6.4083);
+
+        let result = exact_tests::get_fisher_exact_test(&table, TestKind::OneTailedUpper).unwrap();
+        assert_eq!(half_away_from_zero(result.test_result.p_value, 4), 0.2429);
+
+        let table = ContingencyTable::new(ndarray::arr2(&[[10.0, 3.0], [2.0, 15.0]])).unwrap();
+        let result = exact_tests::get_fisher_exact_test(&table, TestKind::TwoTailed).unwrap();
+        assert_eq!(half_away_from_zero(result.odds_ratio, 4), 21.3053);
+        assert_eq!(result.test_result.statistic, result.odds_ratio);
+
+        // Zero cells give an odds ratio at the end of its range instead of NaN
+        let table = ContingencyTable::new(ndarray::arr2(&[[0.0, 5.0], [4.0, 3.0]])).unwrap();
+        assert_eq!(exact_tests::get_fisher_exact_test(&table, TestKind::TwoTailed).unwrap().odds_ratio, 0.0);
+        let table = ContingencyTable::new(ndarray::arr2(&[[2.0, 0.0], [3.0, 6.0]])).unwrap();
+        assert_eq!(exact_tests::get_fisher_exact_test(&table, TestKind::TwoTailed).unwrap().odds_ratio, f64::INFINITY);
+        let table = ContingencyTable::new(ndarray::arr2(&[[0.0, 0.0], [3.0, 6.0]])).unwrap();
+        assert_eq!(exact_tests::get_fisher_exact_test(&table, TestKind::TwoTailed).unwrap().test_result.p_value, 1.0);
+    }
+
+    #[test]
+    fn test_fisher_exact_r_by_c()
+    {
+        let table = ContingencyTable::new(ndarray::arr2(&[[3.0, 1.0], [1.0, 3.0]])).unwrap();
+        let result = exact_tests::get_fisher_exact_test_r_by_c(&table, FisherMethod::Exact).unwrap();
+        assert_eq!(half_away_from_zero(result.p_value, 4), 0.4857);
+
+        let table = ContingencyTable::new(ndarray::arr2(&[[2.0, 3.0, 4.0], [5.0, 1.0, 0.0], [1.0, 2.0, 6.0]])).unwrap();
+        let exact = exact_tests::get_fisher_exact_test_r_by_c(&table, FisherMethod::Exact).unwrap();
+        let simulated = exact_tests::get_fisher_exact_test_r_by_c(&table, FisherMethod::MonteCarlo { num_simulations: 5000, seed: 42 }).unwrap();
+        assert!(exact.p_value > 0.0 && exact.p_value < 1.0);
+        assert_approx_eq::assert_approx_eq!(exact.p_value, simulated.p_value, 0.02);
+
+        // Tables with a heavy diagonal, the network algorithm prunes most of the tables with these margins
+        let diagonal = |on: f64, off: f64| ContingencyTable::new(Array2::from_shape_fn((4, 4), |(i, j)| if i == j { on } else { off })).unwrap();
+        let result = exact_tests::get_fisher_exact_test_r_by_c(&diagonal(5.0, 3.0), FisherMethod::Exact).unwrap();
+        assert_eq!(half_away_from_zero(result.p_value, 4), 0.9694);
+        let result = exact_tests::get_fisher_exact_test_r_by_c(&diagonal(10.0, 3.0), FisherMethod::Exact).unwrap();
+        assert_eq!(half_away_from_zero(result.p_value, 4), 0.0021);
+        assert!(exact_tests::get_fisher_exact_test_r_by_c(&diagonal(20.0, 15.0), FisherMethod::Exact).is_err());
+        assert!(exact_tests::get_fisher_exact_test_r_by_c(&diagonal(20.0, 15.0), FisherMethod::MonteCarlo { num_simulations: 1000, seed: 42 }).is_ok());
+    }
+
+    #[test]
+    fn test_exact_binomial_test()
+    {
+        let result = exact_tests::get_binomial_test(7, 20, 0.5, TestKind::TwoTailed, 0.95).unwrap();
+
+        assert_eq!(half_away_from_zero(result.test_result.p_value, 4), 0.2632);
+        assert_eq!(half_away_from_zero(result.confidence_interval.0, 4), 0.1539);
+        assert_eq!(half_away_from_zero(result.confidence_interval.1, 4), 0.5922);
+        assert_eq!(result.estimate, 0.35);
+    }
+
+    #[test]
+    fn test_mcnemar()
+    {
+        let table = ContingencyTable::new(ndarray::arr2(&[[20.0, 10.0], [3.0, 15.0]])).unwrap();
+
+        let exact = exact_tests::get_mcnemar_test(&table, true, false).unwrap();
+        assert_eq!(half_away_from_zero(exact.p_value, 4), 0.0923);
+
+        let asymptotic = exact_tests::get_mcnemar_test(&table, false, true).unwrap();
+        assert_eq!(half_away_from_zero(asymptotic.statistic, NUM_DECIMAL_DIGITS), 2.769);
+        assert_eq!(half_away_from_zero(asymptotic.p_value, 4), 0.0961);
+    }
+
+    #[test]
+    fn test_ranks_with_ties()
+    {
+        let ranks = utilities::get_ranks(&arr1(&[3.0, 1.0, 4.0, 1.0, 5.0])).unwrap();
+        assert_eq!(ranks, arr1(&[3.0, 1.5, 4.0, 1.5, 5.0]));
+    }
+
+    #[test]
+    fn test_mann_whitney_u()
+    {
+        let sample_1 = arr1(&[3.1, 4.5, 2.2, 5.9, 6.3]);
+        let sample_2 = arr1(&[1.2, 2.8, 0.9, 3.3, 1.7, 2.0]);
+
+        let exact = rank_tests::get_mann_whitney_u_test(&sample_1, &sample_2, TestKind::TwoTailed, RankTestMethod::Auto).unwrap();
+        assert_eq!(exact.statistic, 27.0);
+        assert_eq!(half_away_from_zero(exact.p_value, 4), 0.0303);
+        assert_eq!(half_away_from_zero(exact.effect_size.unwrap(), NUM_DECIMAL_DIGITS), 0.8);
+
+        let upper = rank_tests::get_mann_whitney_u_test(&sample_1, &sample_2, TestKind::OneTailedUpper, RankTestMethod::Exact).unwrap();
+        assert_eq!(half_away_from_zero(upper.p_value, 4), 0.0152);
+
+        // Ties force the normal approximation with tie and continuity corrections
+        let tied_1 = arr1(&[1.0, 2.0, 2.0, 3.0, 4.0, 5.0, 5.0, 6.0]);
+        let tied_2 = arr1(&[3.0, 3.0, 4.0, 6.0, 7.0, 7.0, 8.0, 9.0, 9.0]);
+        let asymptotic = rank_tests::get_mann_whitney_u_test(&tied_1, &tied_2, TestKind::TwoTailed, RankTestMethod::Auto).unwrap();
+        assert_eq!(asymptotic.statistic, 13.0);
+        assert_eq!(half_away_from_zero(asymptotic.p_value, 4), 0.0294);
+        assert!(rank_tests::get_mann_whitney_u_test(&tied_1, &tied_2, TestKind::TwoTailed, RankTestMethod::Exact).is_err());
+    }
+
+    #[test]
+    fn test_wilcoxon_signed_rank()
+    {
+        let before = arr1(&[1.83, 0.50, 1.62, 2.48, 1.68, 1.88, 1.55, 3.06, 1.30]);
+        let after = arr1(&[0.878, 0.647, 0.598, 2.05, 1.06, 1.29, 1.06, 3.15, 1.29]);
+
+        let result = rank_tests::get_paired_wilcoxon_signed_rank_test(&before, &after, TestKind::TwoTailed, RankTestMethod::Auto).unwrap();
+        assert_eq!(result.statistic, 40.0);
+        assert_eq!(half_away_from_zero(result.p_value, 4), 0.0391);
+        assert_eq!(half_away_from_zero(result.effect_size.unwrap(), NUM_DECIMAL_DIGITS), 0.778);
+
+        let upper = rank_tests::get_paired_wilcoxon_signed_rank_test(&before, &after, TestKind::OneTailedUpper, RankTestMethod::Auto).unwrap();
+        assert_eq!(half_away_from_zero(upper.p_value, 4), 0.0195);
+    }
+
+    #[test]
+    fn test_kruskal_wallis_and_dunn()
+    {
+        let groups = [arr1(&[2.9, 3.0, 2.5, 2.6, 3.2]), arr1(&[3.8, 2.7, 4.0, 2.4]), arr1(&[2.8, 3.4, 3.7, 2.2, 2.0])];
+
+        let result = rank_tests::get_kruskal_wallis_test(&groups, RankTestMethod::Auto).unwrap();
+        assert_eq!(half_away_from_zero(result.statistic, 4), 0.7714);
+        assert_eq!(half_away_from_zero(result.p_value, 4), 0.68);
+        assert_eq!(half_away_from_zero(result.effect_size.unwrap(), 4), 0.0593);
+
+        let comparisons = rank_tests::get_dunn_test(&groups, PAdjustMethod::Bonferroni, 0.05).unwrap();
+        assert_eq!(comparisons.len(), 3);
+        assert_eq!(half_away_from_zero(comparisons[0].z_statistic, 4), -0.6414);
+        assert_eq!(comparisons[0].adjusted_p_value, 1.0);
+        assert!(comparisons.iter().all(|comparison| !comparison.reject));
+    }
+
+    #[test]
+    fn test_friedman()
+    {
+        let data = ndarray::arr2(&[[1.0, 2.0, 3.0], [1.0, 3.0, 2.0], [1.0, 2.0, 3.0], [2.0, 1.0, 3.0], [1.0, 2.0, 3.0]]);
+
+        let exact = rank_tests::get_friedman_test(&data, RankTestMethod::Auto).unwrap();
+        assert_eq!(half_away_from_zero(exact.statistic, NUM_DECIMAL_DIGITS), 6.4);
+        assert_eq!(half_away_from_zero(exact.p_value, 4), 0.0394);
+        assert_eq!(half_away_from_zero(exact.effect_size.unwrap(), NUM_DECIMAL_DIGITS), 0.64);
+
+        let asymptotic = rank_tests::get_friedman_test(&data, RankTestMethod::Asymptotic).unwrap();
+        assert_eq!(half_away_from_zero(asymptotic.p_value, 4), 0.0408);
+    }
+
+    fn get_skewed_sample() -> Array1<f64>
+    {
+        arr1(&[148.0, 154.0, 158.0, 160.0, 161.0, 162.0, 166.0, 170.0, 182.0, 195.0, 236.0])
+    }
+
+    fn get_symmetric_sample() -> Array1<f64>
+    {
+        arr1(&[2.1, 3.4, 1.9, 5.6, 4.4, 3.3, 2.8, 3.9, 4.1, 3.0, 3.6, 2.5, 4.8, 3.2, 2.9, 3.7, 4.0, 3.1, 2.6, 3.5])
+    }
+
+    #[test]
+    fn test_shapiro_wilk()
+    {
+        let skewed = ShapiroWilk.run(&get_skewed_sample()).unwrap();
+        assert_eq!(half_away_from_zero(skewed.statistic, 4), 0.7888);
+        assert_eq!(half_away_from_zero(skewed.p_value, 4), 0.0067);
+
+        let symmetric = ShapiroWilk.run(&get_symmetric_sample()).unwrap();
+        assert_eq!(half_away_from_zero(symmetric.statistic, 4), 0.9794);
+        assert_eq!(half_away_from_zero(symmetric.p_value, 4), 0.9264);
+
+        assert!(ShapiroWilk.run(&arr1(&[1.0, 2.0])).is_err());
+    }
+
+    #[test]
+    fn test_anderson_darling_and_lilliefors()
+    {
+        let anderson_darling = AndersonDarling.run(&get_skewed_sample()).unwrap();
+        assert_eq!(half_away_from_zero(anderson_darling.statistic, 4), 0.9468);
+        assert_eq!(half_away_from_zero(anderson_darling.p_value, 4), 0.0105);
+
+        let lilliefors = Lilliefors.run(&get_skewed_sample()).unwrap();
+        assert_eq!(half_away_from_zero(lilliefors.statistic, 4), 0.2592);
+        assert_eq!(half_away_from_zero(lilliefors.p_value, 4), 0.0374);
+
+        let lilliefors = Lilliefors.run(&get_symmetric_sample()).unwrap();
+        assert_eq!(half_away_from_zero(lilliefors.p_value, 4), 0.9889);
+    }
+
+    #[test]
+    fn test_kolmogorov_smirnov()
+    {
+        let test = KolmogorovSmirnov::new(|x| distributions::get_normal_cdf(x - 2.75));
+        let result = test.run(&get_symmetric_sample()).unwrap();
+        assert_eq!(half_away_from_zero(result.statistic, 4), 0.3199);
+        // Monte Carlo estimate with 400000 draws is 0.0254
+        assert_eq!(half_away_from_zero(result.p_value, 3), 0.025);
+
+        let samples = [arr1(&[0.61, 1.2, 2.3, 0.15, 1.7, 0.92]), arr1(&[2.2, 3.1, 1.9, 2.8, 3.6, 2.55, 4.0])];
+        let two_sample = TwoSampleKolmogorovSmirnov.run(&samples).unwrap();
+        assert_eq!(half_away_from_zero(two_sample.statistic, 4), 0.8333);
+        assert_eq!(half_away_from_zero(two_sample.p_value, 4), 0.0152);
+    }
+
+    #[test]
+    fn test_moment_based_normality_tests()
+    {
+        let jarque_bera = JarqueBera.run(&get_skewed_sample()).unwrap();
+        assert_eq!(half_away_from_zero(jarque_bera.statistic, 4), 6.9828);
+        assert_eq!(half_away_from_zero(jarque_bera.p_value, 4), 0.0305);
+
+        let k_squared = DAgostinoPearson.run(&get_skewed_sample()).unwrap();
+        assert_eq!(half_away_from_zero(k_squared.statistic, 4), 13.0343);
+        assert_eq!(half_away_from_zero(k_squared.p_value, 4), 0.0015);
+
+        let k_squared = DAgostinoPearson.run(&get_symmetric_sample()).unwrap();
+        assert_eq!(half_away_from_zero(k_squared.p_value, 4), 0.3798);
+    }
+
+    #[test]
+    fn test_z_test_through_trait()
+    {
+        let sample = Array1::<f64>::from_elem(112, 72180.0);
+        let ztest = ZTest::new(ZTestKind::OneTailedUpper, 0.05).unwrap().with_null_hypothesis(69873.0, 13985.0).unwrap();
+        let result = ztest.run(&sample).unwrap();
+        assert_eq!(half_away_from_zero(result.statistic, NUM_DECIMAL_DIGITS), 1.746);
+        assert_eq!(half_away_from_zero(result.p_value, 4), 0.0404);
+        assert_eq!(half_away_from_zero(result.effect_size.unwrap(), 4), 0.165);
+
+        // Without a null hypothesis there is nothing to test against
+        assert!(ZTest::new(ZTestKind::OneTailedUpper, 0.05).unwrap().run(&sample).is_err());
+    }
+
+    #[test]
+    fn test_t_tests()
+    {
+        // Student's sleep data
+        let samples = [arr1(&[0.7, -1.6, -0.2, -1.2, -0.1, 3.4, 3.7, 0.8, 0.0, 2.0]),
+                       arr1(&[1.9, 0.8, 1.1, 0.1, -0.1, 4.4, 5.5, 1.6, 4.6, 3.4])];
+
+        let welch = Ttest::new(TTestKind::TwoSample { equal_variances: false }, TestKind::TwoTailed).run(&samples).unwrap();
+        assert_eq!(half_away_from_zero(welch.statistic, 4), -1.8608);
+        assert_eq!(half_away_from_zero(welch.degrees_of_freedom[0], NUM_DECIMAL_DIGITS), 17.776);
+        assert_eq!(half_away_from_zero(welch.p_value, 5), 0.07939);
+
+        let student = Ttest::new(TTestKind::TwoSample { equal_variances: true }, TestKind::TwoTailed).run(&samples).unwrap();
+        assert_eq!(half_away_from_zero(student.p_value, 5), 0.07919);
+
+        let paired = Ttest::new(TTestKind::Paired, TestKind::TwoTailed).run(&samples).unwrap();
+        assert_eq!(half_away_from_zero(paired.statistic, 4), -4.0621);
+        assert_eq!(half_away_from_zero(paired.p_value, 6), 0.002833);
+
+        let one_sample = Ttest::new(TTestKind::OneSample { hypothesized_mean: 0.0 }, TestKind::TwoTailed);
+        assert!(one_sample.run(&samples).is_err());
+    }
+
+    #[test]
+    fn test_adjusted_p_values()
+    {
+        let p_values = arr1(&[0.01, 0.04, 0.03, 0.005, 0.2, 0.5]);
+        let adjust = |method| multiple_testing::get_adjusted_p_values(&p_values, method).unwrap()
+            .iter()
+            .map(|&p| half_away_from_zero(p, 4))
+            .collect::<Vec<f64>>();
+
+        assert_eq!(adjust(PAdjustMethod::Bonferroni), vec![0.06, 0.24, 0.18, 0.03, 1.0, 1.0]);
+        assert_eq!(adjust(PAdjustMethod::Holm), vec![0.05, 0.12, 0.12, 0.03, 0.4, 0.5]);
+        assert_eq!(adjust(PAdjustMethod::Hochberg), vec![0.05, 0.12, 0.12, 0.03, 0.4, 0.5]);
+        assert_eq!(adjust(PAdjustMethod::Hommel), vec![0.05, 0.12, 0.09, 0.03, 0.4, 0.5]);
+        assert_eq!(adjust(PAdjustMethod::BenjaminiHochberg), vec![0.03, 0.06, 0.06, 0.03, 0.24, 0.5]);
+        assert_eq!(adjust(PAdjustMethod::BenjaminiYekutieli), vec![0.0735, 0.147, 0.147, 0.0735, 0.588, 1.0]);
+    }
+
+    #[test]
+    fn test_noncentral_distributions()
+    {
+        // Reference values from numerical integration over the chi-squared scale
+        assert_eq!(half_away_from_zero(distributions::get_noncentral_t_cdf(1.5, 10.0, 2.0).unwrap(), 6), 0.304785);
+        assert_eq!(half_away_from_zero(distributions::get_noncentral_t_cdf(-1.0, 5.0, 1.0).unwrap(), 6), 0.030807);
+        assert_eq!(half_away_from_zero(distributions::get_noncentral_chi_squared_cdf(5.0, 3.0, 2.0).unwrap(), 6), 0.593405);
+        // Large noncentralities, where the Poisson weights near zero underflow
+        assert_eq!(half_away_from_zero(distributions::get_noncentral_t_cdf(45.0, 1000.0, 45.0).unwrap(), 6), 0.496306);
+        assert_eq!(half_away_from_zero(distributions::get_noncentral_t_cdf(50.0, 1000.0, 50.0).unwrap(), 6), 0.49606);
+        assert_eq!(half_away_from_zero(distributions::get_noncentral_t_cdf(60.0, 200.0, 55.0).unwrap(), 6), 0.941646);
+        assert!(distributions::get_noncentral_t_cdf(500.0, 10.0, 500.0).is_err());
+        // Without noncentrality the central distributions are recovered
+        assert_approx_eq::assert_approx_eq!(distributions::get_noncentral_f_cdf(2.0, 3.0, 20.0, 0.0).unwrap(), distributions::get_f_cdf(2.0, 3.0, 20.0).unwrap());
+    }
+
+    #[test]
+    fn test_power()
+    {
+        let two_sided = TestKind::TwoTailed;
+        let power = power_analysis::get_power(PowerTest::TwoSampleT(two_sided), 0.5, 20.0, 0.05).unwrap();
+        assert_eq!(half_away_from_zero(power, 4), 0.3379);
+
+        let anova = power_analysis::get_power(PowerTest::OneWayAnova { num_groups: 4 }, 0.28, 20.0, 0.05).unwrap();
+        assert_eq!(half_away_from_zero(anova, 6), 0.514979);
+
+        let h = power_analysis::get_cohens_h(0.65, 0.5).unwrap();
+        let proportions = power_analysis::get_power(PowerTest::TwoProportions(TestKind::OneTailedUpper), 0.3, 80.0, 0.05).unwrap();
+        assert_eq!(half_away_from_zero(h, 4), 0.3047);
+        assert_eq!(half_away_from_zero(proportions, 4), 0.5997);
+    }
+
+    #[test]
+    fn test_power_analysis_solves_for_missing_quantity()
+    {
+        let sample_size = power_analysis::get_power_analysis(PowerTest::TwoSampleT(TestKind::TwoTailed), Some(0.5), None, Some(0.05), Some(0.8)).unwrap();
+        assert_eq!(half_away_from_zero(sample_size.sample_size, 4), 63.7656);
+
+        let correlation = power_analysis::get_power_analysis(PowerTest::Correlation(TestKind::TwoTailed), Some(0.3), None, Some(0.05), Some(0.8)).unwrap();
+        assert_eq!(half_away_from_zero(correlation.sample_size, 4), 84.0736);
+
+        let effect = power_analysis::get_power_analysis(PowerTest::OneSampleT(TestKind::OneTailedLower), None, Some(30.0), Some(0.05), Some(0.9)).unwrap();
+        assert!(effect.effect_size < 0.0);
+        let power = power_analysis::get_power(PowerTest::OneSampleT(TestKind::OneTailedLower), effect.effect_size, 30.0, 0.05).unwrap();
+        assert_approx_eq::assert_approx_eq!(power, 0.9, 1e-8);
+
+        let alpha = power_analysis::get_power_analysis(PowerTest::ZTest(TestKind::TwoTailed), Some(0.5), Some(20.0), None, Some(0.8)).unwrap();
+        assert_eq!(half_away_from_zero(alpha.alpha_level, 4), 0.163);
+
+        assert!(power_analysis::get_power_analysis(PowerTest::ZTest(TestKind::TwoTailed), Some(0.5), None, None, Some(0.8)).is_err());
+    }
+
+    fn get_sleep_samples() -> (Array1<f64>, Array1<f64>)
+    {
+        (arr1(&[0.7, -1.6, -0.2, -1.2, -0.1, 3.4, 3.7, 0.8, 0.0, 2.0]),
+         arr1(&[1.9, 0.8, 1.1, 0.1, -0.1, 4.4, 5.5, 1.6, 4.6, 3.4]))
+    }
+
+    #[test]
+    fn test_standardized_mean_differences()
+    {
+        let (sample_1, sample_2) = get_sleep_samples();
+
+        let cohens_d = effect_sizes::get_cohens_d(&sample_1, &sample_2, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(cohens_d.estimate, 4), -0.8322);
+        assert_eq!(half_away_from_zero(cohens_d.confidence_interval.0, 4), -1.7388);
+        assert_eq!(half_away_from_zero(cohens_d.confidence_interval.1, 4), 0.0955);
+
+        let hedges_g = effect_sizes::get_hedges_g(&sample_1, &sample_2, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(hedges_g.estimate, 4), -0.7969);
+
+        let glass_delta = effect_sizes::get_glass_delta(&sample_1, &sample_2, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(glass_delta.estimate, 4), -0.7891);
+        assert!(glass_delta.confidence_interval.0 < -0.7891 && glass_delta.confidence_interval.1 > 0.0);
+
+        // Large t statistics need the noncentral t far from zero noncentrality
+        let base: Array1<f64> = (0..500).map(|i| (i as f64 * 0.618034).fract()).collect();
+        for &(shift, lower, upper) in [(0.87, 2.829, 3.1913), (1.16, 3.7983, 4.229)].iter()
+        {
+            let cohens_d = effect_sizes::get_cohens_d(&(&base + shift), &base, 0.95).unwrap();
+            assert_eq!(half_away_from_zero(cohens_d.confidence_interval.0, 4), lower);
+            assert_eq!(half_away_from_zero(cohens_d.confidence_interval.1, 4), upper);
+            for effect_size in [cohens_d, effect_sizes::get_hedges_g(&(&base + shift), &base, 0.95).unwrap(), effect_sizes::get_glass_delta(&(&base + shift), &base, 0.95).unwrap()].iter()
+            {
+                assert!(effect_size.confidence_interval.0 < effect_size.estimate && effect_size.estimate < effect_size.confidence_interval.1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dominance_effect_sizes()
+    {
+        let (sample_1, sample_2) = get_sleep_samples();
+
+        let cliffs_delta = effect_sizes::get_cliffs_delta(&sample_1, &sample_2, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(cliffs_delta.estimate, NUM_DECIMAL_DIGITS), -0.49);
+        assert_eq!(half_away_from_zero(cliffs_delta.confidence_interval.0, 4), -0.7931);
+        assert_eq!(half_away_from_zero(cliffs_delta.confidence_interval.1, 4), 0.0076);
+
+        let common_language = effect_sizes::get_common_language_effect_size(&sample_1, &sample_2, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(common_language.estimate, NUM_DECIMAL_DIGITS), 0.255);
+    }
+
+    #[test]
+    fn test_anova_and_ratio_effect_sizes()
+    {
+        let table = analysis_of_variance::get_one_way_anova(&get_anova_groups()).unwrap();
+        assert_eq!(half_away_from_zero(effect_sizes::get_eta_squared(&table, "Between groups").unwrap(), 4), 0.5526);
+        assert_eq!(half_away_from_zero(effect_sizes::get_omega_squared(&table, "Between groups").unwrap(), 4), 0.4787);
+        let partial = effect_sizes::get_partial_eta_squared(&table, "Between groups", 0.95).unwrap();
+        // With a single factor partial eta squared equals eta squared
+        assert_eq!(half_away_from_zero(partial.estimate, 4), 0.5526);
+        assert!(partial.confidence_interval.0 > 0.0 && partial.confidence_interval.1 < 1.0);
+
+        let counts = ContingencyTable::new(ndarray::arr2(&[[20.0, 80.0], [10.0, 90.0]])).unwrap();
+        let odds_ratio = effect_sizes::get_odds_ratio(&counts, 0.95).unwrap();
+        assert_eq!(odds_ratio.estimate, 2.25);
+        assert_eq!(half_away_from_zero(odds_ratio.confidence_interval.0, 4), 0.9943);
+        assert_eq!(half_away_from_zero(odds_ratio.confidence_interval.1, 4), 5.0915);
+
+        let risk_ratio = effect_sizes::get_risk_ratio(&counts, 0.95).unwrap();
+        assert_eq!(risk_ratio.estimate, 2.0);
+        assert_eq!(half_away_from_zero(risk_ratio.confidence_interval.1, 4), 4.0545);
+    }
+
+    #[test]
+    fn test_mean_intervals()
+    {
+        let (sample_1, sample_2) = get_sleep_samples();
+
+        let t_interval = confidence_intervals::get_mean_t_interval(&sample_1, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(t_interval.confidence_interval.0, 4), -0.5298);
+        assert_eq!(half_away_from_zero(t_interval.confidence_interval.1, 4), 2.0298);
+
+        let z_interval = confidence_intervals::get_mean_z_interval(&sample_1, 2.0, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(z_interval.confidence_interval.1, 4), 1.9896);
+
+        let welch = confidence_intervals::get_mean_difference_interval(&sample_1, &sample_2, false, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(welch.estimate, NUM_DECIMAL_DIGITS), -1.58);
+        assert_eq!(half_away_from_zero(welch.confidence_interval.0, 4), -3.3655);
+        assert_eq!(half_away_from_zero(welch.confidence_interval.1, 4), 0.2055);
+
+        let pooled = confidence_intervals::get_mean_difference_interval(&sample_1, &sample_2, true, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(pooled.confidence_interval.0, 4), -3.3639);
+    }
+
+    #[test]
+    fn test_proportion_intervals()
+    {
+        let interval = |method| confidence_intervals::get_proportion_interval(15, 50, method, 0.95).unwrap().confidence_interval;
+
+        let wald = interval(ProportionIntervalMethod::Wald);
+        assert_eq!((half_away_from_zero(wald.0, 4), half_away_from_zero(wald.1, 4)), (0.173, 0.427));
+        let wilson = interval(ProportionIntervalMethod::Wilson);
+        assert_eq!((half_away_from_zero(wilson.0, 4), half_away_from_zero(wilson.1, 4)), (0.191, 0.4375));
+        let agresti_coull = interval(ProportionIntervalMethod::AgrestiCoull);
+        assert_eq!((half_away_from_zero(agresti_coull.0, 4), half_away_from_zero(agresti_coull.1, 4)), (0.1903, 0.4383));
+        let clopper_pearson = interval(ProportionIntervalMethod::ClopperPearson);
+        assert!(clopper_pearson.0 < wilson.0 && clopper_pearson.1 > wilson.1);
+
+        // Wald collapses at the boundary, Wilson does not
+        let boundary = confidence_intervals::get_proportion_interval(0, 20, ProportionIntervalMethod::Wilson, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(boundary.confidence_interval.0, 6), 0.0);
+        assert!(boundary.confidence_interval.1 > 0.1);
+    }
+
+    #[test]
+    fn test_variance_correlation_and_median_intervals()
+    {
+        let (sample_1, sample_2) = get_sleep_samples();
+
+        let variance = confidence_intervals::get_variance_interval(&sample_1, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(variance.confidence_interval.0, 4), 1.5142);
+        assert_eq!(half_away_from_zero(variance.confidence_interval.1, 4), 10.667);
+
+        let ratio = confidence_intervals::get_variance_ratio_interval(&sample_1, &sample_2, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(ratio.estimate, 4), 0.7983);
+        assert_eq!(half_away_from_zero(ratio.confidence_interval.0, 4), 0.1983);
+        assert_eq!(half_away_from_zero(ratio.confidence_interval.1, 4), 3.2141);
+
+        let correlation = confidence_intervals::get_correlation_interval(&sample_1, &sample_2, 0.95).unwrap();
+        assert_eq!(half_away_from_zero(correlation.confidence_interval.0, 4), 0.3315);
+        assert_eq!(half_away_from_zero(correlation.confidence_interval.1, 4), 0.9494);
+
+        let median = confidence_intervals::get_median_interval(&sample_1, 0.95).unwrap();
+        assert_eq!(median.estimate, 0.35);
+        assert_eq!(median.confidence_interval, (-1.2, 3.4));
+    }
+
+    #[test]
+    fn test_exact_binomials()
+    {
+        assert_eq!(counting::get_combinations(40, 20), Ok(137846528820));
+        assert_eq!(counting::get_combinations(10, 0), Ok(1));
+        assert_eq!(counting::get_permutations(10, 0), Ok(1));
+        assert_eq!(counting::get_combinations(3, 5), Err(String::from("r must not be greater than n!")));
+        assert_eq!(counting::get_permutations(3, 5), Err(String::from("r must not be greater than n!")));
+        assert!(counting::get_combinations(200, 100).is_err());
+        assert_eq!(counting::get_checked_factorial(34).unwrap(), utilities::get_factorial(34));
+        assert!(counting::get_checked_factorial(35).is_err());
+    }
+
+    #[test]
+    fn test_big_counting()
+    {
+        let result = counting::get_big_combinations(200, 100).unwrap();
+        assert_eq!(result.to_string(), "90548514656103281165404177077484163874504589675413336841320");
+        assert_eq!(counting::get_big_factorial(50).to_string(), "30414093201713378043612608166064768844377641568960512000000000000");
+        assert_eq!(counting::get_big_permutations(10, 3).unwrap().to_u128(), Some(720));
+        assert_eq!(counting::get_big_factorial(0).to_string(), "1");
+
+        let ln_result = counting::get_ln_combinations(200, 100).unwrap();
+        assert_approx_eq::assert_approx_eq!(ln_result, 135.7532, 1e-4);
+    }
+
+    #[test]
+    fn test_multinomial()
+    {
+        assert_eq!(counting::get_multinomial(&[2, 3, 5]), Ok(2520));
+        assert_eq!(counting::get_multinomial(&[]), Ok(1));
+        assert_approx_eq::assert_approx_eq!(counting::get_ln_multinomial(&[2, 3, 5]), 2520f64.ln(), 1e-10);
+    }
+
+    #[test]
+    fn test_special_counting_numbers()
+    {
+        assert_eq!(counting::get_stirling_first_kind(10, 3), Ok(1172700));
+        assert_eq!(counting::get_stirling_first_kind(10, 0), Ok(0));
+        assert_eq!(counting::get_stirling_second_kind(10, 3), Ok(9330));
+        assert_eq!(counting::get_stirling_second_kind(0, 0), Ok(1));
+        assert_eq!(counting::get_stirling_second_kind(3, 4), Ok(0));
+        assert_eq!(counting::get_bell_number(25), Ok(4638590332229999353));
+        assert_eq!(counting::get_derangements(10), Ok(1334961));
+        assert_eq!(counting::get_derangements(1), Ok(0));
+        assert_eq!(counting::get_catalan_number(10), Ok(16796));
+        assert_eq!(counting::get_catalan_number(65), Ok(1440418573150919668872489894243865350));
+        assert!(counting::get_catalan_number(80).is_err());
+    }
+
+    #[test]
+    fn test_combination_iterators()
+    {
+        let combinations: Vec<Vec<usize>> = counting::CombinationIterator::new(4, 2).collect();
+        assert_eq!(combinations, vec![vec![0, 1], vec![0, 2], vec![0, 3], vec![1, 2], vec![1, 3], vec![2, 3]]);
+        assert_eq!(counting::CombinationIterator::new(10, 4).count(), 210);
+        assert_eq!(counting::CombinationIterator::new(3, 0).count(), 1);
+        assert_eq!(counting::CombinationIterator::new(3, 4).count(), 0);
+
+        let permutations: Vec<Vec<usize>> = counting::PermutationIterator::new(3, 2).collect();
+        assert_eq!(permutations, vec![vec![0, 1], vec![0, 2], vec![1, 0], vec![1, 2], vec![2, 0], vec![2, 1]]);
+        assert_eq!(counting::PermutationIterator::new(6, 6).count(), 720);
+
+        let power_set: Vec<Vec<usize>> = counting::PowerSetIterator::new(3).collect();
+        assert_eq!(power_set, vec![vec![], vec![0], vec![1], vec![2], vec![0, 1], vec![0, 2], vec![1, 2], vec![0, 1, 2]]);
+
+        let multisets: Vec<Vec<usize>> = counting::MultisetCombinationIterator::new(3, 2).collect();
+        assert_eq!(multisets, vec![vec![0, 0], vec![0, 1], vec![0, 2], vec![1, 1], vec![1, 2], vec![2, 2]]);
+        assert_eq!(counting::MultisetCombinationIterator::new(5, 3).count(), 35);
+
+        let product: Vec<Vec<usize>> = counting::CartesianProductIterator::new(&[2, 3]).collect();
+        assert_eq!(product, vec![vec![0, 0], vec![0, 1], vec![0, 2], vec![1, 0], vec![1, 1], vec![1, 2]]);
+        assert_eq!(counting::CartesianProductIterator::new(&[2, 0, 3]).count(), 0);
+    }
+
+    #[test]
+    fn test_lexicographic_ranking()
+    {
+        for (rank, combination) in counting::CombinationIterator::new(9, 4).enumerate()
+        {
+            assert_eq!(counting::get_combination_rank(&combination, 9), Ok(rank as u128));
+            assert_eq!(counting::get_combination_from_rank(9, 4, rank as u128), Ok(combination));
+        }
+        for (rank, permutation) in counting::PermutationIterator::new(6, 3).enumerate()
+        {
+            assert_eq!(counting::get_permutation_rank(&permutation, 6), Ok(rank as u128));
+            assert_eq!(counting::get_permutation_from_rank(6, 3, rank as u128), Ok(permutation));
+        }
+        assert!(counting::get_combination_from_rank(9, 4, 126).is_err());
+        assert!(counting::get_combination_rank(&[2, 1], 9).is_err());
+        assert!(counting::get_permutation_rank(&[1, 1], 6).is_err());
+
+        // Chunks started from a rank continue the full sequence
+        let chunk: Vec<Vec<usize>> = counting::CombinationIterator::from_rank(40, 20, 1000).unwrap().take(3).collect();
+        let expected: Vec<Vec<usize>> = (1000..1003).map(|rank| counting::get_combination_from_rank(40, 20, rank).unwrap()).collect();
+        assert_eq!(chunk, expected);
+        let last = counting::get_combination_from_rank(40, 20, 137846528819).unwrap();
+        assert_eq!(last, (20..40).collect::<Vec<usize>>());
+        assert_eq!(counting::PermutationIterator::from_rank(6, 3, 119).unwrap().count(), 1);
+    }
+
+    fn get_periodic_series() -> TimeSeries
+    {
+        TimeSeries::from_values((0..50).map(|t| 10.0 * (t as f64 / 3.0).sin() + (t % 5) as f64).collect()).unwrap()
+    }
+
+    #[test]
+    fn test_lag_and_difference()
+    {
+        let series = TimeSeries::new(arr1(&[4.0, 9.0, 1.0, 16.0]), arr1(&[3.0, 1.0, 0.0, 2.0])).unwrap();
+        assert_eq!(series.get_values(), &arr1(&[1.0, 9.0, 16.0, 4.0]));
+        assert_eq!(series.get_index(), &arr1(&[0.0, 1.0, 2.0, 3.0]));
+
+        let lagged = series.get_lag(1).unwrap();
+        assert_eq!(lagged.get_values(), &arr1(&[1.0, 9.0, 16.0]));
+        assert_eq!(lagged.get_index(), &arr1(&[1.0, 2.0, 3.0]));
+
+        let differenced = series.get_difference(1, 2).unwrap();
+        assert_eq!(differenced.get_values(), &arr1(&[-1.0, -19.0]));
+        assert_eq!(differenced.get_index(), &arr1(&[2.0, 3.0]));
+        assert_eq!(series.get_difference(2, 1).unwrap().get_values(), &arr1(&[15.0, -5.0]));
+        assert!(series.get_lag(4).is_err());
+        assert!(TimeSeries::new(arr1(&[1.0, 2.0]), arr1(&[1.0])).is_err());
+    }
+
+    #[test]
+    fn test_acf_and_pacf()
+    {
+        let series = get_periodic_series();
+
+        let acf = series.get_acf(6, 0.95).unwrap();
+        assert_eq!(acf.values.iter().map(|&r| half_away_from_zero(r, 4)).collect::<Vec<f64>>(), vec![0.8977, 0.7174, 0.4812, 0.2187, -0.0364, -0.3356]);
+        assert_eq!(acf.confidence_bands.iter().map(|&b| half_away_from_zero(b, 4)).collect::<Vec<f64>>(), vec![0.2772, 0.448, 0.5289, 0.5615, 0.568, 0.5682]);
+
+        let pacf = series.get_pacf(6, 0.95).unwrap();
+        assert_eq!(pacf.values.iter().map(|&r| half_away_from_zero(r, 4)).collect::<Vec<f64>>(), vec![0.8977, -0.4563, -0.3062, -0.2012, -0.1066, -0.6555]);
+        assert_eq!(half_away_from_zero(pacf.confidence_bands[5], 4), 0.2772);
+        assert!(series.get_acf(50, 0.95).is_err());
+    }
+
+    #[test]
+    fn test_portmanteau_tests()
+    {
+        let series = TimeSeries::from_values((0..40).map(|t| ((t * 7) % 11 + (t * t) % 5) as f64).collect()).unwrap();
+
+        let ljung_box = series.get_ljung_box_test(4, 0).unwrap();
+        assert_eq!(half_away_from_zero(ljung_box.statistic, 4), 11.1108);
+        assert_eq!(half_away_from_zero(ljung_box.p_value, 4), 0.0253);
+        assert_eq!(ljung_box.degrees_of_freedom, vec![4.0]);
+
+        let box_pierce = series.get_box_pierce_test(4, 0).unwrap();
+        assert_eq!(half_away_from_zero(box_pierce.statistic, 4), 9.9032);
+        assert_eq!(half_away_from_zero(box_pierce.p_value, 4), 0.0421);
+
+        let residual_test = series.get_ljung_box_test(4, 2).unwrap();
+        assert_eq!(half_away_from_zero(residual_test.p_value, 4), 0.0039);
+        assert!(series.get_ljung_box_test(4, 4).is_err());
+    }
+
+    #[test]
+    fn test_resampling()
+    {
+        let data = file_reading::read_csv_to_array("src/datasets/unique_passengers.csv", false).unwrap();
+        let series = TimeSeries::from_columns(&data, 0, 1).unwrap();
+
+        let counts = series.get_resampled(1.0, Aggregation::Count).unwrap();
+        assert_eq!(counts.get_index(), &arr1(&[2015.0, 2016.0, 2017.0, 2018.0, 2019.0, 2020.0]));
+        assert_eq!(counts.get_values(), &arr1(&[282.0, 318.0, 357.0, 348.0, 397.0, 392.0]));
+
+        let sums = series.get_resampled(1.0, Aggregation::Sum).unwrap();
+        assert_eq!(sums.get_values()[0], 2137455.0);
+        let maxima = series.get_resampled(1.0, Aggregation::Max).unwrap();
+        assert_eq!(maxima.get_values()[4], 238975.0);
+
+        let two_year_counts = series.get_resampled(2.0, Aggregation::Count).unwrap();
+        assert_eq!(two_year_counts.get_index(), &arr1(&[2014.0, 2016.0, 2018.0, 2020.0]));
+        assert_eq!(two_year_counts.get_values(), &arr1(&[282.0, 675.0, 745.0, 392.0]));
+    }
+
+    fn get_seasonal_series() -> TimeSeries
+    {
+        let values = (0..24).map(|t| 100.0 + 2.0 * t as f64 + 10.0 * (std::f64::consts::FRAC_PI_2 * t as f64).sin() + ((t * 7) % 5) as f64 - 2.0);
+        TimeSeries::from_values(values.collect()).unwrap()
+    }
+
+    #[test]
+    fn test_moving_averages()
+    {
+        let series = TimeSeries::from_values(arr1(&[1.0, 2.0, 4.0, 8.0, 16.0])).unwrap();
+
+        let simple = smoothing::get_simple_moving_average(&series, 3).unwrap();
+        assert_eq!(simple.get_values().iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![2.3333, 4.6667, 9.3333]);
+        assert_eq!(simple.get_index(), &arr1(&[2.0, 3.0, 4.0]));
+
+        let weighted = smoothing::get_weighted_moving_average(&series, &arr1(&[1.0, 3.0])).unwrap();
+        assert_eq!(weighted.get_values(), &arr1(&[1.75, 3.5, 7.0, 14.0]));
+
+        let centered = smoothing::get_centered_moving_average(&series, 2).unwrap();
+        assert_eq!(centered.get_values(), &arr1(&[2.25, 4.5, 9.0]));
+        assert_eq!(centered.get_index(), &arr1(&[1.0, 2.0, 3.0]));
+
+        let ewma = smoothing::get_ewma(&series, 0.5).unwrap();
+        assert_eq!(ewma.get_values(), &arr1(&[1.0, 1.5, 2.75, 5.375, 10.6875]));
+        assert!(smoothing::get_simple_moving_average(&series, 6).is_err());
+    }
+
+    #[test]
+    fn test_holt_winters_with_given_parameters()
+    {
+        let series = get_seasonal_series();
+        let model = ExponentialSmoothing::new(TrendKind::Additive, SeasonalKind::Additive, 4).unwrap();
+        let parameters = SmoothingParameters { alpha: 0.4, beta: Some(0.2), gamma: Some(0.3), phi: None };
+        let fit = model.fit_with_parameters(&series, parameters).unwrap();
+        assert_eq!(half_away_from_zero(fit.sse, 4), 123.0649);
+        assert_eq!(fit.fitted_values.len(), 20);
+
+        let forecast = fit.get_forecast(5, 0.95).unwrap();
+        assert_eq!(forecast.mean.iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![147.2731, 159.9965, 152.6297, 143.5308, 155.1975]);
+        assert_eq!(forecast.standard_errors.iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![2.6906, 2.9845, 3.3432, 3.7606, 4.4728]);
+        assert_approx_eq::assert_approx_eq!(forecast.upper[0] - forecast.mean[0], 1.959964 * forecast.standard_errors[0], 1e-5);
+
+        let positive_series = TimeSeries::from_values((0..24).map(|t| (50.0 + t as f64) * (1.0 + 0.2 * [1.0, -1.0, 0.5, -0.5][t % 4]) + ((t * 3) % 4) as f64 - 1.5).collect()).unwrap();
+        let multiplicative = ExponentialSmoothing::new(TrendKind::Additive, SeasonalKind::Multiplicative, 4).unwrap();
+        let parameters = SmoothingParameters { alpha: 0.5, beta: Some(0.1), gamma: Some(0.2), phi: None };
+        let forecast = multiplicative.fit_with_parameters(&positive_series, parameters).unwrap().get_forecast(3, 0.95).unwrap();
+        assert_eq!(forecast.mean.iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![86.7319, 61.9486, 84.3041]);
+        assert!(multiplicative.fit(&TimeSeries::from_values(arr1(&[1.0, -1.0, 2.0, 3.0, 1.0, 2.0, 3.0, 4.0, 5.0])).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_exponential_smoothing_fitting()
+    {
+        let series = TimeSeries::from_values((0..30).map(|t| 10.0 + ((t * 7) % 11) as f64 * 0.5 + 0.3 * t as f64).collect()).unwrap();
+
+        // Grid search over alpha gives 136.5008 at alpha = 0.424
+        let simple = ExponentialSmoothing::new(TrendKind::None, SeasonalKind::None, 1).unwrap().fit(&series).unwrap();
+        assert_eq!(half_away_from_zero(simple.parameters.alpha, 3), 0.424);
+        assert!(simple.sse <= 136.5008);
+        let forecast = simple.get_forecast(3, 0.95).unwrap();
+        assert_eq!(forecast.mean[0], forecast.mean[2]);
+        assert!(forecast.standard_errors[2] > forecast.standard_errors[0]);
+
+        let damped = ExponentialSmoothing::new(TrendKind::DampedAdditive, SeasonalKind::None, 1).unwrap().fit(&series).unwrap();
+        let phi = damped.parameters.phi.unwrap();
+        assert!((0.8..=0.98).contains(&phi));
+        let forecast = damped.get_forecast(3, 0.95).unwrap();
+        assert_approx_eq::assert_approx_eq!(forecast.mean[2] - forecast.mean[1], phi * (forecast.mean[1] - forecast.mean[0]), 1e-10);
+
+        // A grid with step 0.02 over all three parameters reaches 79.0603
+        let seasonal = ExponentialSmoothing::new(TrendKind::Additive, SeasonalKind::Additive, 4).unwrap().fit(&get_seasonal_series()).unwrap();
+        assert!(seasonal.sse <= 79.0603);
+        assert!(ExponentialSmoothing::new(TrendKind::None, SeasonalKind::Additive, 1).is_err());
+    }
+
+    fn get_arma_noise(t: usize) -> f64
+    {
+        ((t * 37 + 11) % 101) as f64 / 101.0 - 0.5 + ((t * t * 13) % 17) as f64 / 17.0 - 0.5
+    }
+
+    // ARMA(1, 1) with phi = 0.6, theta = 0.3 and mean 5
+    fn get_arma_series() -> TimeSeries
+    {
+        let mut values = Vec::new();
+        let (mut previous_value, mut previous_noise) = (0.0, 0.0);
+        for t in 0..80
+        {
+            let noise = get_arma_noise(t);
+            previous_value = 0.6 * previous_value + noise + 0.3 * previous_noise;
+            previous_noise = noise;
+            values.push(previous_value + 5.0);
+        }
+        TimeSeries::from_values(Array1::from(values)).unwrap()
+    }
+
+    fn get_random_walk() -> TimeSeries
+    {
+        let mut level = 0.0;
+        TimeSeries::from_values((0..100).map(|t| { level += get_arma_noise(t) + 0.05; level }).collect()).unwrap()
+    }
+
+    #[test]
+    fn test_stationarity_tests()
+    {
+        let (stationary, random_walk) = (get_arma_series(), get_random_walk());
+
+        let adf = stationarity_tests::get_adf_test(&stationary, None, DeterministicTerms::Constant).unwrap();
+        assert_eq!(adf.lags, 4);
+        assert_eq!(half_away_from_zero(adf.test_result.statistic, 4), -4.0973);
+        assert_eq!(half_away_from_zero(adf.test_result.p_value, 4), 0.001);
+        assert_eq!(adf.critical_values.iter().map(|&(_, c)| half_away_from_zero(c, 4)).collect::<Vec<f64>>(), vec![-3.5207, -2.9009, -2.5878]);
+
+        let adf = stationarity_tests::get_adf_test(&random_walk, Some(4), DeterministicTerms::Constant).unwrap();
+        assert_eq!(half_away_from_zero(adf.test_result.statistic, 4), -1.554);
+        assert_eq!(half_away_from_zero(adf.test_result.p_value, 4), 0.5067);
+        let adf = stationarity_tests::get_adf_test(&random_walk, Some(4), DeterministicTerms::ConstantAndTrend).unwrap();
+        assert_eq!(half_away_from_zero(adf.test_result.statistic, 4), -3.6855);
+        assert_eq!(half_away_from_zero(adf.test_result.p_value, 4), 0.0233);
+
+        let kpss = stationarity_tests::get_kpss_test(&stationary, None, DeterministicTerms::Constant).unwrap();
+        assert_eq!(kpss.lags, 2);
+        assert_eq!(half_away_from_zero(kpss.test_result.statistic, 4), 0.1076);
+        assert_eq!(kpss.test_result.p_value, 0.1);
+        let kpss = stationarity_tests::get_kpss_test(&random_walk, None, DeterministicTerms::Constant).unwrap();
+        assert_eq!(half_away_from_zero(kpss.test_result.statistic, 4), 2.472);
+        assert_eq!(kpss.test_result.p_value, 0.01);
+        let kpss = stationarity_tests::get_kpss_test(&random_walk, None, DeterministicTerms::ConstantAndTrend).unwrap();
+        assert_eq!(half_away_from_zero(kpss.test_result.statistic, 4), 0.1428);
+        assert_eq!(half_away_from_zero(kpss.test_result.p_value, 4), 0.056);
+
+        for test in [StationarityTest::Kpss, StationarityTest::AugmentedDickeyFuller].iter()
+        {
+            assert_eq!(stationarity_tests::get_differencing_order(&stationary, 2, *test, 0.05), Ok(0));
+            assert_eq!(stationarity_tests::get_differencing_order(&random_walk, 2, *test, 0.05), Ok(1));
+        }
+    }
+
+    #[test]
+    fn test_adf_lag_selection()
+    {
+        let (stationary, random_walk) = (get_arma_series(), get_random_walk());
+        let aic = stationarity_tests::get_adf_test_with_lag_selection(&random_walk, None, DeterministicTerms::Constant, InformationCriterion::Aic).unwrap();
+        assert_eq!(aic.lags, 12);
+        assert_eq!(half_away_from_zero(aic.test_result.statistic, 4), -0.7537);
+        assert_eq!(half_away_from_zero(aic.test_result.p_value, 4), 0.8324);
+        let bic = stationarity_tests::get_adf_test_with_lag_selection(&random_walk, None, DeterministicTerms::Constant, InformationCriterion::Bic).unwrap();
+        assert_eq!(bic.lags, 1);
+        assert_eq!(half_away_from_zero(bic.test_result.statistic, 4), -2.2122);
+        assert_eq!(half_away_from_zero(bic.test_result.p_value, 4), 0.2018);
+        let bic = stationarity_tests::get_adf_test_with_lag_selection(&random_walk, None, DeterministicTerms::ConstantAndTrend, InformationCriterion::Bic).unwrap();
+        assert_eq!(bic.lags, 0);
+        assert_eq!(half_away_from_zero(bic.test_result.statistic, 4), -5.7585);
+
+        let aic = stationarity_tests::get_adf_test_with_lag_selection(&stationary, None, DeterministicTerms::ConstantAndTrend, InformationCriterion::Aic).unwrap();
+        assert_eq!(aic.lags, 11);
+        assert_eq!(half_away_from_zero(aic.test_result.statistic, 4), -4.0273);
+        assert_eq!(half_away_from_zero(aic.test_result.p_value, 4), 0.008);
+        assert!(stationarity_tests::get_adf_test_with_lag_selection(&stationary, Some(70), DeterministicTerms::Constant, InformationCriterion::Aic).is_err());
+
+        // Schwert's default of 8 lags does not fit 20 observations and is capped at 7, or 6 with a trend
+        let short = TimeSeries::from_values(random_walk.get_values().slice(s![..20]).to_owned()).unwrap();
+        let aic = stationarity_tests::get_adf_test_with_lag_selection(&short, None, DeterministicTerms::Constant, InformationCriterion::Aic).unwrap();
+        assert!(aic.lags <= 7);
+        assert!(stationarity_tests::get_adf_test_with_lag_selection(&short, None, DeterministicTerms::ConstantAndTrend, InformationCriterion::Bic).is_ok());
+        assert!(stationarity_tests::get_adf_test_with_lag_selection(&short, Some(8), DeterministicTerms::Constant, InformationCriterion::Aic).is_err());
+    }
+
+    #[test]
+    fn test_phillips_perron_test()
+    {
+        let (stationary, random_walk) = (get_arma_series(), get_random_walk());
+        let pp = stationarity_tests::get_phillips_perron_test(&stationary, None, DeterministicTerms::Constant).unwrap();
+        assert_eq!(pp.lags, 3);
+        assert_eq!(half_away_from_zero(pp.test_result.statistic, 4), -5.5852);
+        assert!(pp.test_result.p_value < 1e-5);
+        assert_eq!(pp.critical_values.iter().map(|&(level, _)| level).collect::<Vec<f64>>(), vec![0.01, 0.05, 0.1]);
+
+        let pp = stationarity_tests::get_phillips_perron_test(&random_walk, None, DeterministicTerms::Constant).unwrap();
+        assert_eq!(pp.lags, 4);
+        assert_eq!(half_away_from_zero(pp.test_result.statistic, 4), -3.0362);
+        assert_eq!(half_away_from_zero(pp.test_result.p_value, 4), 0.0317);
+        let pp = stationarity_tests::get_phillips_perron_test(&random_walk, None, DeterministicTerms::ConstantAndTrend).unwrap();
+        assert_eq!(half_away_from_zero(pp.test_result.statistic, 4), -5.8427);
+
+        assert_eq!(stationarity_tests::get_differencing_order(&stationary, 2, StationarityTest::PhillipsPerron, 0.05), Ok(0));
+        assert!(stationarity_tests::get_phillips_perron_test(&stationary, Some(79), DeterministicTerms::Constant).is_err());
+    }
+
+    #[test]
+    fn test_arima_likelihoods()
+    {
+        // Exact values from the inverse of the full ARMA(1, 1) autocovariance matrix
+        let series = get_arma_series();
+        let coefficients = arr1(&[0.5, 0.2, 4.9]);
+        let exact = Arima::new(1, 0, 1, ArimaMethod::MaximumLikelihood).fit_with_coefficients(&series, &coefficients).unwrap();
+        assert_eq!(half_away_from_zero(exact.log_likelihood, 6), -42.922591);
+        assert_eq!(half_away_from_zero(exact.sigma_squared, 6), 0.170111);
+        assert_eq!(exact.coefficient_names, vec!["ar1", "ma1", "mean"]);
+
+        let conditional = Arima::new(1, 0, 1, ArimaMethod::ConditionalSumOfSquares).fit_with_coefficients(&series, &coefficients).unwrap();
+        assert_eq!(half_away_from_zero(conditional.log_likelihood, 6), -41.517095);
+        assert_eq!(conditional.residuals.len(), 79);
+        assert!(Arima::new(1, 0, 1, ArimaMethod::MaximumLikelihood).fit_with_coefficients(&series, &arr1(&[0.5])).is_err());
+    }
+
+    #[test]
+    fn test_arima_fitting_and_forecasting()
+    {
+        let series = get_arma_series();
+        let fit = Arima::new(1, 0, 1, ArimaMethod::MaximumLikelihood).fit(&series).unwrap();
+        // A grid search over the three coefficients reaches -40.1016
+        assert!(fit.log_likelihood >= -40.1016);
+        assert_approx_eq::assert_approx_eq!(fit.aic, -2.0 * fit.log_likelihood + 8.0, 1e-10);
+        assert_approx_eq::assert_approx_eq!(fit.bic, -2.0 * fit.log_likelihood + 4.0 * 80f64.ln(), 1e-10);
+        assert!(fit.standard_errors.iter().all(|&error| error > 0.0 && error < 0.5));
+
+        let (phi, theta, mean) = (fit.coefficients[0], fit.coefficients[1], fit.coefficients[2]);
+        let forecast = fit.get_forecast(3, 0.95).unwrap();
+        assert_approx_eq::assert_approx_eq!(forecast.mean[1] - mean, phi * (forecast.mean[0] - mean), 1e-10);
+        assert_approx_eq::assert_approx_eq!(forecast.standard_errors[0], fit.sigma_squared.sqrt(), 1e-10);
+        assert_approx_eq::assert_approx_eq!(forecast.standard_errors[1], (fit.sigma_squared * (1.0 + (phi + theta).powi(2))).sqrt(), 1e-10);
+
+        let diagnostics = fit.get_residual_diagnostics(10).unwrap();
+        assert_eq!(diagnostics.ljung_box.degrees_of_freedom, vec![8.0]);
+        assert!(diagnostics.ljung_box.p_value > 0.05);
+
+        let conditional = Arima::new(1, 0, 1, ArimaMethod::ConditionalSumOfSquares).fit(&series).unwrap();
+        assert!(conditional.log_likelihood >= -41.5171);
+    }
+
+    #[test]
+    fn test_differenced_arima_forecasts()
+    {
+        let random_walk = get_random_walk();
+        let values = random_walk.get_values();
+        let fit = Arima::new(0, 1, 0, ArimaMethod::ConditionalSumOfSquares).fit(&random_walk).unwrap();
+        assert_eq!(half_away_from_zero(fit.sigma_squared, 6), 0.190287);
+        let forecast = fit.get_forecast(4, 0.95).unwrap();
+        assert_eq!(forecast.mean, Array1::from_elem(4, values[99]));
+        assert_approx_eq::assert_approx_eq!(forecast.standard_errors[3], 2.0 * fit.sigma_squared.sqrt(), 1e-10);
+
+        // Seasonal naive forecasts repeat the last season
+        let seasonal = Arima::new(0, 0, 0, ArimaMethod::MaximumLikelihood).with_seasonal_order(0, 1, 0, 4).unwrap().fit(&random_walk).unwrap();
+        let forecast = seasonal.get_forecast(6, 0.95).unwrap();
+        assert_eq!(forecast.mean.to_vec(), vec![values[96], values[97], values[98], values[99], values[96], values[97]]);
+        assert!(Arima::new(0, 1, 0, ArimaMethod::MaximumLikelihood).with_mean(true).is_err());
+    }
+
+    #[test]
+    fn test_auto_arima()
+    {
+        let fit = AutoArima::new(InformationCriterion::Aic, ArimaMethod::MaximumLikelihood).with_max_order(2, 2, 2).fit(&get_arma_series()).unwrap();
+        assert_eq!(fit.coefficient_names.last().unwrap(), "mean");
+        let manual = Arima::new(1, 0, 1, ArimaMethod::MaximumLikelihood).fit(&get_arma_series()).unwrap();
+        assert!(fit.aic <= manual.aic);
+
+        let fit = AutoArima::new(InformationCriterion::Bic, ArimaMethod::ConditionalSumOfSquares).with_max_order(1, 2, 1).fit(&get_random_walk()).unwrap();
+        assert_eq!(fit.residuals.len() + 1 + fit.coefficient_names.iter().filter(|name| name.starts_with("ar")).count(), 100);
+        assert!(!fit.coefficient_names.contains(&String::from("mean")));
+    }
+
+    #[test]
+    fn test_classical_decomposition()
+    {
+        let series = get_seasonal_series();
+        let additive = decomposition::get_classical_decomposition(&series, 4, DecompositionKind::Additive).unwrap();
+        assert!(additive.trend[1].is_nan() && additive.trend[22].is_nan());
+        assert_eq!(half_away_from_zero(additive.trend[2], 4), 104.125);
+        assert_eq!(additive.seasonal.slice(s![..4]).iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![0.0, 10.0, 0.0, -10.0]);
+        assert_eq!(additive.remainder.slice(s![2..6]).iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![1.875, -1.25, 1.25, -1.875]);
+        assert_eq!(half_away_from_zero(additive.seasonal_strength, 4), 0.961);
+
+        let multiplicative = decomposition::get_classical_decomposition(&series, 4, DecompositionKind::Multiplicative).unwrap();
+        assert_eq!(multiplicative.seasonal.slice(s![..4]).iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![1.001, 1.0801, 1.0011, 0.9177]);
+        assert_eq!(half_away_from_zero(multiplicative.remainder[2], 4), 1.0169);
+        assert_eq!(half_away_from_zero(multiplicative.seasonal_strength, 4), 0.9536);
+
+        assert!(decomposition::get_classical_decomposition(&series, 13, DecompositionKind::Additive).is_err());
+        let negative = TimeSeries::from_values(arr1(&[1.0, -1.0, 2.0, 3.0])).unwrap();
+        assert!(decomposition::get_classical_decomposition(&negative, 2, DecompositionKind::Multiplicative).is_err());
+    }
+
+    #[test]
+    fn test_stl_decomposition()
+    {
+        let series = get_seasonal_series();
+        let stl = decomposition::Stl::new(4, 7).unwrap().decompose(&series).unwrap();
+        let reconstructed = &stl.trend + &stl.seasonal + &stl.remainder;
+        for (a, b) in reconstructed.iter().zip(series.get_values().iter())
+        {
+            assert_approx_eq::assert_approx_eq!(a, b, 1e-9);
+        }
+        // Linear trend and a sine of amplitude 10 are recovered
+        for t in 0..24
+        {
+            assert_approx_eq::assert_approx_eq!(stl.trend[t], 100.0 + 2.0 * t as f64, 2.0);
+            assert_approx_eq::assert_approx_eq!(stl.seasonal[t], 10.0 * (std::f64::consts::FRAC_PI_2 * t as f64).sin(), 1.5);
+        }
+        assert!(stl.seasonal_strength > 0.95);
+
+        assert!(decomposition::Stl::new(4, 6).is_err());
+        assert!(decomposition::Stl::new(4, 7).unwrap().with_trend_window(4).is_err());
+        let wide_trend = decomposition::Stl::new(4, 7).unwrap().with_trend_window(23).unwrap().decompose(&series).unwrap();
+        assert_eq!(wide_trend.trend.len(), 24);
+    }
+
+    #[test]
+    fn test_robust_stl()
+    {
+        let mut values = get_seasonal_series().get_values().clone();
+        values[13] += 50.0;
+        let series = TimeSeries::from_values(values).unwrap();
+        let plain = decomposition::Stl::new(4, 7).unwrap().decompose(&series).unwrap();
+        let robust = decomposition::Stl::new(4, 7).unwrap().with_robust_iterations(true).decompose(&series).unwrap();
+        // The robust fit leaves the outlier in the remainder instead of the seasonal and trend components
+        assert!(robust.remainder[13] > 45.0 && plain.remainder[13] < 30.0);
+        assert_approx_eq::assert_approx_eq!(robust.seasonal[13], 10.0, 1.5);
+        assert_approx_eq::assert_approx_eq!(robust.trend[13], 126.0, 1.5);
+    }
+
+    #[test]
+    fn test_singular_value_decomposition()
+    {
+        let a = ndarray::arr2(&[[3.0, 2.0, 2.0], [2.0, 3.0, -2.0]]).t().to_owned();
+        let svd = linear_algebra::get_singular_value_decomposition(&a).unwrap();
+        let (u, singular_values, v) = (svd.u, svd.singular_values, svd.v);
+        assert_eq!(singular_values.iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![5.0, 3.0]);
+        let reconstructed = linear_algebra::get_matrix_product(&(&u * &singular_values), &v.t().to_owned()).unwrap();
+        for (x, y) in reconstructed.iter().zip(a.iter())
+        {
+            assert_approx_eq::assert_approx_eq!(x, y, 1e-12);
+        }
+        let gram = linear_algebra::get_matrix_product(&u.t().to_owned(), &u).unwrap();
+        assert_approx_eq::assert_approx_eq!(gram[[0, 1]], 0.0, 1e-12);
+        assert_approx_eq::assert_approx_eq!(gram[[1, 1]], 1.0, 1e-12);
+    }
+
+    #[test]
+    fn test_pca_on_banknotes()
+    {
+        let data = file_reading::read_csv_to_array("src/datasets/data_banknote_authentication.csv", false).unwrap();
+        let features = data.slice(s![.., ..4]).to_owned();
+        let pca = PrincipalComponentAnalysis::new(true, false).fit(&features).unwrap();
+        assert_eq!(pca.explained_variance.iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![49.8801, 9.2973, 4.3908, 1.9494]);
+        assert_eq!(pca.cumulative_variance_ratio.iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![0.7613, 0.9032, 0.9702, 1.0]);
+        assert_eq!(pca.loadings.column(0).iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![0.141, 0.8104, -0.5497, -0.1455]);
+        assert_eq!(pca.scores.row(0).iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![4.9026, 0.1841, -3.7768, -2.1716]);
+
+        let scores = pca.transform(&features.slice(s![..5, ..]).to_owned()).unwrap();
+        assert_approx_eq::assert_approx_eq!(scores[[4, 2]], pca.scores[[4, 2]], 1e-10);
+        let reconstructed = pca.inverse_transform(&pca.scores).unwrap();
+        assert_approx_eq::assert_approx_eq!(reconstructed[[7, 3]], features[[7, 3]], 1e-10);
+        // The squared error of a rank two reconstruction is the variance of the dropped components
+        let truncated = pca.inverse_transform(&pca.scores.slice(s![.., ..2]).to_owned()).unwrap();
+        let squared_error = (&truncated - &features).mapv(|x| x * x).sum() / (features.nrows() as f64 - 1.0);
+        assert_approx_eq::assert_approx_eq!(squared_error, pca.explained_variance[2] + pca.explained_variance[3], 1e-8);
+        assert!(pca.inverse_transform(&Array2::zeros((1, 5))).is_err());
+        assert!(pca.transform(&data).is_err());
+    }
+
+    #[test]
+    fn test_scaled_pca_on_fish()
+    {
+        let data = file_reading::read_csv_to_array("src/datasets/Fish.csv", false).unwrap();
+        let pca = PrincipalComponentAnalysis::new(true, true).fit(&data).unwrap();
+        assert_eq!(pca.explained_variance.iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![5.2698, 0.5105, 0.1203, 0.0975, 0.0016, 0.0003]);
+        assert_eq!(half_away_from_zero(pca.explained_variance_ratio[0], 4), 0.8783);
+        assert_eq!(pca.loadings.column(0).iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![0.4173, 0.4215, 0.4234, 0.4277, 0.3427, 0.4105]);
+        assert_eq!(half_away_from_zero(pca.scores[[0, 1]], 4), 0.6803);
+        // Eigenvalues of a correlation matrix add up to the number of variables
+        assert_approx_eq::assert_approx_eq!(pca.explained_variance.sum(), 6.0, 1e-10);
+    }
+
+    #[test]
+    fn test_pca_component_criteria()
+    {
+        let fish = file_reading::read_csv_to_array("src/datasets/Fish.csv", false).unwrap();
+        let banknotes = file_reading::read_csv_to_array("src/datasets/data_banknote_authentication.csv", false).unwrap();
+        let parallel_analysis = ComponentCriterion::ParallelAnalysis { num_simulations: 20, seed: 7 };
+        let pca = PrincipalComponentAnalysis::new(true, true).fit(&fish).unwrap();
+        assert_eq!(pca.get_num_components(ComponentCriterion::Kaiser), Ok(1));
+        assert_eq!(pca.get_num_components(parallel_analysis), Ok(1));
+        let pca = PrincipalComponentAnalysis::new(true, true).fit(&banknotes.slice(s![.., ..4]).to_owned()).unwrap();
+        assert_eq!(pca.get_num_components(ComponentCriterion::Kaiser), Ok(2));
+        assert_eq!(pca.get_num_components(parallel_analysis), Ok(2));
+        assert!(pca.get_num_components(ComponentCriterion::ParallelAnalysis { num_simulations: 0, seed: 7 }).is_err());
+
+        let constant = ndarray::arr2(&[[1.0, 2.0], [1.0, 3.0], [1.0, 5.0]]);
+        assert!(PrincipalComponentAnalysis::new(true, true).fit(&constant).is_err());
+        assert!(PrincipalComponentAnalysis::new(true, false).fit(&constant).is_ok());
+    }
+
+    // Two correlated factors with simple structure, R = L Phi L^T off the diagonal
+    fn get_two_factor_correlation(factor_correlation: f64) -> Array2<f64>
+    {
+        let loadings = ndarray::arr2(&[[0.8, 0.0], [0.7, 0.0], [0.6, 0.0], [0.0, 0.75], [0.0, 0.6], [0.0, 0.5]]);
+        let phi = ndarray::arr2(&[[1.0, factor_correlation], [factor_correlation, 1.0]]);
+        let mut correlation = linear_algebra::get_matrix_product(&linear_algebra::get_matrix_product(&loadings, &phi).unwrap(), &loadings.t().to_owned()).unwrap();
+        correlation.diag_mut().fill(1.0);
+        correlation
+    }
+
+    // Not exactly two-factor
+    fn get_perturbed_two_factor_correlation() -> Array2<f64>
+    {
+        let mut correlation = get_two_factor_correlation(0.3);
+        for &(i, j, e) in [(0, 1, 0.05), (0, 2, -0.04), (0, 3, 0.03), (1, 4, 0.04), (2, 3, 0.05), (3, 5, -0.04)].iter()
+        {
+            correlation[[i, j]] += e;
+            correlation[[j, i]] += e;
+        }
+        correlation
+    }
+
+    fn get_fish_correlation() -> Array2<f64>
+    {
+        let data = file_reading::read_csv_to_array("src/datasets/Fish.csv", false).unwrap();
+        utilities::get_correlation_coefficient_matrix(&data).unwrap()
+    }
+
+    #[test]
+    fn test_factor_extraction()
+    {
+        let correlation = get_two_factor_correlation(0.3);
+        for extraction in [FactorExtraction::MaximumLikelihood, FactorExtraction::PrincipalAxis].iter()
+        {
+            let solution = FactorAnalysis::new(2, *extraction).fit(&correlation).unwrap();
+            assert_eq!(solution.communalities.iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![0.64, 0.49, 0.36, 0.5625, 0.36, 0.25]);
+            assert_eq!(solution.factor_correlations, Array2::eye(2));
+        }
+
+        // The uniquenesses minimize the maximum likelihood discrepancy
+        let solution = FactorAnalysis::new(2, FactorExtraction::MaximumLikelihood).fit(&get_perturbed_two_factor_correlation()).unwrap();
+        assert_eq!(solution.uniquenesses.iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![0.3595, 0.4191, 0.6946, 0.4826, 0.6077, 0.7785]);
+
+        // Principal axis communalities of the fish measurements drift above one
+        assert!(FactorAnalysis::new(2, FactorExtraction::PrincipalAxis).fit(&get_fish_correlation()).is_err());
+        assert!(FactorAnalysis::new(6, FactorExtraction::PrincipalAxis).fit(&correlation).is_err());
+        assert!(FactorAnalysis::new(1, FactorExtraction::PrincipalAxis).fit(&Array2::zeros((3, 3))).is_err());
+    }
+
+    #[test]
+    fn test_factor_rotations()
+    {
+        let correlation = get_perturbed_two_factor_correlation();
+        let fit = |rotation: Rotation| FactorAnalysis::new(2, FactorExtraction::PrincipalAxis).with_rotation(rotation).fit(&correlation).unwrap();
+        let unrotated = fit(Rotation::None);
+        let round_column = |solution: &factor_analysis::FactorSolution, k: usize| solution.loadings.column(k).iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>();
+        assert_eq!(round_column(&unrotated, 1), vec![-0.3282, -0.3257, -0.1941, 0.5075, 0.4666, 0.3495]);
+
+        let varimax = fit(Rotation::Varimax);
+        assert_eq!(round_column(&varimax, 0), vec![0.7904, 0.7542, 0.5393, 0.1437, 0.0981, 0.0733]);
+        let quartimax = fit(Rotation::Quartimax);
+        assert_eq!(round_column(&quartimax, 1), vec![0.1259, 0.1051, 0.1215, 0.7011, 0.6222, 0.466]);
+        let promax = fit(Rotation::Promax { power: 4.0 });
+        assert_eq!(round_column(&promax, 1), vec![0.0009, -0.0149, 0.038, 0.7116, 0.6364, 0.4767]);
+        assert_eq!(half_away_from_zero(promax.factor_correlations[[0, 1]], 4), 0.3367);
+        let oblimin = fit(Rotation::Oblimin { gamma: 0.0 });
+        assert_eq!(round_column(&oblimin, 0), vec![0.8039, 0.7698, 0.542, 0.0241, -0.0094, -0.0072]);
+        assert_eq!(half_away_from_zero(oblimin.factor_correlations[[1, 0]], 4), 0.3344);
+
+        // Rotations redistribute but keep the communalities
+        for solution in [varimax, quartimax, promax, oblimin].iter()
+        {
+            for (a, b) in solution.communalities.iter().zip(unrotated.communalities.iter())
+            {
+                assert_approx_eq::assert_approx_eq!(a, b, 1e-8);
+            }
+        }
+
+        // Quartimin recovers a simple structure with correlated factors
+        let solution = FactorAnalysis::new(2, FactorExtraction::MaximumLikelihood).with_rotation(Rotation::Oblimin { gamma: 0.0 }).fit(&get_two_factor_correlation(0.3)).unwrap();
+        assert_eq!(round_column(&solution, 0), vec![0.8, 0.7, 0.6, 0.0, 0.0, 0.0]);
+        assert_eq!(round_column(&solution, 1), vec![0.0, 0.0, 0.0, 0.75, 0.6, 0.5]);
+        assert_eq!(half_away_from_zero(solution.factor_correlations[[0, 1]], 4), 0.3);
+        assert!(FactorAnalysis::new(2, FactorExtraction::PrincipalAxis).with_rotation(Rotation::Promax { power: 1.0 }).fit(&correlation).is_err());
+    }
+
+    #[test]
+    fn test_kmo_and_bartlett_sphericity()
+    {
+        let banknotes = file_reading::read_csv_to_array("src/datasets/data_banknote_authentication.csv", false).unwrap();
+        let correlation = utilities::get_correlation_coefficient_matrix(&banknotes).unwrap();
+        let kmo = factor_analysis::get_kmo(&correlation).unwrap();
+        assert_eq!(half_away_from_zero(kmo.overall, 4), 0.5518);
+        assert_eq!(kmo.variables.iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![0.4726, 0.5604, 0.6206, 0.4855]);
+        let bartlett = factor_analysis::get_bartlett_sphericity_test(&correlation, banknotes.nrows()).unwrap();
+        assert_eq!(half_away_from_zero(bartlett.statistic, 2), 2394.5);
+        assert_eq!(bartlett.degrees_of_freedom, vec![6.0]);
+        assert!(bartlett.p_value < 1e-10);
+
+        let kmo = factor_analysis::get_kmo(&get_fish_correlation()).unwrap();
+        assert_eq!(half_away_from_zero(kmo.overall, 4), 0.782);
+        // Uncorrelated variables cannot be distinguished from sphericity
+        let bartlett = factor_analysis::get_bartlett_sphericity_test(&Array2::eye(3), 50).unwrap();
+        assert_eq!(bartlett.statistic, 0.0);
+        assert_eq!(bartlett.p_value, 1.0);
+        assert!(factor_analysis::get_bartlett_sphericity_test(&correlation, 3).is_err());
+    }
+
+    // Three well separated groups of twenty points around (0, 0), (6, 0) and (0, 6), point i belongs to group i % 3
+    fn get_blobs() -> Array2<f64>
+    {
+        Array2::from_shape_fn((60, 2), |(i, j)|
+        {
+            let center = if i % 3 == j + 1 { 6.0 } else { 0.0 };
+            center + ((i * (7 + 4 * j) + 3 * j) % 11) as f64 / 11.0 - 0.5
+        })
+    }
+
+    #[test]
+    fn test_kmeans()
+    {
+        let blobs = get_blobs();
+        let clustering = KMeans::new(3, 1).fit(&blobs).unwrap();
+        assert!((0..60).all(|i| clustering.labels[i] == clustering.labels[i % 3]));
+        let first = blobs.slice(s![0..;3, ..]).mean_axis(ndarray::Axis(0)).unwrap();
+        assert_eq!(clustering.centroids.row(clustering.labels[0]), first);
+
+        // Lloyd and Elkan take the same steps from the same seeding
+        let banknotes = file_reading::read_csv_to_array("src/datasets/data_banknote_authentication.csv", false).unwrap();
+        let data = banknotes.slice(s![.., ..4]).to_owned();
+        for &(k, inertia) in [(2, 44049.4429), (3, 29365.7858)].iter()
+        {
+            let lloyd = KMeans::new(k, 7).fit(&data).unwrap();
+            let elkan = KMeans::new(k, 7).with_algorithm(KMeansAlgorithm::Elkan).fit(&data).unwrap();
+            assert_eq!(half_away_from_zero(lloyd.inertia, 4), inertia);
+            assert_eq!(lloyd.labels, elkan.labels);
+            assert_eq!(lloyd.iterations, elkan.iterations);
+            assert_approx_eq::assert_approx_eq!(lloyd.inertia, elkan.inertia, 1e-6);
+        }
+        let restarted = KMeans::new(4, 7).with_num_initializations(5).fit(&data).unwrap();
+        assert!(restarted.inertia <= KMeans::new(4, 7).fit(&data).unwrap().inertia);
+
+        assert!(KMeans::new(0, 1).fit(&blobs).is_err());
+        assert!(KMeans::new(61, 1).fit(&blobs).is_err());
+        assert!(KMeans::new(2, 1).fit(&Array2::ones((5, 2))).is_err());
+        assert!(KMeans::new(3, 7).with_max_iterations(2).fit(&data).is_err());
+    }
+
+    #[test]
+    fn test_mini_batch_kmeans()
+    {
+        let blobs = get_blobs();
+        let clustering = MiniBatchKMeans::new(3, 10, 1).fit(&blobs).unwrap();
+        assert!((0..60).all(|i| clustering.labels[i] == clustering.labels[i % 3]));
+        assert!(clustering.iterations < 100);
+
+        let banknotes = file_reading::read_csv_to_array("src/datasets/data_banknote_authentication.csv", false).unwrap();
+        let data = banknotes.slice(s![.., ..4]).to_owned();
+        let clustering = MiniBatchKMeans::new(2, 100, 7).with_tolerance(0.0).fit(&data).unwrap();
+        assert_eq!(clustering.iterations, 100);
+        assert!(clustering.inertia < 1.05 * 44049.4429);
+        assert!(MiniBatchKMeans::new(2, 0, 7).fit(&data).is_err());
+    }
+
+    #[test]
+    fn test_kmedoids_and_silhouette()
+    {
+        let fish = file_reading::read_csv_to_array("src/datasets/Fish.csv", false).unwrap();
+        let distances = clustering::get_distance_matrix(&fish.slice(s![..40, 1..4]).to_owned(), DistanceMetric::Euclidean).unwrap();
+        // The same medoids as an exhaustive search over all triples
+        let clustering = KMedoids::new(3).fit(&distances).unwrap();
+        let mut medoids = clustering.medoids.to_vec();
+        medoids.sort_unstable();
+        assert_eq!(medoids, vec![8, 26, 37]);
+        assert_eq!(half_away_from_zero(clustering.total_distance, 4), 121.53);
+
+        let widths = clustering::get_silhouette_widths(&distances, &clustering.labels).unwrap();
+        assert_eq!(widths.slice(s![..5]).iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>(), vec![0.4064, 0.5738, 0.5734, 0.6991, 0.708]);
+        assert_eq!(half_away_from_zero(clustering::get_silhouette_score(&distances, &clustering.labels).unwrap(), 4), 0.569);
+
+        assert!(clustering::get_silhouette_score(&distances, &Array1::zeros(40)).is_err());
+        assert!(KMedoids::new(41).fit(&distances).is_err());
+        assert!(KMedoids::new(2).fit(&Array2::ones((3, 3))).is_err());
+    }
+
+    #[test]
+    fn test_choosing_number_of_clusters()
+    {
+        let blobs = get_blobs();
+        let inertias = clustering::get_elbow_curve(&blobs, 8, 1).unwrap();
+        assert!(inertias.windows(2).into_iter().all(|pair| pair[1] <= pair[0]));
+        assert_eq!(clustering::get_elbow(&inertias).unwrap(), 3);
+
+        let distances = clustering::get_distance_matrix(&blobs, DistanceMetric::Euclidean).unwrap();
+        let scores: Vec<f64> = (2..7).map(|k| clustering::get_silhouette_score(&distances, &KMeans::new(k, 1).with_num_initializations(5).fit(&blobs).unwrap().labels).unwrap()).collect();
+        let best = scores.iter().enumerate().fold(0, |best, (i, &score)| if score > scores[best] { i } else { best });
+        assert_eq!(best + 2, 3);
+        assert!(clustering::get_elbow(&arr1(&[2.0, 1.0])).is_err());
+    }
+
+    #[test]
+    fn test_distance_metrics()
+    {
+        let points = ndarray::arr2(&[[3.0, 0.0], [0.0, 4.0], [3.0, 4.0]]);
+        let round_row = |distances: &Array2<f64>| distances.row(0).iter().map(|&x| half_away_from_zero(x, 4)).collect::<Vec<f64>>();
+        assert_eq!(round_row(&clustering::get_distance_matrix(&points, DistanceMetric::Euclidean).unwrap()), vec![0.0, 5.0, 4.0]);
+        assert_eq!(round_row(&clustering::get_distance_matrix(&points, DistanceMetric::Manhattan).unwrap()), vec![0.0, 7.0, 4.0]);
+        assert_eq!(round_row(&clustering::get_distance_matrix(&points, DistanceMetric::Cosine).unwrap()), vec![0.0, 1.0, 0.4]);
+
+        let fish = file_reading::read_csv_to_array("src/datasets/Fish.csv", false).unwrap();
+        let distances = clustering::get_distance_matrix(&fish.slice(s![..40, 1..4]).to_owned(), DistanceMetric::Mahalanobis).unwrap();
+        assert_eq!(half_away_from_zero(distances[[0, 1]], 4), 0.3758);
+        assert_eq!(half_away_from_zero(distances[[39, 0]], 4), 3.5982);
+
+        assert!(clustering::get_distance_matrix(&ndarray::arr2(&[[1.0, 0.0], [0.0, 0.0]]), DistanceMetric::Cosine).is_err());
+        assert!(clustering::get_distance_matrix(&ndarray::arr2(&[[1.0, 2.0], [2.0, 4.0], [3.0, 6.0]]), DistanceMetric::Mahalanobis).is_err());
+    }
+
+    #[test]
+    fn test_agglomerative_clustering()
+    {
+        let fish = file_reading::read_csv_to_array("src/datasets/Fish.csv", false).unwrap();
+        let data = fish.slice(s![..40, 1..4]).to_owned();
+        let expected = [(Linkage::Single, 10.5432, 35, 1), (Linkage::Complete, 47.6625, 16, 19), (Linkage::Average, 26.9987, 30, 5), (Linkage::Ward, 79.8413, 16, 19)];
+        for &(linkage, height, first, second) in expected.iter()
+        {
+            let dendrogram = AgglomerativeClustering::new(linkage).fit(&data).unwrap();
+            let last = dendrogram.linkage_matrix.row(38);
+            assert_eq!(half_away_from_zero(last[2], 4), height);
+            assert_eq!(last[3], 40.0);
+            assert!(last[0] < last[1] && last[1] == 77.0);
+            let labels = dendrogram.cut_into_clusters(3).unwrap();
+            let expected_labels: Vec<usize> = (0..40).map(|i| if i < first { 0 } else if i < first + second { 1 } else { 2 }).collect();
+            assert_eq!(labels.to_vec(), expected_labels);
+        }
+
+        // Ward joins the last three clusters at 23.2854, 44.7009 and 79.8413
+        let dendrogram = AgglomerativeClustering::new(Linkage::Ward).fit(&data).unwrap();
+        assert_eq!(dendrogram.cut_at_height(30.0), dendrogram.cut_into_clusters(3).unwrap());
+        assert_eq!(dendrogram.cut_at_height(80.0), Array1::<usize>::zeros(40));
+        assert_eq!(dendrogram.cut_at_height(-1.0).to_vec(), (0..40).collect::<Vec<usize>>());
+
+        let dendrogram = AgglomerativeClustering::new(Linkage::Complete).with_metric(DistanceMetric::Mahalanobis).fit(&data).unwrap();
+        assert_eq!(half_away_from_zero(dendrogram.linkage_matrix[[38, 2]], 4), 5.2902);
+        assert!(AgglomerativeClustering::new(Linkage::Ward).with_metric(DistanceMetric::Manhattan).fit(&data).is_err());
+        assert!(dendrogram.cut_into_clusters(41).is_err());
+    }
+
+    #[test]
+    fn test_dbscan()
+    {
+        let outliers = ndarray::arr2(&[[3.0, 3.0], [-4.0, 8.0], [9.0, 9.0]]);
+        let data = ndarray::concatenate![ndarray::Axis(0), get_blobs(), outliers];
+        let clustering = Dbscan::new(0.5, 4).fit(&data).unwrap();
+        assert_eq!(clustering.num_clusters, 3);
+        assert!((0..60).all(|i| clustering.labels[i] == Some(i % 3)));
+        assert_eq!(clustering.labels.slice(s![60..]).to_vec(), vec![None, None, None]);
+
+        // Varying density splits the banknotes into many small clusters
+        let banknotes = file_reading::read_csv_to_array("src/datasets/data_banknote_authentication.csv", false).unwrap();
+        let data = banknotes.slice(s![.., ..4]).to_owned();
+        let clustering = Dbscan::new(1.0, 5).fit(&data).unwrap();
+        assert_eq!(clustering.num_clusters, 51);
+        assert_eq!(clustering.labels.iter().filter(|label| label.is_none()).count(), 102);
+        let clustering = Dbscan::new(1.0, 5).with_metric(DistanceMetric::Mahalanobis).fit(&data).unwrap();
+        assert_eq!(clustering.num_clusters, 1);
+        assert_eq!(clustering.labels.iter().filter(|label| label.is_none()).count(), 1);
+        assert!(Dbscan::new(0.0, 5).fit(&data).is_err());
+    }
+
+    #[test]
+    fn test_hdbscan()
+    {
+        let outliers = ndarray::arr2(&[[3.0, 3.0], [-4.0, 8.0], [9.0, 9.0]]);
+        let data = ndarray::concatenate![ndarray::Axis(0), get_blobs(), outliers];
+        let clustering = Hdbscan::new(5).fit(&data).unwrap();
+        assert_eq!(clustering.num_clusters, 3);
+        assert!((0..60).all(|i| clustering.labels[i] == Some(i % 3)));
+        // The centre point joins a group before the groups join each other
+        assert_eq!(clustering.labels.slice(s![60..]).to_vec(), vec![Some(2), None, None]);
+
+        let banknotes = file_reading::read_csv_to_array("src/datasets/data_banknote_authentication.csv", false).unwrap();
+        let data = banknotes.slice(s![.., ..4]).to_owned();
+        let cluster_sizes = |clustering: &simple_statistics::density_clustering::DensityClustering|
+        {
+            let mut sizes = vec![0; clustering.num_clusters];
+            clustering.labels.iter().flatten().for_each(|&label| sizes[label] += 1);
+            sizes
+        };
+        let clustering = Hdbscan::new(15).fit(&data).unwrap();
+        assert_eq!(cluster_sizes(&clustering), vec![26, 1335]);
+        assert_eq!(clustering.labels.iter().filter(|label| label.is_none()).count(), 11);
+        assert!(Hdbscan::new(1).fit(&data).is_err());
+        assert!(Hdbscan::new(5).with_min_points(2000).fit(&data).is_err());
+    }
+
     // Rounding utilities
     fn round_vector_elements(x: &Array1<f64>, decimal_precision: u32) -> Vec<f64> 
     {